@@ -0,0 +1,236 @@
+use crate::types::LogMessage;
+use crate::ui::app::{App, ContainerKind, LogEntry, PodInfo, PodKey};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+/// Upper bound on a checkpoint file; the oldest lines are left out beyond it
+pub const MAX_CHECKPOINT_BYTES: usize = 64 * 1024 * 1024;
+
+/// One JSON line of a checkpoint: the pods first, then the buffered lines
+/// oldest first
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Record {
+    Pod {
+        cluster: String,
+        namespace: String,
+        pod: String,
+        container: String,
+        phase: String,
+        enabled: bool,
+    },
+    Line {
+        cluster: String,
+        namespace: String,
+        pod: String,
+        container: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        line: String,
+    },
+    Note {
+        timestamp: chrono::DateTime<chrono::Utc>,
+        text: String,
+    },
+}
+
+/// Snapshot the buffer and pod list to `path` as JSON Lines. The file is
+/// written next to its destination and renamed into place, so a crash never
+/// leaves a half-written checkpoint behind.
+pub fn write(path: &Path, app: &App) -> anyhow::Result<()> {
+    Snapshot::of(app).write(path)
+}
+
+/// The pods and lines of a checkpoint, taken from the app so the file can be
+/// written off the UI loop (e.g. with `spawn_blocking`)
+#[derive(Debug)]
+pub struct Snapshot {
+    pods: Vec<Record>,
+    /// Newest first, up to about the size bound
+    lines: Vec<Record>,
+}
+
+impl Snapshot {
+    pub fn of(app: &App) -> Self {
+        let pods = app
+            .pods
+            .iter()
+            .map(|info| Record::Pod {
+                cluster: info.key.cluster.clone(),
+                namespace: info.key.namespace.clone(),
+                pod: info.key.pod_name.clone(),
+                container: info.key.container_name.clone(),
+                phase: info.phase.clone(),
+                enabled: app.pod_states.get(&info.key).is_none_or(|s| s.enabled),
+            })
+            .collect();
+
+        // Each line takes at least its text in the file: stop copying past the
+        // size bound, which `write` enforces on the JSON
+        let mut copied = 0;
+        let mut lines = Vec::new();
+        // Elided stack frames are saved as the lines they were folded from
+        'lines: for msg in app.log_buffer.iter().rev() {
+            if msg.is_note() {
+                copied += msg.line.len();
+                lines.push(Record::Note {
+                    timestamp: msg.timestamp,
+                    text: msg.line.clone(),
+                });
+                continue;
+            }
+            let texts: Vec<&String> = if msg.elided.is_empty() {
+                vec![&msg.line]
+            } else {
+                msg.elided.iter().rev().collect()
+            };
+            for text in texts {
+                if copied > MAX_CHECKPOINT_BYTES {
+                    break 'lines;
+                }
+                copied += text.len();
+                lines.push(Record::Line {
+                    cluster: msg.cluster.clone(),
+                    namespace: msg.namespace.clone(),
+                    pod: msg.pod_name.clone(),
+                    container: msg.container_name.clone(),
+                    timestamp: msg.timestamp,
+                    line: text.clone(),
+                });
+            }
+        }
+        Self { pods, lines }
+    }
+
+    /// Write the snapshot to `path`; see [`write`]
+    pub fn write(self, path: &Path) -> anyhow::Result<()> {
+        let pods = self
+            .pods
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Newest lines first until the size bound is reached
+        let mut budget =
+            MAX_CHECKPOINT_BYTES.saturating_sub(pods.iter().map(|p| p.len() + 1).sum());
+        let mut lines = Vec::new();
+        for record in &self.lines {
+            let json = serde_json::to_string(record)?;
+            if json.len() + 1 > budget {
+                break;
+            }
+            budget -= json.len() + 1;
+            lines.push(json);
+        }
+
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp = std::path::PathBuf::from(tmp_name);
+        let mut file = std::io::BufWriter::new(
+            std::fs::File::create(&tmp)
+                .with_context(|| format!("Cannot create {}", tmp.display()))?,
+        );
+        for json in pods.iter().chain(lines.iter().rev()) {
+            writeln!(file, "{}", json)?;
+        }
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Cannot move checkpoint into place at {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// A checkpoint read back from disk
+#[derive(Debug)]
+pub struct Checkpoint {
+    records: Vec<Record>,
+}
+
+impl Checkpoint {
+    /// Read a checkpoint written by `write`. A missing file is not an error:
+    /// nothing was captured yet.
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Cannot read {}", path.display())),
+        };
+        let records = contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!("Invalid checkpoint {} at line {}", path.display(), i + 1)
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Some(Self { records }))
+    }
+
+    /// Put the pods and lines back into the app. Returns the number of lines
+    /// restored.
+    pub fn restore(self, app: &mut App) -> usize {
+        let mut restored = 0;
+        for record in self.records {
+            match record {
+                Record::Pod {
+                    cluster,
+                    namespace,
+                    pod,
+                    container,
+                    phase,
+                    enabled,
+                } => {
+                    let key = PodKey {
+                        cluster,
+                        namespace,
+                        pod_name: pod,
+                        container_name: container,
+                    };
+                    app.add_pod(PodInfo {
+                        key: key.clone(),
+                        phase,
+                        created: None,
+                        restarts: 0,
+                        image: None,
+                        skipped: false,
+                        workload: None,
+                        qos_class: None,
+                        priority: None,
+                        node: None,
+                        zone: None,
+                        resources: None,
+                        kind: ContainerKind::Regular,
+                    });
+                    if let Some(state) = app.pod_states.get_mut(&key) {
+                        state.enabled = enabled;
+                    }
+                }
+                Record::Line {
+                    cluster,
+                    namespace,
+                    pod,
+                    container,
+                    timestamp,
+                    line,
+                } => {
+                    app.add_log(LogMessage {
+                        cluster,
+                        namespace,
+                        pod_name: pod,
+                        container_name: container,
+                        line,
+                        timestamp,
+                    });
+                    restored += 1;
+                }
+                Record::Note { timestamp, text } => {
+                    app.add_note(LogEntry::note(text, timestamp));
+                    restored += 1;
+                }
+            }
+        }
+        restored
+    }
+}
@@ -1,9 +1,25 @@
+use crate::exit::EXIT_CODES_HELP;
+use crate::types::LogLevel;
+use crate::utils::{
+    AnnotationFilter, AutoScrollResume, CrdSelector, LineTemplate, PrefixFormat, Redaction,
+    parse_annotation_filter, parse_auto_scroll_resume, parse_crd_selector, parse_duration,
+    parse_frame_pattern, parse_level, parse_nonzero_duration, parse_prefix_format, parse_qos_class,
+    parse_redaction, parse_server_url, parse_template, parse_trace_field,
+};
 use clap::Parser;
+use regex::Regex;
 
 #[derive(Parser)]
 #[command(name = "kubectl-tail")]
 #[command(about = "Tail logs from Kubernetes pods with continuous discovery")]
+#[command(after_help = EXIT_CODES_HELP)]
+// A later value replaces an earlier one, so flags given on the command line
+// win over the same flags from a --profile
+#[command(args_override_self = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Subcommand>,
+
     /// Resources to tail logs from (e.g., my-pod, deployment/my-deployment)
     pub resources: Vec<String>,
 
@@ -11,22 +27,66 @@ pub struct Cli {
     #[arg(short = 'l', long)]
     pub selector: Option<String>,
 
-    /// Namespace
-    #[arg(short = 'n', long)]
-    pub namespace: Option<String>,
+    /// Where a custom resource keeps its pod selector, as
+    /// <group/version/kind>=<path> (e.g.
+    /// argoproj.io/v1alpha1/Rollout=.spec.selector), so `rollout/<name>` can be
+    /// tailed (repeatable)
+    #[arg(long, value_name = "GVK=PATH", value_parser = parse_crd_selector)]
+    pub crd_selector_path: Vec<CrdSelector>,
+
+    /// Only tail pods with this annotation, as key=value or just key to require
+    /// its presence (repeatable, all must match)
+    #[arg(long, value_name = "KEY[=VALUE]", value_parser = parse_annotation_filter)]
+    pub annotation: Vec<AnnotationFilter>,
+
+    /// Namespace (comma-separated list to watch several namespaces)
+    #[arg(short = 'n', long, value_delimiter = ',')]
+    pub namespace: Vec<String>,
 
-    /// Container name (if multi-container pod)
+    /// Watch pods in every namespace. Only with a label selector, --annotation,
+    /// --kinds or `all`: pod and workload names are ambiguous across namespaces
+    #[arg(short = 'A', long, conflicts_with = "namespace")]
+    pub all_namespaces: bool,
+
+    /// Container name (if multi-container pod). Anything but a plain name is a
+    /// regex matched against whole names, tailing every matching container
+    /// (e.g. `app|migrations`); with a leading `~` the regex may match anywhere
+    /// in the name (e.g. `~api$`). Pods without a matching container are skipped
     #[arg(short = 'c', long)]
     pub container: Option<String>,
 
-    /// Kubernetes context (single value - for multi-cluster use context/namespace/kind/name format)
+    /// Never tail containers whose name matches this glob (repeatable, e.g.
+    /// `--exclude-container '*-proxy'`); they are left out of the sidebar too.
+    /// Takes precedence over --container
+    #[arg(long, value_name = "GLOB")]
+    pub exclude_container: Vec<String>,
+
+    /// Fail at startup if a pod named on the command line (e.g. `pod/web-0`)
+    /// doesn't exist, instead of waiting for it to appear. Selectors and
+    /// workloads still match pods created later
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Kubernetes context (repeat to fan resources and the selector out across
+    /// clusters; or use the context/namespace/kind/name format). Globs such as
+    /// `prod-*` expand to every matching kubeconfig context
     #[arg(long = "context")]
-    pub context: Option<String>,
+    pub context: Vec<String>,
 
     /// Number of lines to show from the end of the logs on startup
     #[arg(long)]
     pub tail: Option<i64>,
 
+    /// Only show lines newer than this on startup, e.g. 15m, 2h or 90s; takes
+    /// precedence over --tail
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub since: Option<std::time::Duration>,
+
+    /// Show the log of each container's previous, terminated instance (e.g.
+    /// the one that just crashed) instead of following the running one
+    #[arg(short = 'p', long)]
+    pub previous: bool,
+
     /// Enable verbose output
     #[arg(short, long)]
     pub verbose: bool,
@@ -35,6 +95,113 @@ pub struct Cli {
     #[arg(short = 'g', long)]
     pub grep: Option<String>,
 
+    /// Drop lines matching this regex, e.g. health checks; applied after --grep
+    #[arg(long, value_name = "REGEX")]
+    pub exclude: Option<String>,
+
+    /// Only show lines at this level or above (trace, debug, info, warn, error,
+    /// fatal). Lines without a recognizable level are hidden. In the TUI, `e`
+    /// turns the filter off and on again.
+    #[arg(long, value_parser = parse_level)]
+    pub level: Option<LogLevel>,
+
+    /// Ring the terminal bell (and flash the status bar in TUI mode) when a line
+    /// matches this regex, at most once every 5 seconds
+    #[arg(long)]
+    pub notify: Option<String>,
+
+    /// Where lines carry their trace ID, for following one request with T: a
+    /// field name (matches `name=ID`, `name: ID` or `"name":"ID"`) or a regex
+    /// whose first capture group is the ID
+    #[arg(long, value_name = "FIELD|REGEX", default_value = "trace_id", value_parser = parse_trace_field)]
+    pub trace_field: Regex,
+
+    /// Fold runs of consecutive stack frames matching this regex (e.g. vendor
+    /// or framework paths) into a `... N frames elided ...` line; Enter on it
+    /// shows the full trace (TUI mode)
+    #[arg(long, value_name = "REGEX", value_parser = parse_frame_pattern)]
+    pub elide_frames: Option<Regex>,
+
+    /// Pad or shorten the line prefix to a fixed width so log messages line up
+    /// in a column (stdout mode)
+    #[arg(long)]
+    pub compact: bool,
+
+    /// Prefix width for --compact (default: the longest prefix seen so far, up to 48)
+    #[arg(long, requires = "compact")]
+    pub compact_width: Option<usize>,
+
+    /// Prefix each line with a per-container sequence number (1, 2, 3, …; stdout
+    /// mode), so downstream consumers can detect lost lines from gaps
+    #[arg(long)]
+    pub seq: bool,
+
+    /// How often buffered stdout output is flushed (e.g. 50ms); 0 flushes every
+    /// line. Larger values trade a little latency for throughput when piping
+    #[arg(long, default_value = "50ms", value_parser = parse_duration)]
+    pub flush_interval: std::time::Duration,
+
+    /// Print each line in this format instead of `[prefix] message` (stdout mode).
+    /// Fields: {{.timestamp}}, {{.cluster}}, {{.namespace}}, {{.pod}},
+    /// {{.container}}, {{.message}}
+    #[arg(long, value_parser = parse_template, conflicts_with = "compact")]
+    pub template: Option<LineTemplate>,
+
+    /// Format of the line prefix instead of `[cluster.namespace/pod/container]`,
+    /// in stdout mode and the TUI, e.g. `{namespace}/{pod}>`. Placeholders:
+    /// {cluster}, {namespace}, {pod}, {container}, {timestamp}
+    #[arg(long, value_parser = parse_prefix_format, conflicts_with = "template")]
+    pub prefix_format: Option<PrefixFormat>,
+
+    /// Show structured lines by their fields: with `json`, JSON object lines are
+    /// shown as `time LEVEL message key=value …` with the level colored, in the
+    /// TUI and stdout mode. Search and filters still match the raw line; other
+    /// lines are shown as is
+    #[arg(long, value_enum)]
+    pub parse: Option<ParseFormat>,
+
+    /// Key of the message in --parse json lines (default: msg or message)
+    #[arg(long, value_name = "KEY", requires = "parse")]
+    pub json_message_key: Option<String>,
+
+    /// Output format of stdout mode: colored `[prefix] message` text, or one
+    /// JSON object per line (cluster, namespace, pod_name, container_name,
+    /// timestamp, line) for jq or a log shipper. JSON implies --no-tui
+    #[arg(
+        short = 'o',
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        conflicts_with_all = ["template", "compact"]
+    )]
+    pub output: OutputFormat,
+
+    /// Periodically snapshot the TUI buffer and pod list to this file (JSON
+    /// Lines), so a capture survives a crash
+    #[arg(long)]
+    pub checkpoint: Option<std::path::PathBuf>,
+
+    /// How often to write the --checkpoint (e.g. 30s, 5m)
+    #[arg(long, default_value = "30s", value_parser = parse_duration, requires = "checkpoint")]
+    pub checkpoint_interval: std::time::Duration,
+
+    /// Reload the --checkpoint file into the buffer on startup
+    #[arg(long, requires = "checkpoint")]
+    pub resume: bool,
+
+    /// Shell command that `|` in the TUI pipes the marked lines to (e.g.
+    /// `grep -c ERROR` or an upload script). It runs without a terminal; output
+    /// longer than one line is saved to a file
+    #[arg(long)]
+    pub pipe_cmd: Option<String>,
+
+    /// Shell command that `o` in the TUI runs for the selected pod, with
+    /// {context}, {namespace}, {pod} and {container} filled in (e.g. `kubectl
+    /// --context {context} -n {namespace} exec -it {pod} -c {container} -- sh`).
+    /// The TUI is suspended until it exits
+    #[arg(long, value_name = "COMMAND")]
+    pub open_cmd: Option<String>,
+
     /// Disable TUI mode and use stdout (backward compatibility)
     #[arg(long)]
     pub no_tui: bool,
@@ -42,4 +209,210 @@ pub struct Cli {
     /// Maximum buffer size for log messages in TUI mode
     #[arg(long, default_value = "10000")]
     pub buffer_size: usize,
+
+    /// Truncate displayed log lines longer than this many characters (Enter shows the full line)
+    #[arg(long)]
+    pub max_line_width: Option<usize>,
+
+    /// Don't color lines by their detected level in the TUI
+    #[arg(long)]
+    pub no_level_colors: bool,
+
+    /// Wrap log lines at this column instead of the pane edge, for line breaks
+    /// that don't depend on the terminal size (`w` toggles)
+    #[arg(long, value_name = "COLUMNS", value_parser = clap::value_parser!(u16).range(1..))]
+    pub wrap_width: Option<u16>,
+
+    /// Truncate lines longer than this many bytes as they are received, before
+    /// they are buffered or printed, to bound memory when a container writes
+    /// huge lines. Truncated lines end with `… [N bytes truncated]`
+    #[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_line_bytes: Option<u64>,
+
+    /// Ask the API server for gzip-compressed log streams and decompress them
+    /// here, to save bandwidth on slow links. Servers that don't compress send
+    /// the stream as is
+    #[arg(long)]
+    pub gzip: bool,
+
+    /// Redact matches of a regex at ingestion, as <REGEX>=<REPLACEMENT> (repeatable).
+    /// The original text is never kept in the buffer or written to any output
+    #[arg(long, value_name = "REGEX=REPLACEMENT", value_parser = parse_redaction)]
+    pub redact: Vec<Redaction>,
+
+    /// Discover pods by periodically listing them instead of watching (for RBAC
+    /// setups or proxies that forbid the watch verb)
+    #[arg(long)]
+    pub poll: bool,
+
+    /// How often to list pods in --poll mode (e.g. 10s, 1m)
+    #[arg(long, default_value = "10s", value_parser = parse_nonzero_duration)]
+    pub poll_interval: std::time::Duration,
+
+    /// Re-resolve the selectors of resources (e.g. svc/x, deploy/x) periodically
+    /// and follow edits: pods that start matching are tailed, pods that stop
+    /// matching are dropped
+    #[arg(long)]
+    pub watch_selector_change: bool,
+
+    /// How often to re-resolve selectors with --watch-selector-change (e.g. 30s, 1m)
+    #[arg(long, default_value = "30s", value_parser = parse_nonzero_duration, requires = "watch_selector_change")]
+    pub selector_refresh: std::time::Duration,
+
+    /// Keep reading a deleted pod's logs for this long to catch its last lines
+    /// (e.g. 2s, 0 to stop immediately)
+    #[arg(long, default_value = "2s", value_parser = parse_duration)]
+    pub delete_grace: std::time::Duration,
+
+    /// Keep deleted pods in the sidebar (greyed out) for this long, with the
+    /// last --tombstone-lines lines of each container; Enter on one shows them
+    /// (TUI mode, e.g. 10m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub tombstones: Option<std::time::Duration>,
+
+    /// How many final lines of each container --tombstones keeps
+    #[arg(long, default_value = "200", requires = "tombstones")]
+    pub tombstone_lines: usize,
+
+    /// Show each pod's primary container image tag in the sidebar
+    #[arg(long)]
+    pub show_image: bool,
+
+    /// Show each pod's QoS class and priority in the sidebar
+    #[arg(long)]
+    pub show_qos: bool,
+
+    /// Show a sparkline of each container's lines per second over the last
+    /// 20 seconds in the sidebar
+    #[arg(long)]
+    pub sparklines: bool,
+
+    /// Show JSON access-log lines (nginx ingress, Envoy, Traefik, Caddy,
+    /// Kubernetes audit, or formats from the config file) as aligned method,
+    /// status, duration and path columns (TUI mode)
+    #[arg(long)]
+    pub access_logs: bool,
+
+    /// Only tail pods of these QoS classes (Guaranteed, Burstable, BestEffort;
+    /// comma-separated)
+    #[arg(long, value_delimiter = ',', value_parser = parse_qos_class)]
+    pub qos: Vec<String>,
+
+    /// Exit once every tailed pod has completed, with a non-zero exit code if
+    /// any container failed (useful for Jobs in CI)
+    #[arg(long)]
+    pub wait: bool,
+
+    /// In stdout mode, exit once every stream has ended and no watcher can
+    /// find more pods (e.g. all named pods were deleted), instead of waiting
+    /// for Ctrl-C
+    #[arg(long)]
+    pub exit_when_done: bool,
+
+    /// End the session cleanly after this long (e.g. 5m), as if interrupted
+    /// with Ctrl-C: output is flushed and summaries are written
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub duration: Option<std::time::Duration>,
+
+    /// Watch Warning events for the tailed pods and show a badge with their
+    /// count and latest reason on the pod's sidebar row (TUI mode)
+    #[arg(long)]
+    pub watch_events: bool,
+
+    /// Only tail the N most recently created pods of each selector, switching to
+    /// newer pods as they appear
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub newest: Option<u32>,
+
+    /// Which containers of a pod to tail: only those currently running (others
+    /// are picked up once they start), or all of them
+    #[arg(long, value_enum, default_value_t = ContainerState::All)]
+    pub container_state: ContainerState,
+
+    /// Also tail init containers, shown as `(init)` in the sidebar. Those that
+    /// already completed are read once for their log
+    #[arg(long)]
+    pub include_init: bool,
+
+    /// Also tail ephemeral containers (e.g. from `kubectl debug`), shown as
+    /// `(ephemeral)` in the sidebar, including ones added while tailing
+    #[arg(long)]
+    pub include_ephemeral: bool,
+
+    /// Only tail these ordinals of the given StatefulSets (e.g. 0,1), like
+    /// `statefulset/db[0-1]`
+    #[arg(long, value_delimiter = ',')]
+    pub ordinals: Vec<u32>,
+
+    /// Only tail pods owned by these workload kinds (e.g. deployment,statefulset;
+    /// `pod` for bare pods). Without resources, discovers every pod in the
+    /// namespace. The sidebar groups pods by workload.
+    #[arg(long, value_delimiter = ',')]
+    pub kinds: Vec<String>,
+
+    /// When auto-scroll re-engages after scrolling up: `sticky` (on reaching the
+    /// bottom), `timeout:<DURATION>` (also after that long without scrolling, e.g.
+    /// timeout:10s) or `never` (only on End)
+    #[arg(long, default_value = "sticky", value_parser = parse_auto_scroll_resume)]
+    pub auto_scroll_resume: AutoScrollResume,
+
+    /// Abort instead of connecting to a cluster whose kubeconfig sets
+    /// insecure-skip-tls-verify
+    #[arg(long)]
+    pub refuse_insecure: bool,
+
+    /// API server URL to connect to instead of the kubeconfig cluster's,
+    /// keeping the context's credentials (e.g. http://127.0.0.1:8001 for
+    /// `kubectl proxy`). Only with a single context
+    #[arg(long, value_name = "URL", value_parser = parse_server_url)]
+    pub server: Option<String>,
+
+    /// Also write every received line, as `[prefix] line` without colors, to
+    /// file descriptor 3 (e.g. `3>capture.log`) or, as --tee-stdout=PATH, to a
+    /// file, while the TUI runs (TUI mode only)
+    #[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+    pub tee_stdout: Option<Option<std::path::PathBuf>>,
+
+    /// Config file to read instead of ~/.config/kubectl-tail/config.yaml
+    #[arg(long)]
+    pub config: Option<std::path::PathBuf>,
+
+    /// Start from a named profile in the config file; its contexts,
+    /// namespaces and resources are combined with those given here, and any
+    /// other flag given here overrides the profile's
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Only show lines from contexts tagged with this environment in the
+    /// config file's `environments` (in the TUI, `:env` changes it)
+    #[arg(long, value_name = "ENV")]
+    pub env: Option<String>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subcommand {
+    /// Check that each kubeconfig context (or each --context) is reachable
+    /// and allows listing and watching pods and reading their logs in its
+    /// namespace (or the first --namespace), then exit
+    Doctor,
+}
+
+/// Line format for --output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Structured line format for --parse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ParseFormat {
+    Json,
+}
+
+/// Container filter for --container-state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContainerState {
+    Running,
+    All,
 }
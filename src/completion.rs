@@ -0,0 +1,117 @@
+use k8s_openapi::api::core::v1::Pod;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How a container ended
+#[derive(Debug, Clone)]
+pub struct ContainerExit {
+    pub container: String,
+    pub exit_code: i32,
+    pub reason: Option<String>,
+}
+
+/// Tracks every tailed pod until it reaches a terminal phase (`--wait`).
+/// Pods are identified as `cluster/namespace/pod`.
+#[derive(Default)]
+pub struct CompletionTracker {
+    pods: Mutex<HashMap<String, Option<Vec<ContainerExit>>>>,
+    done: Notify,
+}
+
+impl CompletionTracker {
+    /// Start waiting for a pod to finish
+    pub fn register(&self, pod_id: &str) {
+        self.pods
+            .lock()
+            .unwrap()
+            .entry(pod_id.to_string())
+            .or_insert(None);
+    }
+
+    pub fn is_finished(&self, pod_id: &str) -> bool {
+        matches!(self.pods.lock().unwrap().get(pod_id), Some(Some(_)))
+    }
+
+    /// True when at least one pod was registered and all of them have finished
+    pub fn all_finished(&self) -> bool {
+        let pods = self.pods.lock().unwrap();
+        !pods.is_empty() && pods.values().all(|p| p.is_some())
+    }
+
+    /// Record the final container states of a registered pod. Wakes up `wait`
+    /// once every registered pod has finished.
+    pub fn finish(&self, pod_id: &str, exits: Vec<ContainerExit>) {
+        let mut pods = self.pods.lock().unwrap();
+        let Some(slot) = pods.get_mut(pod_id) else {
+            return;
+        };
+        if slot.is_none() {
+            *slot = Some(exits);
+        }
+        if pods.values().all(|p| p.is_some()) {
+            self.done.notify_one();
+        }
+    }
+
+    /// Resolve once all registered pods have finished. Pods registered after
+    /// the others finished (e.g. a Job retrying) are waited for as well, as long
+    /// as they show up within `settle`.
+    pub async fn wait(&self, settle: Duration) {
+        loop {
+            self.done.notified().await;
+            tokio::time::sleep(settle).await;
+            if self.all_finished() {
+                return;
+            }
+        }
+    }
+
+    /// Per-container summary lines, and whether every container exited cleanly
+    pub fn summary(&self) -> (Vec<String>, bool) {
+        let pods = self.pods.lock().unwrap();
+        let mut ids: Vec<&String> = pods.keys().collect();
+        ids.sort();
+
+        let mut lines = Vec::new();
+        let mut success = true;
+        for id in ids {
+            for exit in pods[id].iter().flatten() {
+                success &= exit.exit_code == 0;
+                lines.push(format!(
+                    "{}/{}: exit code {}{}",
+                    id,
+                    exit.container,
+                    exit.exit_code,
+                    exit.reason
+                        .as_ref()
+                        .map(|r| format!(" ({})", r))
+                        .unwrap_or_default()
+                ));
+            }
+        }
+        (lines, success)
+    }
+}
+
+/// Exit codes of all terminated containers of a pod
+pub fn container_exits(pod: &Pod) -> Vec<ContainerExit> {
+    pod.status
+        .as_ref()
+        .and_then(|s| s.container_statuses.as_ref())
+        .map(|statuses| {
+            statuses
+                .iter()
+                .filter_map(|cs| {
+                    let terminated = cs.state.as_ref()?.terminated.as_ref()?;
+                    Some(ContainerExit {
+                        container: cs.name.clone(),
+                        exit_code: terminated.exit_code,
+                        reason: terminated.reason.clone(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,328 @@
+use anyhow::Context;
+use ratatui::style::{Color, Modifier, Style};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Settings read from `~/.config/kubectl-tail/config.yaml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Keywords that are always highlighted in the log view
+    pub highlights: Vec<HighlightConfig>,
+    /// Named sets of command-line settings, selected with `--profile`
+    pub profiles: BTreeMap<String, Profile>,
+    /// Environment tags (e.g. prod, staging) and the contexts that belong to them
+    pub environments: BTreeMap<String, Vec<String>>,
+    /// JSON access-log formats rendered as columns with `--access-logs`,
+    /// tried before the built-in ones
+    pub access_logs: Vec<AccessLogSchema>,
+}
+
+/// A saved invocation, e.g. everything needed to tail one incident's services
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub contexts: Vec<String>,
+    pub namespaces: Vec<String>,
+    pub resources: Vec<String>,
+    pub selector: Option<String>,
+    pub container: Option<String>,
+    pub grep: Option<String>,
+    pub level: Option<String>,
+    /// Any other flags, passed through verbatim (e.g. `["--since", "10m"]`)
+    pub args: Vec<String>,
+}
+
+/// A highlight rule as written in the config file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightConfig {
+    pub pattern: String,
+    pub color: String,
+    #[serde(default)]
+    pub bold: bool,
+}
+
+impl Profile {
+    /// The profile as command-line arguments, to be parsed ahead of the real ones
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for context in &self.contexts {
+            args.push(format!("--context={context}"));
+        }
+        for namespace in &self.namespaces {
+            args.push(format!("--namespace={namespace}"));
+        }
+        let options = [
+            ("--selector", &self.selector),
+            ("--container", &self.container),
+            ("--grep", &self.grep),
+            ("--level", &self.level),
+        ];
+        for (flag, value) in options {
+            if let Some(value) = value {
+                args.push(format!("{flag}={value}"));
+            }
+        }
+        args.extend(self.args.iter().cloned());
+        args.extend(self.resources.iter().cloned());
+        args
+    }
+}
+
+/// A compiled highlight rule
+#[derive(Debug, Clone)]
+pub struct HighlightRule {
+    pub regex: Regex,
+    pub style: Style,
+}
+
+/// Where an access-log format keeps its fields. Fields are dotted paths into
+/// the JSON object (e.g. `request.method`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessLogSchema {
+    pub name: String,
+    pub method: String,
+    pub path: String,
+    pub status: String,
+    #[serde(default)]
+    pub duration: Option<String>,
+    /// Unit of a numeric duration: s, ms, us or ns
+    #[serde(default = "default_duration_unit")]
+    pub duration_unit: String,
+}
+
+fn default_duration_unit() -> String {
+    "s".to_string()
+}
+
+/// An access-log line broken into the fields shown as columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessLogEntry {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub duration: Option<String>,
+}
+
+/// Access-log formats recognized without any configuration
+pub fn builtin_access_log_schemas() -> Vec<AccessLogSchema> {
+    let schema =
+        |name: &str, method: &str, path: &str, status: &str, duration: Option<&str>, unit: &str| {
+            AccessLogSchema {
+                name: name.to_string(),
+                method: method.to_string(),
+                path: path.to_string(),
+                status: status.to_string(),
+                duration: duration.map(str::to_string),
+                duration_unit: unit.to_string(),
+            }
+        };
+    vec![
+        schema(
+            "envoy",
+            "method",
+            "path",
+            "response_code",
+            Some("duration"),
+            "ms",
+        ),
+        schema(
+            "nginx-ingress",
+            "request_method",
+            "request_uri",
+            "status",
+            Some("request_time"),
+            "s",
+        ),
+        schema(
+            "traefik",
+            "RequestMethod",
+            "RequestPath",
+            "DownstreamStatus",
+            Some("Duration"),
+            "ns",
+        ),
+        schema(
+            "caddy",
+            "request.method",
+            "request.uri",
+            "status",
+            Some("duration"),
+            "s",
+        ),
+        schema(
+            "kubernetes-audit",
+            "verb",
+            "requestURI",
+            "responseStatus.code",
+            None,
+            "s",
+        ),
+    ]
+}
+
+impl AccessLogSchema {
+    /// The fields of `object`, if it has at least method, path and status
+    pub fn extract(&self, object: &serde_json::Value) -> Option<AccessLogEntry> {
+        let field = |path: &str| path.split('.').try_fold(object, |v, key| v.get(key));
+        let text = |v: &serde_json::Value| match v {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        };
+        let status = field(&self.status).and_then(|v| match v {
+            serde_json::Value::String(s) => s.parse().ok(),
+            v => v.as_u64().and_then(|n| u16::try_from(n).ok()),
+        })?;
+        Some(AccessLogEntry {
+            method: field(&self.method).and_then(text)?.to_uppercase(),
+            path: field(&self.path).and_then(text)?,
+            status,
+            duration: self
+                .duration
+                .as_deref()
+                .and_then(field)
+                .and_then(|v| self.format_duration(v)),
+        })
+    }
+
+    /// A duration as the largest unit that keeps it at least 1, e.g. `12ms`
+    fn format_duration(&self, value: &serde_json::Value) -> Option<String> {
+        let amount = match value {
+            serde_json::Value::Number(n) => n.as_f64()?,
+            // Already formatted, e.g. Go's "1.5ms"
+            serde_json::Value::String(s) => match s.parse() {
+                Ok(n) => n,
+                Err(_) => return Some(s.clone()),
+            },
+            _ => return None,
+        };
+        let seconds = match self.duration_unit.as_str() {
+            "ms" => amount / 1e3,
+            "us" => amount / 1e6,
+            "ns" => amount / 1e9,
+            _ => amount,
+        };
+        Some(if seconds >= 1.0 {
+            format!("{seconds:.2}s")
+        } else if seconds >= 1e-3 {
+            format!("{:.0}ms", seconds * 1e3)
+        } else {
+            format!("{:.0}µs", seconds * 1e6)
+        })
+    }
+}
+
+/// Recognize a JSON access-log line; the first schema that fits wins
+pub fn parse_access_log(line: &str, schemas: &[AccessLogSchema]) -> Option<AccessLogEntry> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    let object: serde_json::Value = serde_json::from_str(line).ok()?;
+    schemas.iter().find_map(|schema| schema.extract(&object))
+}
+
+/// `$XDG_CONFIG_HOME/kubectl-tail/config.yaml`, falling back to `~/.config`
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("kubectl-tail").join("config.yaml"))
+}
+
+impl Config {
+    /// Load the config file. An explicitly given path must exist; a missing
+    /// default config file simply yields the defaults.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let (path, required) = match path {
+            Some(p) => (p.to_path_buf(), true),
+            None => match default_config_path() {
+                Some(p) => (p, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)
+                .with_context(|| format!("Invalid config file {}", path.display())),
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Cannot read config file {}", path.display())),
+        }
+    }
+
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        // An empty file deserializes to unit, not to an empty mapping
+        if contents.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(serde_yaml::from_str(contents)?)
+    }
+
+    /// Look up a profile by name
+    pub fn profile(&self, name: &str) -> anyhow::Result<&Profile> {
+        self.profiles.get(name).ok_or_else(|| {
+            if self.profiles.is_empty() {
+                anyhow::anyhow!("Unknown profile '{name}': the config file defines no profiles")
+            } else {
+                let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                anyhow::anyhow!("Unknown profile '{name}' (available: {})", names.join(", "))
+            }
+        })
+    }
+
+    /// The environment tag of each context. A context may belong to only one
+    /// environment.
+    pub fn context_environments(&self) -> anyhow::Result<HashMap<String, String>> {
+        let mut tags = HashMap::new();
+        for (env, contexts) in &self.environments {
+            for context in contexts {
+                if let Some(other) = tags.insert(context.clone(), env.clone()) {
+                    anyhow::bail!(
+                        "Context '{context}' is listed in both environments '{other}' and '{env}'"
+                    );
+                }
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Access-log formats from the config file, then the built-in ones
+    pub fn access_log_schemas(&self) -> anyhow::Result<Vec<AccessLogSchema>> {
+        for schema in &self.access_logs {
+            if !matches!(schema.duration_unit.as_str(), "s" | "ms" | "us" | "ns") {
+                anyhow::bail!(
+                    "Access log format '{}' has invalid duration_unit '{}' (expected s, ms, us or ns)",
+                    schema.name,
+                    schema.duration_unit
+                );
+            }
+        }
+        let mut schemas = self.access_logs.clone();
+        schemas.extend(builtin_access_log_schemas());
+        Ok(schemas)
+    }
+
+    /// Compile the highlight rules, in file order
+    pub fn highlight_rules(&self) -> anyhow::Result<Vec<HighlightRule>> {
+        self.highlights
+            .iter()
+            .map(|h| {
+                let regex = Regex::new(&h.pattern)
+                    .with_context(|| format!("Invalid highlight pattern '{}'", h.pattern))?;
+                let color: Color = h
+                    .color
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid highlight color '{}'", h.color))?;
+                let mut style = Style::default().fg(color);
+                if h.bold {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                Ok(HighlightRule { regex, style })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,320 @@
+use crate::cli::Cli;
+use crate::exit::{ExitCode, WithExitCode, kube_error_code};
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+};
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{ListParams, PostParams};
+use kube::{Api, Client};
+use std::time::{Duration, Instant};
+
+/// How long one context may take before it is reported as unreachable
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Ok,
+    /// The check could not run because an earlier one failed
+    Skipped,
+    /// Why, and the exit code it calls for
+    Failed(String, ExitCode),
+}
+
+impl Check {
+    fn cell(&self) -> &str {
+        match self {
+            Check::Ok => "ok",
+            Check::Skipped => "-",
+            Check::Failed(..) => "FAIL",
+        }
+    }
+}
+
+/// Results for one context
+#[derive(Debug, Clone)]
+pub struct ContextReport {
+    pub context: String,
+    pub namespace: String,
+    pub client: Check,
+    pub list_pods: Check,
+    pub watch_pods: Check,
+    pub read_logs: Check,
+    /// Round trip of the pod list request
+    pub latency: Option<Duration>,
+}
+
+impl ContextReport {
+    fn new(context: &str) -> Self {
+        Self {
+            context: context.to_string(),
+            namespace: "-".to_string(),
+            client: Check::Skipped,
+            list_pods: Check::Skipped,
+            watch_pods: Check::Skipped,
+            read_logs: Check::Skipped,
+            latency: None,
+        }
+    }
+
+    fn checks(&self) -> [(&'static str, &Check); 4] {
+        [
+            ("client", &self.client),
+            ("list pods", &self.list_pods),
+            ("watch pods", &self.watch_pods),
+            ("read logs", &self.read_logs),
+        ]
+    }
+
+    /// Exit code of the first failed check, None when all passed
+    pub fn failure_code(&self) -> Option<ExitCode> {
+        self.checks()
+            .into_iter()
+            .find_map(|(_, check)| match check {
+                Check::Failed(_, code) => Some(*code),
+                _ => None,
+            })
+    }
+}
+
+/// Check every given context, or every context in the kubeconfig, and print
+/// a table of the results. Fails when any check did: unreachable when no
+/// cluster answered, forbidden when any rejected us.
+pub async fn run(cli: &Cli) -> anyhow::Result<()> {
+    let contexts = if !cli.context.is_empty() {
+        cli.context.clone()
+    } else if cli.server.is_some() {
+        // --server stands in for one cluster: check the current context's
+        let kubeconfig = kube::config::Kubeconfig::read()?;
+        kubeconfig.current_context.into_iter().collect()
+    } else {
+        let kubeconfig = kube::config::Kubeconfig::read()?;
+        kubeconfig.contexts.into_iter().map(|c| c.name).collect()
+    };
+    if cli.server.is_some() && contexts.len() > 1 {
+        anyhow::bail!("--server can only be used with a single context");
+    }
+    if contexts.is_empty() {
+        anyhow::bail!("No contexts found in the kubeconfig");
+    }
+
+    let reports = futures::future::join_all(contexts.iter().map(|context| async {
+        let namespace = cli.namespace.first().cloned();
+        match tokio::time::timeout(
+            CHECK_TIMEOUT,
+            check_context(context, namespace, cli.refuse_insecure, cli.server.clone()),
+        )
+        .await
+        {
+            Ok(report) => report,
+            Err(_) => {
+                let mut report = ContextReport::new(context);
+                report.client = Check::Failed(
+                    format!("no answer within {}s", CHECK_TIMEOUT.as_secs()),
+                    ExitCode::Unreachable,
+                );
+                report
+            }
+        }
+    }))
+    .await;
+
+    for line in format_report(&reports) {
+        println!("{}", line);
+    }
+    let failures: Vec<ExitCode> = reports
+        .iter()
+        .filter_map(ContextReport::failure_code)
+        .collect();
+    if !failures.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} of {} contexts failed the checks",
+            failures.len(),
+            reports.len()
+        ))
+        .exit_code(crate::exit::combined_code(&failures));
+    }
+    Ok(())
+}
+
+async fn check_context(
+    context: &str,
+    namespace: Option<String>,
+    refuse_insecure: bool,
+    server: Option<String>,
+) -> ContextReport {
+    let mut report = ContextReport::new(context);
+    let client = match crate::initialize_clients(
+        vec![context.to_string()],
+        refuse_insecure,
+        server.as_deref(),
+    )
+    .await
+    {
+        Ok(mut clients) => clients.remove(0).1,
+        Err(e) => {
+            report.client = Check::Failed(e.to_string(), crate::exit::code_of(&e));
+            return report;
+        }
+    };
+    report.client = Check::Ok;
+    let namespace = namespace.unwrap_or_else(|| client.default_namespace().to_string());
+    report.namespace = namespace.clone();
+
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+    let started = Instant::now();
+    match pods.list(&ListParams::default().limit(1)).await {
+        Ok(_) => {
+            report.latency = Some(started.elapsed());
+            report.list_pods = Check::Ok;
+        }
+        Err(e) => {
+            report.list_pods = Check::Failed(describe_error(&e), kube_error_code(&e));
+            // Nothing else will get through a dead connection or bad credentials
+            if !matches!(&e, kube::Error::Api(err) if err.code == 403) {
+                return report;
+            }
+        }
+    }
+    report.watch_pods = can_i(&client, &namespace, "watch", None).await;
+    report.read_logs = can_i(&client, &namespace, "get", Some("log")).await;
+    report
+}
+
+/// Ask the API server whether we may `verb` pods (or a pod subresource) in
+/// the namespace, without touching any pod
+async fn can_i(client: &Client, namespace: &str, verb: &str, subresource: Option<&str>) -> Check {
+    let review = SelfSubjectAccessReview {
+        spec: SelfSubjectAccessReviewSpec {
+            resource_attributes: Some(ResourceAttributes {
+                namespace: Some(namespace.to_string()),
+                verb: Some(verb.to_string()),
+                resource: Some("pods".to_string()),
+                subresource: subresource.map(str::to_string),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let what = match subresource {
+        Some(sub) => format!("{} pods/{}", verb, sub),
+        None => format!("{} pods", verb),
+    };
+    let reviews: Api<SelfSubjectAccessReview> = Api::all(client.clone());
+    match reviews.create(&PostParams::default(), &review).await {
+        Ok(review) => match review.status {
+            Some(status) if status.allowed => Check::Ok,
+            Some(status) => Check::Failed(
+                format!(
+                    "RBAC denies {} in {}{}",
+                    what,
+                    namespace,
+                    status
+                        .reason
+                        .filter(|r| !r.is_empty())
+                        .map(|r| format!(" ({})", r))
+                        .unwrap_or_default()
+                ),
+                ExitCode::Forbidden,
+            ),
+            None => Check::Failed(
+                format!("no answer on whether we may {}", what),
+                ExitCode::Failure,
+            ),
+        },
+        Err(e) => Check::Failed(
+            format!("cannot check {}: {}", what, describe_error(&e)),
+            kube_error_code(&e),
+        ),
+    }
+}
+
+fn describe_error(e: &kube::Error) -> String {
+    match e {
+        kube::Error::Api(err) if err.code == 401 => {
+            "unauthorized (credentials rejected)".to_string()
+        }
+        kube::Error::Api(err) if err.code == 403 => format!("forbidden: {}", err.message),
+        other => other.to_string(),
+    }
+}
+
+/// The report as a table, followed by one line per failed check
+pub fn format_report(reports: &[ContextReport]) -> Vec<String> {
+    let context_width = reports
+        .iter()
+        .map(|r| r.context.len())
+        .chain(["CONTEXT".len()])
+        .max()
+        .unwrap_or_default();
+    let namespace_width = reports
+        .iter()
+        .map(|r| r.namespace.len())
+        .chain(["NAMESPACE".len()])
+        .max()
+        .unwrap_or_default();
+
+    let row = |cells: [&str; 7]| {
+        format!(
+            "{:<cw$}  {:<nw$}  {:<6}  {:<9}  {:<10}  {:<9}  {}",
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            cells[4],
+            cells[5],
+            cells[6],
+            cw = context_width,
+            nw = namespace_width
+        )
+        .trim_end()
+        .to_string()
+    };
+
+    let mut lines = vec![row([
+        "CONTEXT",
+        "NAMESPACE",
+        "CLIENT",
+        "LIST PODS",
+        "WATCH PODS",
+        "READ LOGS",
+        "LATENCY",
+    ])];
+    for report in reports {
+        let latency = report
+            .latency
+            .map(|l| format!("{}ms", l.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        lines.push(row([
+            &report.context,
+            &report.namespace,
+            report.client.cell(),
+            report.list_pods.cell(),
+            report.watch_pods.cell(),
+            report.read_logs.cell(),
+            &latency,
+        ]));
+    }
+
+    let failures: Vec<String> = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .checks()
+                .into_iter()
+                .filter_map(|(name, check)| match check {
+                    Check::Failed(reason, _) => {
+                        Some(format!("  {} {}: {}", report.context, name, reason))
+                    }
+                    _ => None,
+                })
+        })
+        .collect();
+    if !failures.is_empty() {
+        lines.push(String::new());
+        lines.push("Problems:".to_string());
+        lines.extend(failures);
+    }
+    lines
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// How the process ends, so wrapping scripts can tell failure modes apart.
+/// Listed in `--help`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    /// Any other error, or a container that failed with --wait
+    Failure = 1,
+    /// Invalid arguments, profile or config file
+    Usage = 2,
+    /// A resource named on the command line does not exist
+    NoMatch = 3,
+    /// No cluster could be reached
+    Unreachable = 4,
+    /// Credentials were rejected or RBAC forbids what is needed
+    Forbidden = 5,
+}
+
+/// `--help` text describing the exit codes
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Other error, or a container failed (--wait)
+  2  Usage error: invalid arguments, profile or config file
+  3  A named resource does not exist
+  4  No cluster could be reached
+  5  Credentials rejected or access forbidden by RBAC";
+
+/// An error that ends the process with a specific exit code
+#[derive(Debug)]
+pub struct Failure {
+    pub code: ExitCode,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.error)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Attach an exit code to an error, unless it already carries one
+pub trait WithExitCode<T> {
+    fn exit_code(self, code: ExitCode) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> WithExitCode<T> for Result<T, E> {
+    fn exit_code(self, code: ExitCode) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            let error = e.into();
+            if error.is::<Failure>() {
+                error
+            } else {
+                Failure { code, error }.into()
+            }
+        })
+    }
+}
+
+/// The exit code for an error: the one attached with `exit_code`, otherwise
+/// derived from the Kubernetes error that caused it
+pub fn code_of(err: &anyhow::Error) -> ExitCode {
+    use kube::runtime::watcher::Error as WatchError;
+
+    if let Some(failure) = err.downcast_ref::<Failure>() {
+        return failure.code;
+    }
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<kube::Error>() {
+            return kube_error_code(e);
+        }
+        match cause.downcast_ref::<WatchError>() {
+            Some(
+                WatchError::InitialListFailed(e)
+                | WatchError::WatchStartFailed(e)
+                | WatchError::WatchFailed(e),
+            ) => return kube_error_code(e),
+            Some(WatchError::WatchError(response)) if matches!(response.code, 401 | 403) => {
+                return ExitCode::Forbidden;
+            }
+            _ => {}
+        }
+    }
+    ExitCode::Failure
+}
+
+pub fn kube_error_code(e: &kube::Error) -> ExitCode {
+    match e {
+        kube::Error::Api(err) if matches!(err.code, 401 | 403) => ExitCode::Forbidden,
+        kube::Error::Auth(_) => ExitCode::Forbidden,
+        // The API server answered, just not as hoped
+        kube::Error::Api(_) | kube::Error::SerdeError(_) => ExitCode::Failure,
+        _ => ExitCode::Unreachable,
+    }
+}
+
+/// One exit code for several failures: unreachable when none got an answer,
+/// forbidden when any was rejected
+pub fn combined_code(failures: &[ExitCode]) -> ExitCode {
+    if failures.iter().all(|c| *c == ExitCode::Unreachable) {
+        ExitCode::Unreachable
+    } else if failures.contains(&ExitCode::Forbidden) {
+        ExitCode::Forbidden
+    } else {
+        ExitCode::Failure
+    }
+}
+
+/// Pod watchers still running and how the stopped ones ended. A session
+/// whose watchers have all failed has nothing left to tail.
+#[derive(Debug, Default)]
+pub struct WatcherHealth {
+    state: Mutex<WatcherCounts>,
+    all_failed: Notify,
+}
+
+#[derive(Debug, Default)]
+struct WatcherCounts {
+    /// Every watcher registered and not yet stopped, by id
+    watchers: HashMap<u64, WatcherState>,
+    next_id: u64,
+    /// Exit codes of the watchers that stopped with an error
+    failed: Vec<ExitCode>,
+    /// The first of those errors
+    first_error: Option<String>,
+    /// Set once a watcher ended without an error
+    any_ok: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatcherState {
+    Running,
+    /// Watching for an explicitly named pod that was deleted or never
+    /// existed: nothing to tail unless it comes back
+    Gone,
+    /// A resource followed with --watch-selector-change, which may get a
+    /// selector (and a watcher) at any time
+    Following,
+}
+
+impl WatcherCounts {
+    /// Watchers of pods, as opposed to followed resources
+    fn running(&self) -> usize {
+        self.watchers
+            .values()
+            .filter(|w| **w != WatcherState::Following)
+            .count()
+    }
+
+    fn all_failed(&self) -> bool {
+        self.running() == 0 && !self.any_ok && !self.failed.is_empty()
+    }
+}
+
+/// A watcher's entry in the session's [`WatcherHealth`], taken when it is
+/// spawned. Dropping it without [`Watcher::stopped`] (e.g. when the task is
+/// aborted) removes the entry without counting an outcome.
+#[must_use]
+#[derive(Debug)]
+pub struct Watcher {
+    health: Arc<WatcherHealth>,
+    id: WatcherId,
+}
+
+/// Identifies a registered watcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatcherId(u64);
+
+impl Watcher {
+    pub fn id(&self) -> WatcherId {
+        self.id
+    }
+
+    /// Record how the watcher ended; `None` for a clean end
+    pub fn stopped(self, error: Option<&anyhow::Error>) {
+        let mut state = self.health.state.lock().unwrap();
+        state.watchers.remove(&self.id.0);
+        match error {
+            Some(err) => {
+                state.failed.push(code_of(err));
+                state
+                    .first_error
+                    .get_or_insert_with(|| format!("{:#}", err));
+            }
+            None => state.any_ok = true,
+        }
+        if state.all_failed() {
+            self.health.all_failed.notify_one();
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        let mut state = self.health.state.lock().unwrap();
+        if state.watchers.remove(&self.id.0).is_some() && state.all_failed() {
+            self.health.all_failed.notify_one();
+        }
+    }
+}
+
+impl WatcherHealth {
+    fn register(self: &Arc<Self>, watcher: WatcherState) -> Watcher {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.watchers.insert(id, watcher);
+        Watcher {
+            health: self.clone(),
+            id: WatcherId(id),
+        }
+    }
+
+    /// Register a pod watcher as it is spawned
+    pub fn started(self: &Arc<Self>) -> Watcher {
+        self.register(WatcherState::Running)
+    }
+
+    /// Register a resource followed with --watch-selector-change
+    pub fn following(self: &Arc<Self>) -> Watcher {
+        self.register(WatcherState::Following)
+    }
+
+    /// Record whether the pod an explicitly named pod watcher waits for is gone
+    pub fn named_pod_gone(&self, watcher: WatcherId, gone: bool) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(entry) = state.watchers.get_mut(&watcher.0)
+            && *entry != WatcherState::Following
+        {
+            *entry = if gone {
+                WatcherState::Gone
+            } else {
+                WatcherState::Running
+            };
+        }
+    }
+
+    /// Watchers that may still find pods to tail: the running ones, less those
+    /// of named pods that are gone, plus followed resources
+    pub fn waiting(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state
+            .watchers
+            .values()
+            .filter(|w| **w != WatcherState::Gone)
+            .count()
+    }
+
+    /// Exit code for a session whose watchers have all failed: unreachable
+    /// when no cluster answered, forbidden when any rejected us
+    pub fn failure_code(&self) -> Option<ExitCode> {
+        let state = self.state.lock().unwrap();
+        if !state.all_failed() {
+            return None;
+        }
+        Some(combined_code(&state.failed))
+    }
+
+    /// Resolve once every watcher has failed, with an error carrying the
+    /// exit code
+    pub async fn wait_all_failed(&self) -> anyhow::Error {
+        loop {
+            self.all_failed.notified().await;
+            if let Some(code) = self.failure_code() {
+                let first = self.state.lock().unwrap().first_error.clone();
+                return Failure {
+                    code,
+                    error: anyhow::anyhow!(
+                        "Every pod watcher stopped; the first failed with: {}",
+                        first.unwrap_or_default()
+                    ),
+                }
+                .into();
+            }
+        }
+    }
+}
@@ -1,15 +1,344 @@
-use crate::types::LogMessage;
-use crate::utils::strip_ansi_codes;
-use futures::io::AsyncBufReadExt;
-use futures::stream::StreamExt;
-use k8s_openapi::api::core::v1::Pod;
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use crate::exit::WatcherHealth;
+use crate::types::{
+    ChannelStats, ConnectionState, LogMessage, MarkerKind, PodKey, SATURATION_HINT, StreamStates,
+};
+use crate::ui::AppEvent;
+use crate::utils::{
+    CrdSelector, Redaction, apply_redactions, closest_names, selector_at_path, split_timestamp,
+    strip_ansi_codes, truncate_line_bytes,
+};
+use futures::io::{AsyncBufRead, AsyncBufReadExt};
+use futures::stream::TryStreamExt;
+use http_body_util::BodyExt;
+use k8s_openapi::api::core::v1::{Pod, ResourceRequirements};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, OwnerReference};
 use kube::{Api, Client, api::LogParams};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task::AbortHandle;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
+
+/// Session-wide options applied by every tail task
+#[derive(Clone, Default)]
+pub struct TailOptions {
+    /// Lines of history to fetch on the first connection
+    pub tail: Option<i64>,
+    /// Only fetch history this many seconds old on the first connection
+    pub since_seconds: Option<i64>,
+    /// Fetch the log of each container's previous instance once instead of
+    /// following the running one
+    pub previous: bool,
+    /// Read the log once instead of following it, for init and ephemeral
+    /// containers that already finished
+    pub once: bool,
+    /// Substitutions applied to every line before it leaves the tail task
+    pub redactions: Arc<Vec<Redaction>>,
+    /// Longer lines are truncated before they leave the tail task
+    pub max_line_bytes: Option<usize>,
+    /// Request gzip-encoded log streams
+    pub gzip: bool,
+    /// Log channel occupancy, updated on every send
+    pub channel_stats: Arc<ChannelStats>,
+    /// Connection state of every tail task
+    pub stream_states: Arc<StreamStates>,
+    /// How long a deleted pod's streams keep reading before they are aborted
+    pub delete_grace: Duration,
+    /// Clients of every context, rebuilt when their credentials are rejected
+    pub credentials: Arc<Credentials>,
+    /// Running pod watchers and how the stopped ones ended
+    pub watchers: Arc<WatcherHealth>,
+}
+
+/// The client of each context, rebuilt when the API server answers 401. kube
+/// only re-runs an exec credential plugin (e.g. `aws eks get-token`) when the
+/// plugin reported an expiry; a token that expires or is revoked without one is
+/// sent as is until the client is rebuilt from the kubeconfig.
+#[derive(Default)]
+pub struct Credentials {
+    contexts: std::sync::Mutex<HashMap<String, ContextClient>>,
+    /// --server, applied again on every rebuild
+    server: Option<String>,
+}
+
+struct ContextClient {
+    /// Kubeconfig context to load; None for the inferred configuration
+    kubeconfig_context: Option<String>,
+    /// Bumped on every rebuild
+    generation: u64,
+    client: Client,
+}
+
+impl Credentials {
+    pub fn new(server: Option<String>) -> Self {
+        Self {
+            server,
+            ..Self::default()
+        }
+    }
+
+    pub fn register(&self, cluster: &str, kubeconfig_context: Option<String>, client: Client) {
+        self.contexts.lock().unwrap().insert(
+            cluster.to_string(),
+            ContextClient {
+                kubeconfig_context,
+                generation: 0,
+                client,
+            },
+        );
+    }
+
+    /// The newest client of a context and its generation, for passing back to
+    /// `refresh`. None for contexts that were never registered.
+    pub fn current(&self, cluster: &str) -> Option<(u64, Client)> {
+        let contexts = self.contexts.lock().unwrap();
+        let entry = contexts.get(cluster)?;
+        Some((entry.generation, entry.client.clone()))
+    }
+
+    /// Rebuild the client of a context whose credentials were rejected while
+    /// using `generation`. Rebuilding loads the kubeconfig again, which runs its
+    /// exec plugin; callers rejected at the same time share one rebuild.
+    pub async fn refresh(&self, cluster: &str, generation: u64) -> anyhow::Result<(u64, Client)> {
+        let kubeconfig_context = {
+            let contexts = self.contexts.lock().unwrap();
+            let entry = contexts
+                .get(cluster)
+                .ok_or_else(|| anyhow::anyhow!("no client for context '{}'", cluster))?;
+            if entry.generation != generation {
+                return Ok((entry.generation, entry.client.clone()));
+            }
+            entry.kubeconfig_context.clone()
+        };
+
+        let config = match &kubeconfig_context {
+            Some(context) => {
+                kube::Config::from_kubeconfig(&kube::config::KubeConfigOptions {
+                    context: Some(context.clone()),
+                    ..Default::default()
+                })
+                .await?
+            }
+            None => kube::Config::infer().await?,
+        };
+        let config = match &self.server {
+            Some(server) => override_server(config, server)?,
+            None => config,
+        };
+        let client = Client::try_from(config)?;
+
+        let mut contexts = self.contexts.lock().unwrap();
+        let entry = contexts
+            .get_mut(cluster)
+            .ok_or_else(|| anyhow::anyhow!("no client for context '{}'", cluster))?;
+        if entry.generation == generation {
+            entry.generation += 1;
+            entry.client = client;
+            info!("[{}] Rebuilt client with fresh credentials", cluster);
+        }
+        Ok((entry.generation, entry.client.clone()))
+    }
+}
+
+/// Whether the API server rejected the request's credentials
+pub fn is_unauthorized(e: &kube::Error) -> bool {
+    matches!(e, kube::Error::Api(err) if err.code == 401)
+}
+
+/// Point a kubeconfig's client at another API server URL (--server), e.g. a
+/// `kubectl proxy` or a bastion's forwarded port. Credentials are kept for
+/// https; a plain-http server gets none, as they would be sent in the clear
+/// (and an endpoint such as `kubectl proxy` authenticates on its own).
+pub fn override_server(mut config: kube::Config, server: &str) -> anyhow::Result<kube::Config> {
+    config.cluster_url = server
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid --server URL '{}': {}", server, e))?;
+    if is_plain_http(&config) {
+        config.auth_info = Default::default();
+    }
+    Ok(config)
+}
+
+/// Whether a client for `config` talks to its API server without TLS
+pub fn is_plain_http(config: &kube::Config) -> bool {
+    config.cluster_url.scheme_str() == Some("http")
+}
+
+/// Whether a kubeconfig user authenticates with anything, which --server
+/// leaves out for a plain-http server
+pub fn has_credentials(auth: &kube::config::AuthInfo) -> bool {
+    auth.token.is_some()
+        || auth.token_file.is_some()
+        || auth.password.is_some()
+        || auth.client_certificate.is_some()
+        || auth.client_certificate_data.is_some()
+        || auth.client_key.is_some()
+        || auth.client_key_data.is_some()
+        || auth.auth_provider.is_some()
+        || auth.exec.is_some()
+}
+
+/// Resolves the workload owning a pod (e.g. `deployment/web`) by following
+/// controller ownerReferences: Pod → ReplicaSet → Deployment, Pod → Job → CronJob.
+/// Intermediate owners are looked up once and cached.
+pub struct WorkloadResolver {
+    client: Client,
+    cache: std::sync::Mutex<std::collections::HashMap<String, String>>,
+}
+
+impl WorkloadResolver {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            cache: Default::default(),
+        }
+    }
+
+    /// `kind/name` of the pod's top-level owner, lowercased kind; None for bare pods
+    pub async fn resolve(&self, pod: &Pod) -> Option<String> {
+        let owner = controller_owner(&pod.metadata.owner_references)?;
+        let owner_id = format!("{}/{}", owner.kind.to_lowercase(), owner.name);
+        if !matches!(owner.kind.as_str(), "ReplicaSet" | "Job") {
+            return Some(owner_id);
+        }
+
+        // Owners live in the pod's namespace, which varies with -A
+        let namespace = pod.metadata.namespace.as_deref()?;
+        let cache_key = format!("{}/{}", namespace, owner_id);
+        if let Some(cached) = self.cache.lock().unwrap().get(&cache_key) {
+            return Some(cached.clone());
+        }
+        let parents = match owner.kind.as_str() {
+            "ReplicaSet" => Api::<k8s_openapi::api::apps::v1::ReplicaSet>::namespaced(
+                self.client.clone(),
+                namespace,
+            )
+            .get_metadata(&owner.name)
+            .await
+            .map(|m| m.metadata.owner_references),
+            _ => {
+                Api::<k8s_openapi::api::batch::v1::Job>::namespaced(self.client.clone(), namespace)
+                    .get_metadata(&owner.name)
+                    .await
+                    .map(|m| m.metadata.owner_references)
+            }
+        };
+        let workload = match parents {
+            Ok(refs) => controller_owner(&refs)
+                .map(|p| format!("{}/{}", p.kind.to_lowercase(), p.name))
+                .unwrap_or_else(|| owner_id.clone()),
+            Err(e) => {
+                // Don't cache: the lookup may succeed next time
+                debug!("Failed to look up owner of {}: {}", owner_id, e);
+                return Some(owner_id);
+            }
+        };
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(cache_key, workload.clone());
+        Some(workload)
+    }
+}
+
+/// Resolves the topology zone of nodes from their `topology.kubernetes.io/zone`
+/// label, caching each node. Lookups run in the background, off the pod event
+/// path, and each resolved zone is sent to the TUI. Nodes are cluster-scoped,
+/// so many users can't read them; once that is denied no more lookups are made.
+pub struct ZoneResolver {
+    client: Client,
+    cluster: String,
+    events: mpsc::Sender<AppEvent>,
+    cache: std::sync::Mutex<HashMap<String, Option<String>>>,
+    /// Nodes being looked up
+    pending: std::sync::Mutex<HashSet<String>>,
+    denied: std::sync::atomic::AtomicBool,
+}
+
+impl ZoneResolver {
+    pub fn new(client: Client, cluster: String, events: mpsc::Sender<AppEvent>) -> Self {
+        Self {
+            client,
+            cluster,
+            events,
+            cache: Default::default(),
+            pending: Default::default(),
+            denied: Default::default(),
+        }
+    }
+
+    /// The zone of a node if it was already resolved. Otherwise a lookup is
+    /// started (unless one is running) and None returned for now.
+    pub fn zone(self: &Arc<Self>, node: &str) -> Option<String> {
+        if self.denied.load(Ordering::Relaxed) {
+            return None;
+        }
+        if let Some(cached) = self.cache.lock().unwrap().get(node) {
+            return cached.clone();
+        }
+        if self.pending.lock().unwrap().insert(node.to_string()) {
+            let resolver = self.clone();
+            let node = node.to_string();
+            tokio::spawn(async move {
+                let zone = resolver.lookup(&node).await;
+                resolver.pending.lock().unwrap().remove(&node);
+                if let Some(zone) = zone {
+                    let _ = resolver
+                        .events
+                        .send(AppEvent::NodeZone {
+                            cluster: resolver.cluster.clone(),
+                            node,
+                            zone,
+                        })
+                        .await;
+                }
+            });
+        }
+        None
+    }
+
+    async fn lookup(&self, node: &str) -> Option<String> {
+        let zone = match Api::<k8s_openapi::api::core::v1::Node>::all(self.client.clone())
+            .get_metadata(node)
+            .await
+        {
+            Ok(meta) => meta.metadata.labels.and_then(|labels| {
+                labels
+                    .get("topology.kubernetes.io/zone")
+                    .or_else(|| labels.get("failure-domain.beta.kubernetes.io/zone"))
+                    .cloned()
+            }),
+            Err(kube::Error::Api(err)) if err.code == 401 || err.code == 403 => {
+                debug!(
+                    "Not allowed to read nodes ({}), zones unavailable",
+                    err.code
+                );
+                self.denied.store(true, Ordering::Relaxed);
+                return None;
+            }
+            Err(e) => {
+                // Don't cache: the lookup may succeed next time
+                debug!("Failed to look up node {}: {}", node, e);
+                return None;
+            }
+        };
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(node.to_string(), zone.clone());
+        zone
+    }
+}
+
+fn controller_owner(refs: &Option<Vec<OwnerReference>>) -> Option<&OwnerReference> {
+    let refs = refs.as_ref()?;
+    refs.iter()
+        .find(|r| r.controller == Some(true))
+        .or_else(|| refs.first())
+}
 
 trait HasSelector {
     fn get_selector(&self) -> Option<&LabelSelector>;
@@ -69,11 +398,69 @@ where
     Ok(Some(selector.clone()))
 }
 
+async fn list_names<T>(client: &Client, namespace: &str) -> anyhow::Result<Vec<String>>
+where
+    T: k8s_openapi::Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + k8s_openapi::Metadata<Ty = k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta>
+        + serde::de::DeserializeOwned
+        + Clone
+        + Debug,
+{
+    let api: Api<T> = Api::namespaced(client.clone(), namespace);
+    let list = api.list_metadata(&Default::default()).await?;
+    Ok(list
+        .items
+        .into_iter()
+        .filter_map(|m| m.metadata.name)
+        .collect())
+}
+
+/// Names of existing resources of a kind that are close to a name that was not
+/// found, closest first
+pub async fn suggest_resource_names(
+    client: &Client,
+    resource_type: &str,
+    name: &str,
+    namespace: &str,
+) -> anyhow::Result<Vec<String>> {
+    use k8s_openapi::api::{apps::v1 as apps, batch::v1 as batch};
+    let names = match resource_type {
+        "deployment" => list_names::<apps::Deployment>(client, namespace).await?,
+        "statefulset" => list_names::<apps::StatefulSet>(client, namespace).await?,
+        "daemonset" => list_names::<apps::DaemonSet>(client, namespace).await?,
+        "job" => list_names::<batch::Job>(client, namespace).await?,
+        "replicaset" => list_names::<apps::ReplicaSet>(client, namespace).await?,
+        "pod" => list_names::<Pod>(client, namespace).await?,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(closest_names(name, &names))
+}
+
+/// Selector of a custom resource, read from where its `--crd-selector-path`
+/// says it is
+async fn get_selector_from_custom_resource(
+    client: &Client,
+    crd: &CrdSelector,
+    name: &str,
+    namespace: &str,
+) -> anyhow::Result<Option<LabelSelector>> {
+    use kube::api::{ApiResource, DynamicObject, GroupVersionKind};
+    let gvk = GroupVersionKind::gvk(&crd.group, &crd.version, &crd.kind);
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(client.clone(), namespace, &ApiResource::from_gvk(&gvk));
+    let res = api.get(name).await?;
+    let selector = selector_at_path(&res.data, &crd.path)
+        .map_err(|e| anyhow::anyhow!("{}/{}: {}", crd.kind, name, e))?
+        .ok_or_else(|| anyhow::anyhow!("No selector at .{}", crd.path.join(".")))?;
+    Ok(Some(selector))
+}
+
 pub async fn get_selector_from_resource(
     client: &Client,
     resource_type: &str,
     name: &str,
     namespace: &str,
+    crds: &[CrdSelector],
 ) -> anyhow::Result<Option<LabelSelector>> {
     match resource_type {
         "deployment" => {
@@ -107,63 +494,372 @@ pub async fn get_selector_from_resource(
             .await
         }
         "pod" => Ok(None),
-        _ => anyhow::bail!("Unsupported resource type: {}", resource_type),
+        _ => match crds.iter().find(|crd| crd.matches_kind(resource_type)) {
+            Some(crd) => get_selector_from_custom_resource(client, crd, name, namespace).await,
+            None => anyhow::bail!(
+                "Unsupported resource type: {} (custom resources need --crd-selector-path)",
+                resource_type
+            ),
+        },
     }
 }
 
-pub async fn spawn_tail_tasks_for_pod(
+/// Containers of one pod to tail
+#[derive(Debug, Clone)]
+pub struct TailTarget {
+    pub cluster: String,
+    pub namespace: String,
+    pub pod_name: String,
+    /// Pins the tasks to this instance of the pod; see [`spawn_tail_task`]
+    pub pod_uid: Option<String>,
+    pub containers: Vec<String>,
+}
+
+/// Start a tail task for each of the target's containers. Returns each
+/// container's name with its task handle.
+pub fn spawn_tail_tasks_for_pod(
     client: Client,
-    cluster: String,
-    pod_name: String,
-    namespace: String,
-    container: Option<String>,
+    target: TailTarget,
     tx: mpsc::Sender<LogMessage>,
-    tail: Option<i64>,
-) -> Vec<AbortHandle> {
-    if let Some(cont) = container {
-        vec![spawn_tail_task(
-            client, cluster, pod_name, namespace, cont, tx, tail,
-        )]
-    } else {
-        // Fetch pod to get container names
-        let api: Api<Pod> = Api::namespaced(client.clone(), &namespace);
-        match api.get(&pod_name).await {
-            Ok(pod) => {
-                let mut handles = Vec::new();
-                if let Some(spec) = &pod.spec {
-                    for c in &spec.containers {
-                        let handle = spawn_tail_task(
-                            client.clone(),
-                            cluster.clone(),
-                            pod_name.clone(),
-                            namespace.clone(),
-                            c.name.clone(),
-                            tx.clone(),
-                            tail,
-                        );
-                        handles.push(handle);
-                    }
-                }
-                handles
-            }
-            Err(e) => {
-                debug!("Failed to get pod {} for containers: {}", pod_name, e);
-                vec![]
+    options: TailOptions,
+) -> Vec<(String, AbortHandle)> {
+    target
+        .containers
+        .into_iter()
+        .map(|container| {
+            let key = PodKey {
+                cluster: target.cluster.clone(),
+                namespace: target.namespace.clone(),
+                pod_name: target.pod_name.clone(),
+                container_name: container.clone(),
+            };
+            let handle = spawn_tail_task(
+                client.clone(),
+                key,
+                target.pod_uid.clone(),
+                tx.clone(),
+                options.clone(),
+            );
+            (container, handle)
+        })
+        .collect()
+}
+
+/// If a container will never write another log line, how it ended (e.g.
+/// `exit code 0 (Completed)`): the pod is in a terminal phase, or the container
+/// terminated and the restart policy won't bring it back.
+pub fn finished_container(pod: &Pod, container: &str) -> Option<String> {
+    let status = pod.status.as_ref()?;
+    let terminated = status
+        .container_statuses
+        .iter()
+        .chain(status.init_container_statuses.iter())
+        .chain(status.ephemeral_container_statuses.iter())
+        .flatten()
+        .find(|cs| cs.name == container)
+        .and_then(|cs| cs.state.as_ref()?.terminated.as_ref());
+    let pod_finished = matches!(status.phase.as_deref(), Some("Succeeded" | "Failed"));
+    let restart_policy = pod.spec.as_ref().and_then(|s| s.restart_policy.as_deref());
+    let ephemeral = status
+        .ephemeral_container_statuses
+        .iter()
+        .flatten()
+        .any(|cs| cs.name == container);
+    // Sidecars are init containers with their own restart policy of Always
+    let run_once_init = pod
+        .spec
+        .as_ref()
+        .and_then(|s| s.init_containers.as_ref())
+        .and_then(|cs| cs.iter().find(|c| c.name == container))
+        .is_some_and(|c| c.restart_policy.as_deref() != Some("Always"));
+    let restarts = match (restart_policy, terminated) {
+        (_, None) => true,
+        // Ephemeral containers never restart, init containers not once they
+        // succeeded
+        _ if ephemeral => false,
+        (_, Some(t)) if run_once_init && t.exit_code == 0 => false,
+        (Some("Never"), Some(_)) => false,
+        (Some("OnFailure"), Some(t)) => t.exit_code != 0,
+        _ => true,
+    };
+    if !pod_finished && restarts {
+        return None;
+    }
+    Some(match terminated {
+        // The limit it ran into explains an OOM kill
+        Some(t) if t.reason.as_deref() == Some("OOMKilled") => {
+            match resource_requirements(pod, container)
+                .and_then(|r| r.limits.as_ref()?.get("memory"))
+            {
+                Some(limit) => format!(
+                    "exit code {} (OOMKilled, memory limit {})",
+                    t.exit_code, limit.0
+                ),
+                None => format!("exit code {} (OOMKilled)", t.exit_code),
             }
         }
+        Some(t) => match &t.reason {
+            Some(reason) => format!("exit code {} ({})", t.exit_code, reason),
+            None => format!("exit code {}", t.exit_code),
+        },
+        None => format!("pod {}", status.phase.as_deref().unwrap_or("finished")),
+    })
+}
+
+/// Resource requests and limits of a container of any kind
+fn resource_requirements<'a>(pod: &'a Pod, container: &str) -> Option<&'a ResourceRequirements> {
+    let spec = pod.spec.as_ref()?;
+    spec.containers
+        .iter()
+        .chain(spec.init_containers.iter().flatten())
+        .find(|c| c.name == container)
+        .map(|c| c.resources.as_ref())
+        .or_else(|| {
+            spec.ephemeral_containers
+                .iter()
+                .flatten()
+                .find(|c| c.name == container)
+                .map(|c| c.resources.as_ref())
+        })
+        .flatten()
+}
+
+/// A container's CPU/memory requests and limits, e.g.
+/// `requests cpu=100m memory=128Mi, limits memory=256Mi`. None when it sets
+/// neither
+pub fn container_resources(pod: &Pod, container: &str) -> Option<String> {
+    let resources = resource_requirements(pod, container)?;
+    let parts: Vec<String> = [
+        ("requests", &resources.requests),
+        ("limits", &resources.limits),
+    ]
+    .into_iter()
+    .filter_map(|(label, quantities)| {
+        let quantities = quantities.as_ref().filter(|q| !q.is_empty())?;
+        let values: Vec<String> = quantities
+            .iter()
+            .map(|(name, quantity)| format!("{}={}", name, quantity.0))
+            .collect();
+        Some(format!("{} {}", label, values.join(" ")))
+    })
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// Short status of a failed log request: the HTTP status code and Kubernetes
+/// reason for API errors (e.g. `403 Forbidden`), the error itself otherwise
+pub fn stream_error_status(e: &kube::Error) -> String {
+    match e {
+        kube::Error::Api(err) if err.reason.is_empty() => err.code.to_string(),
+        kube::Error::Api(err) => format!("{} {}", err.code, err.reason),
+        other => other.to_string(),
+    }
+}
+
+/// Whether `pod` is a different instance than the one with `uid`, i.e. the pod
+/// was deleted and re-created under the same name. Without a UID to compare,
+/// any pod of that name is taken to be the same.
+pub fn is_other_instance(pod: &Pod, uid: Option<&str>) -> bool {
+    uid.is_some_and(|uid| pod.metadata.uid.as_deref() != Some(uid))
+}
+
+/// A container's log stream. With `wire` (--gzip) the response is requested
+/// gzip-encoded and decompressed here, and `wire` counts the bytes as they
+/// arrive, compressed or not.
+async fn open_log_stream(
+    api: &Api<Pod>,
+    pod_name: &str,
+    lp: &LogParams,
+    wire: Option<&Arc<AtomicU64>>,
+) -> kube::Result<Box<dyn AsyncBufRead + Send + Unpin>> {
+    let Some(wire) = wire else {
+        return Ok(Box::new(api.log_stream(pod_name, lp).await?));
+    };
+    let mut request = kube::core::Request::new(api.resource_url())
+        .logs(pod_name, lp)
+        .map_err(kube::Error::BuildRequest)?;
+    request.headers_mut().insert(
+        "accept-encoding",
+        "gzip".parse().expect("valid header value"),
+    );
+    let response = api
+        .clone()
+        .into_client()
+        .send(request.map(kube::client::Body::from))
+        .await?;
+
+    // Errors as Api::log_stream reports them
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        let body = response.into_body().collect_bytes().await?;
+        let text = String::from_utf8_lossy(&body);
+        return Err(kube::Error::Api(
+            serde_json::from_str(&text).unwrap_or_else(|_| kube::core::ErrorResponse {
+                status: status.to_string(),
+                code: status.as_u16(),
+                message: format!("{:?}", text),
+                reason: "Failed to parse error data".to_string(),
+            }),
+        ));
+    }
+
+    let gzipped = response
+        .headers()
+        .get("content-encoding")
+        .is_some_and(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"));
+    let wire = wire.clone();
+    let body = response
+        .into_body()
+        .into_data_stream()
+        .inspect_ok(move |chunk| {
+            wire.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        })
+        .map_err(std::io::Error::other)
+        .into_async_read();
+    if gzipped {
+        Ok(Box::new(gunzip(body)))
+    } else {
+        Ok(Box::new(body))
+    }
+}
+
+/// Decompress a gzip stream, passing on what each chunk decodes to as soon as
+/// it arrives. Concatenated members are decoded one after another.
+pub fn gunzip(reader: impl AsyncBufRead + Send + Unpin) -> impl AsyncBufRead + Send + Unpin {
+    let mut decoder = async_compression::futures::bufread::GzipDecoder::new(reader);
+    decoder.multiple_members(true);
+    futures::io::BufReader::new(decoder)
+}
+
+/// Bytes of a line read past --max-line-bytes before the rest is discarded
+const READ_SLACK: usize = 4096;
+
+/// One line as read by [`read_line_bounded`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadLine {
+    /// Bytes consumed from the stream, including the newline
+    pub len: usize,
+    /// Bytes of the line dropped past the limit
+    pub discarded: usize,
+}
+
+/// Read one line into `buf`, without its newline, keeping at most `limit`
+/// bytes of it and discarding the rest as it arrives. None at the end of the
+/// stream.
+pub async fn read_line_bounded(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    buf: &mut Vec<u8>,
+    limit: usize,
+) -> std::io::Result<Option<ReadLine>> {
+    buf.clear();
+    let mut read = ReadLine {
+        len: 0,
+        discarded: 0,
+    };
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            break;
+        }
+        let (chunk, done) = match available.iter().position(|b| *b == b'\n') {
+            Some(newline) => (&available[..newline], true),
+            None => (available, false),
+        };
+        let kept = chunk.len().min(limit - buf.len());
+        buf.extend_from_slice(&chunk[..kept]);
+        read.discarded += chunk.len() - kept;
+        let consumed = chunk.len() + done as usize;
+        read.len += consumed;
+        reader.consume_unpin(consumed);
+        if done {
+            break;
+        }
+    }
+    if read.len == 0 {
+        return Ok(None);
+    }
+    if read.discarded == 0 && buf.last() == Some(&b'\r') {
+        buf.pop();
     }
+    Ok(Some(read))
 }
 
+/// Lines a tail task already sent, to drop those a reconnection sends again.
+/// The API resolves sinceTime only to the second, so the lines written in
+/// that second come twice; a line repeated with a new timestamp is kept.
+#[derive(Debug, Default)]
+pub struct SeenLines {
+    last: Option<chrono::DateTime<chrono::Utc>>,
+    /// The last lines sent, with their timestamps
+    recent: std::collections::VecDeque<(chrono::DateTime<chrono::Utc>, String)>,
+}
+
+impl SeenLines {
+    const RECENT: usize = 100;
+
+    /// When the last line sent was written, where a reconnection resumes
+    pub fn last_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last
+    }
+
+    /// Whether a line was already sent: it is older than the last one, or
+    /// the same line with the same timestamp was seen
+    pub fn is_replay(&self, timestamp: chrono::DateTime<chrono::Utc>, line: &str) -> bool {
+        self.last.is_some_and(|last| timestamp < last)
+            || self
+                .recent
+                .iter()
+                .any(|(seen, seen_line)| *seen == timestamp && seen_line == line)
+    }
+
+    pub fn record(&mut self, timestamp: chrono::DateTime<chrono::Utc>, line: &str) {
+        self.recent.push_back((timestamp, line.to_string()));
+        if self.recent.len() > Self::RECENT {
+            self.recent.pop_front();
+        }
+        self.last = Some(timestamp);
+    }
+}
+
+/// Tail one container. With `pod_uid`, the task is pinned to that instance of
+/// the pod and stops instead of reconnecting once the name belongs to a new one.
 pub fn spawn_tail_task(
     client: Client,
-    cluster: String,
-    pod_name: String,
-    namespace: String,
-    container_name: String,
+    key: PodKey,
+    pod_uid: Option<String>,
     tx: mpsc::Sender<LogMessage>,
-    tail: Option<i64>,
+    options: TailOptions,
 ) -> AbortHandle {
-    let api: Api<Pod> = Api::namespaced(client, &namespace);
+    let PodKey {
+        cluster,
+        namespace,
+        pod_name,
+        container_name,
+    } = key.clone();
+    // The newest client of the context: the caller's may predate a rebuild
+    let (mut generation, client) = match options.credentials.current(&cluster) {
+        Some((generation, client)) => (Some(generation), client),
+        None => (None, client),
+    };
+    let mut api: Api<Pod> = Api::namespaced(client, &namespace);
+
+    // A pod re-created under the same name starts afresh
+    options.stream_states.remove(&key);
+    options.stream_states.set(&key, ConnectionState::Connecting);
+    let received = options.stream_states.received_counter(&key);
+    let wire = options
+        .gzip
+        .then(|| options.stream_states.wire_counter(&key));
+    // The API can't follow a previous instance, and a finished container
+    // writes nothing more, so those are one-off fetches
+    let one_off = options.previous || options.once;
+
+    // Longer lines are cut while they are read, so one huge line can't take
+    // unbounded memory. The slack past --max-line-bytes keeps the timestamp
+    // prefix and lets redaction see a secret that straddles the final cut.
+    let read_limit = options
+        .max_line_bytes
+        .map_or(usize::MAX, |max| max.saturating_add(READ_SLACK));
 
     let handle = tokio::spawn(async move {
         debug!(
@@ -171,30 +867,33 @@ pub fn spawn_tail_task(
             pod_name, container_name, namespace
         );
         let mut is_first_attempt = true;
-        let mut last_log_time: Option<chrono::DateTime<chrono::Utc>> = None;
-        let mut recent_logs: std::collections::VecDeque<String> =
-            std::collections::VecDeque::with_capacity(100);
+        let mut seen = SeenLines::default();
+        // Set after rebuilding the client for a 401, until a stream connects
+        let mut refreshed = false;
 
         loop {
             let is_reconnection = !is_first_attempt;
-            let lp_follow = if is_first_attempt {
+            let lp_follow = if is_first_attempt || one_off {
                 // First attempt: use user-specified tail
                 is_first_attempt = false;
                 LogParams {
-                    follow: true,
+                    follow: !one_off,
                     container: Some(container_name.clone()),
-                    tail_lines: tail,
+                    timestamps: true,
+                    tail_lines: options.tail,
+                    since_seconds: options.since_seconds,
                     ..Default::default()
                 }
             } else {
                 // Reconnection: use sinceTime to avoid replay
-                if let Some(since_time) = last_log_time {
-                    // Add 1 second to avoid getting the exact same log line again
-                    let since_time_plus = since_time + chrono::Duration::seconds(1);
+                if let Some(since_time) = seen.last_time() {
+                    // The API only resolves sinceTime to the second, so lines
+                    // written in that second come again; `seen` drops them
                     LogParams {
                         follow: true,
                         container: Some(container_name.clone()),
-                        since_time: Some(since_time_plus),
+                        timestamps: true,
+                        since_time: Some(since_time),
                         ..Default::default()
                     }
                 } else {
@@ -202,15 +901,18 @@ pub fn spawn_tail_task(
                     LogParams {
                         follow: true,
                         container: Some(container_name.clone()),
+                        timestamps: true,
                         tail_lines: Some(0), // Get no historical logs on reconnect
                         ..Default::default()
                     }
                 }
             };
-            match api.log_stream(&pod_name, &lp_follow).await {
+            match open_log_stream(&api, &pod_name, &lp_follow, wire.as_ref()).await {
                 Ok(stream) => {
+                    refreshed = false;
+                    options.stream_states.set(&key, ConnectionState::Connected);
                     if is_reconnection {
-                        if last_log_time.is_some() {
+                        if seen.last_time().is_some() {
                             debug!(
                                 "Reconnected to {}/{} using sinceTime (no replay)",
                                 pod_name, container_name
@@ -222,39 +924,55 @@ pub fn spawn_tail_task(
                             );
                         }
                     }
-                    let mut line_stream = stream.lines();
-                    while let Some(line_result) = line_stream.next().await {
-                        match line_result {
-                            Ok(line) => {
-                                // Simple deduplication: skip if we've seen this exact log line recently
-                                if is_reconnection && recent_logs.contains(&line) {
+                    let mut stream = stream;
+                    let mut buf = Vec::new();
+                    let mut clean_eof = true;
+                    loop {
+                        match read_line_bounded(&mut stream, &mut buf, read_limit).await {
+                            Ok(None) => break,
+                            Ok(Some(read)) => {
+                                // As read, including the newline and what was discarded
+                                received.fetch_add(read.len as u64, Ordering::Relaxed);
+                                let line = String::from_utf8_lossy(&buf);
+                                // When the container wrote the line, as recorded by the
+                                // kubelet; receive time only if the prefix is missing
+                                let (written, line) = split_timestamp(&line);
+                                let timestamp = written.unwrap_or_else(chrono::Utc::now);
+                                // Strip ANSI escape codes to prevent TUI corruption, then
+                                // redact. Only the redacted text is kept from here on, so
+                                // dedup, search and every output see the same content.
+                                let mut line =
+                                    apply_redactions(&strip_ansi_codes(line), &options.redactions);
+                                // Truncated after redaction, so a cut never leaves part
+                                // of a secret unredacted
+                                if let Some(max) = options.max_line_bytes
+                                    && truncate_line_bytes(&mut line, max, read.discarded)
+                                {
+                                    options.channel_stats.record_truncated();
+                                }
+
+                                // Skip what the reconnection sent again
+                                if is_reconnection && seen.is_replay(timestamp, &line) {
                                     debug!(
                                         "Skipping duplicate log line on reconnection for {}/{}",
                                         pod_name, container_name
                                     );
                                     continue;
                                 }
-
-                                // Track recent log lines for deduplication (keep last 100)
-                                recent_logs.push_back(line.clone());
-                                if recent_logs.len() > 100 {
-                                    recent_logs.pop_front();
-                                }
-
-                                // Update last log time to current time for reconnection purposes
-                                last_log_time = Some(chrono::Utc::now());
-
-                                // Strip ANSI escape codes to prevent TUI corruption
-                                let clean_line = strip_ansi_codes(&line);
+                                // Resume after the last line on reconnection
+                                seen.record(timestamp, &line);
 
                                 let msg = LogMessage {
                                     cluster: cluster.clone(),
                                     namespace: namespace.clone(),
                                     pod_name: pod_name.clone(),
                                     container_name: container_name.clone(),
-                                    line: clean_line,
-                                    timestamp: chrono::Utc::now(),
+                                    line,
+                                    timestamp,
                                 };
+                                if options.channel_stats.observe(&tx) {
+                                    warn!("{}", SATURATION_HINT);
+                                }
                                 if tx.send(msg).await.is_err() {
                                     return;
                                 }
@@ -264,30 +982,208 @@ pub fn spawn_tail_task(
                                     "Error reading follow log line from pod {}/{}: {}, retrying",
                                     pod_name, container_name, e
                                 );
+                                clean_eof = false;
                                 break;
                             }
                         }
                     }
-                    // If stream ended, retry
+                    // A previous instance's or finished container's log is
+                    // complete once read
+                    if one_off {
+                        debug!(
+                            "Read {} log of {}/{}, stopping tail",
+                            if options.previous {
+                                "previous"
+                            } else {
+                                "complete"
+                            },
+                            pod_name,
+                            container_name
+                        );
+                        options.stream_states.remove(&key);
+                        return;
+                    }
+                    // The pod was deleted: everything it wrote has been read
+                    if options.stream_states.is_draining(&key) {
+                        debug!(
+                            "Log stream of deleted pod {}/{} ended, stopping tail",
+                            pod_name, container_name
+                        );
+                        options.stream_states.remove(&key);
+                        return;
+                    }
+                    // A clean end of stream for a container that has finished for
+                    // good means there are no more logs to read
+                    if clean_eof {
+                        match api.get_opt(&pod_name).await {
+                            Ok(None) => {
+                                debug!(
+                                    "Pod {}/{} is gone, stopping tail",
+                                    pod_name, container_name
+                                );
+                                options.stream_states.remove(&key);
+                                return;
+                            }
+                            // The task of the new instance owns the key's state now
+                            Ok(Some(pod)) if is_other_instance(&pod, pod_uid.as_deref()) => {
+                                debug!(
+                                    "Pod {}/{} was re-created, stopping tail of the old instance",
+                                    pod_name, container_name
+                                );
+                                return;
+                            }
+                            Ok(Some(pod)) => {
+                                if let Some(ended) = finished_container(&pod, &container_name) {
+                                    debug!(
+                                        "Container {}/{} finished ({}), stopping tail",
+                                        pod_name, container_name, ended
+                                    );
+                                    let msg = LogMessage {
+                                        cluster: cluster.clone(),
+                                        namespace: namespace.clone(),
+                                        pod_name: pod_name.clone(),
+                                        container_name: container_name.clone(),
+                                        line: MarkerKind::stream_closed(ended),
+                                        timestamp: chrono::Utc::now(),
+                                    };
+                                    let _ = tx.send(msg).await;
+                                    options.stream_states.remove(&key);
+                                    return;
+                                }
+                            }
+                            Err(e) => debug!(
+                                "Could not check whether {}/{} finished: {}",
+                                pod_name, container_name, e
+                            ),
+                        }
+                    } else if replaced(&api, &pod_name, pod_uid.as_deref()).await {
+                        debug!(
+                            "Pod {}/{} was re-created, stopping tail of the old instance",
+                            pod_name, container_name
+                        );
+                        return;
+                    }
+                    // The stream ended unexpectedly, retry
+                    options
+                        .stream_states
+                        .set(&key, ConnectionState::Reconnecting(None));
                     debug!(
                         "Log stream ended for pod {}/{}, retrying in 5 seconds",
                         pod_name, container_name
                     );
                 }
                 Err(e) => {
+                    let status = stream_error_status(&e);
+                    if options.stream_states.is_draining(&key) {
+                        debug!(
+                            "Pod {}/{} is gone ({}), stopping tail",
+                            pod_name, container_name, status
+                        );
+                        options.stream_states.remove(&key);
+                        return;
+                    }
                     if let kube::Error::Api(err) = &e
                         && err.code == 404
                     {
                         debug!(
-                            "Pod {}/{} not found (404), stopping tail",
-                            pod_name, container_name
+                            "Pod {}/{} not found ({}), stopping tail",
+                            pod_name, container_name, status
                         );
+                        options.stream_states.remove(&key);
                         return;
                     }
+                    // The container hasn't terminated yet, so there is no
+                    // previous instance to read
+                    if let kube::Error::Api(err) = &e
+                        && err.code == 400
+                        && options.previous
+                    {
+                        warn!(
+                            "No previous log for pod {}/{}: {}",
+                            pod_name, container_name, err.message
+                        );
+                        options
+                            .stream_states
+                            .set(&key, ConnectionState::Failed(status));
+                        return;
+                    }
+                    if replaced(&api, &pod_name, pod_uid.as_deref()).await {
+                        debug!(
+                            "Pod {}/{} was re-created ({}), stopping tail of the old instance",
+                            pod_name, container_name, status
+                        );
+                        return;
+                    }
+                    // Expired credentials: rebuild the client (re-running an exec
+                    // plugin) and reconnect right away, once
+                    if is_unauthorized(&e)
+                        && !refreshed
+                        && let Some(stale) = generation
+                    {
+                        options.stream_states.set(
+                            &key,
+                            ConnectionState::Reconnecting(Some(format!(
+                                "{}, refreshing credentials",
+                                status
+                            ))),
+                        );
+                        match options.credentials.refresh(&cluster, stale).await {
+                            Ok((fresh, client)) => {
+                                debug!(
+                                    "Credentials rejected for pod {}/{}, reconnecting with a rebuilt client",
+                                    pod_name, container_name
+                                );
+                                generation = Some(fresh);
+                                api = Api::namespaced(client, &namespace);
+                                refreshed = true;
+                                continue;
+                            }
+                            Err(err) => {
+                                warn!(
+                                    "Could not refresh credentials of context {} for pod {}/{}: {:#}, giving up",
+                                    cluster, pod_name, container_name, err
+                                );
+                                options.stream_states.set(
+                                    &key,
+                                    ConnectionState::Failed(format!(
+                                        "credential refresh failed: {:#}",
+                                        err
+                                    )),
+                                );
+                                return;
+                            }
+                        }
+                    }
+                    // Permission errors won't heal by retrying; the stream can be
+                    // restarted from the TUI once RBAC is fixed
+                    if let kube::Error::Api(err) = &e
+                        && (err.code == 401 || err.code == 403)
+                    {
+                        let status = if refreshed {
+                            format!("{} after credential refresh", status)
+                        } else {
+                            status
+                        };
+                        warn!(
+                            "Not allowed to stream logs of pod {}/{} ({}): {}, giving up",
+                            pod_name, container_name, status, err.message
+                        );
+                        options
+                            .stream_states
+                            .set(&key, ConnectionState::Failed(status));
+                        return;
+                    }
+                    let detail = match &e {
+                        kube::Error::Api(err) => err.message.clone(),
+                        other => other.to_string(),
+                    };
                     warn!(
-                        "Failed to get follow log stream for pod {}/{}: {}, retrying in 5 seconds",
-                        pod_name, container_name, e
+                        "Failed to get follow log stream for pod {}/{} ({}): {}, retrying in 5 seconds",
+                        pod_name, container_name, status, detail
                     );
+                    options
+                        .stream_states
+                        .set(&key, ConnectionState::Reconnecting(Some(status)));
                 }
             }
             tokio::time::sleep(Duration::from_secs(5)).await;
@@ -296,3 +1192,15 @@ pub fn spawn_tail_task(
 
     handle.abort_handle()
 }
+
+/// Whether the pod named `pod_name` is now a different instance than `uid`.
+/// Lookup errors count as not replaced, so the caller goes on retrying.
+async fn replaced(api: &Api<Pod>, pod_name: &str, uid: Option<&str>) -> bool {
+    if uid.is_none() {
+        return false;
+    }
+    match api.get_opt(pod_name).await {
+        Ok(Some(pod)) => is_other_instance(&pod, uid),
+        _ => false,
+    }
+}
@@ -1,4 +1,8 @@
+pub mod checkpoint;
 pub mod cli;
+pub mod completion;
+pub mod config;
+pub mod exit;
 pub mod kubernetes;
 pub mod types;
 pub mod ui;
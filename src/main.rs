@@ -1,4 +1,9 @@
+mod checkpoint;
 mod cli;
+mod completion;
+mod config;
+mod doctor;
+mod exit;
 mod kubernetes;
 #[cfg(test)]
 mod tests;
@@ -6,6 +11,8 @@ mod types;
 mod ui;
 mod utils;
 
+use anyhow::Context;
+use base64::{Engine, prelude::BASE64_STANDARD};
 use clap::Parser;
 use crossterm::{
     execute,
@@ -13,22 +20,29 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use futures::{TryStreamExt, stream::StreamExt};
-use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::api::core::v1::{Event as K8sEvent, Pod};
 use kube::runtime::watcher::{Config as WatcherConfig, Event, watcher};
-use kube::{Api, Client, ResourceExt, config};
+use kube::{Api, Client, ResourceExt, api::ListParams};
 use ratatui::{Terminal, backend::CrosstermBackend};
 use regex::Regex;
 use std::collections::HashMap;
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, mpsc};
 use tokio::task::AbortHandle;
 use tracing::{debug, error, info, warn};
 
-use cli::Cli;
-use kubernetes::{get_selector_from_resource, spawn_tail_tasks_for_pod};
-use types::LogMessage;
-use ui::app::{PodInfo, PodKey};
+use cli::{Cli, OutputFormat};
+use completion::{CompletionTracker, ContainerExit, container_exits};
+use config::Config;
+use exit::{ExitCode, Watcher, WatcherHealth, WatcherId, WithExitCode};
+use kubernetes::{
+    Credentials, TailOptions, TailTarget, WorkloadResolver, ZoneResolver, container_resources,
+    finished_container, get_selector_from_resource, spawn_tail_tasks_for_pod,
+};
+use types::{ChannelStats, LineSequence, LogLevel, LogMessage, MarkerKind, PodKey, StreamStates};
+use ui::app::{ContainerKind, PodInfo};
 use ui::{App, AppEvent};
 use utils::*;
 
@@ -37,27 +51,310 @@ use utils::*;
 struct TailContext {
     client: Client,
     cluster: String,
-    namespace: String,
+    /// The namespace watched, or with -A every one. The context of a pod
+    /// (see `for_pod`) is always scoped to the pod's namespace.
+    scope: Scope,
     container: Option<String>,
     tx: mpsc::Sender<LogMessage>,
-    tail: Option<i64>,
+    options: TailOptions,
+    annotations: Arc<Vec<AnnotationFilter>>,
+    /// Set with --wait: records how each tailed pod ends
+    completion: Option<Arc<CompletionTracker>>,
+    /// Set with --newest: how many of the most recent pods each watcher tails
+    newest: Option<usize>,
+    /// Set with --container-state running: skip containers that aren't running yet
+    running_only: bool,
+    /// --include-init and --include-ephemeral
+    extra: ExtraContainers,
+    /// --exclude-container: globs of container names never tailed or listed
+    exclude_containers: Arc<Vec<String>>,
+    /// Set when grouping by workload (`all` or --kinds): resolves pod owners
+    workloads: Option<Arc<WorkloadResolver>>,
+    /// --kinds: workload kinds whose pods are tailed (empty for any)
+    kinds: Arc<Vec<String>>,
+    /// --qos: QoS classes whose pods are tailed (empty for any)
+    qos: Arc<Vec<String>>,
+    /// Set in TUI mode: resolves node zones for the error hotspot view
+    zones: Option<Arc<ZoneResolver>>,
+    /// --crd-selector-path: where custom resources keep their pod selector
+    crd_selectors: Arc<Vec<CrdSelector>>,
+    /// Set on the watcher of an explicitly named pod, to its entry in the
+    /// watcher health: once the pod is gone it waits for nothing else
+    named_pod: Option<WatcherId>,
+}
+
+impl TailContext {
+    /// This context for a resource naming its own container (`name:container`),
+    /// which takes precedence over -c
+    fn for_container(&self, container: &Option<String>) -> Self {
+        Self {
+            container: container.clone().or_else(|| self.container.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Name, image and kind of the containers of a pod that are shown: those
+    /// [`containers_to_tail`] picks, only the running ones when `running_only`
+    fn containers_of(
+        &self,
+        pod: &Pod,
+        running_only: bool,
+    ) -> Vec<(String, Option<String>, ContainerKind)> {
+        let images = ExtraContainers {
+            init: true,
+            ephemeral: true,
+        }
+        .of(pod);
+        containers_to_tail(
+            pod,
+            &self.container,
+            running_only,
+            self.extra,
+            &self.exclude_containers,
+        )
+        .into_iter()
+        .map(|(name, kind)| {
+            let image = images
+                .iter()
+                .find(|(c, ..)| *c == name)
+                .and_then(|(_, image, _)| image.clone());
+            (name, image, kind)
+        })
+        .collect()
+    }
+
+    /// Topology zone of the node a pod runs on, if already known. Unknown
+    /// nodes are looked up in the background and their zone sent to the TUI.
+    fn zone_of(&self, pod: &Pod) -> Option<String> {
+        let node = pod.spec.as_ref()?.node_name.as_deref()?;
+        self.zones.as_ref()?.zone(node)
+    }
+
+    /// Whether a pod passes --annotation, --qos and --kinds, which leave pods
+    /// out whatever their phase
+    async fn matches_filters(&self, pod: &Pod) -> bool {
+        if !matches_annotations(pod, &self.annotations) || !matches_qos(pod, &self.qos) {
+            return false;
+        }
+        let workload = match &self.workloads {
+            Some(resolver) => resolver.resolve(pod).await,
+            None => None,
+        };
+        matches_kinds(workload.as_deref(), &self.kinds)
+    }
+
+    /// The newest client of this context, which may have been rebuilt with
+    /// fresh credentials since the context was set up
+    fn current_client(&self) -> Client {
+        self.options
+            .credentials
+            .current(&self.cluster)
+            .map_or_else(|| self.client.clone(), |(_, client)| client)
+    }
+
+    /// Whether this context's watchers span every namespace (-A)
+    fn all_namespaces(&self) -> bool {
+        self.scope == Scope::All
+    }
+
+    /// The namespace of a context scoped to one: that of any pod (see
+    /// `for_pod`), and of the watchers of named resources, which -A rejects
+    fn namespace(&self) -> &str {
+        match &self.scope {
+            Scope::Namespace(namespace) => namespace,
+            Scope::All => unreachable!("a context of every namespace has no namespace"),
+        }
+    }
+
+    /// The pods API of this context's namespace, or of all of them with -A
+    fn pods_api(&self) -> Api<Pod> {
+        self.scope.api(self.client.clone())
+    }
+
+    /// How this context's watchers tell pods apart: by name, or with -A by
+    /// `namespace/name`
+    fn pod_id(&self, pod: &Pod) -> String {
+        match pod.namespace() {
+            Some(namespace) if self.all_namespaces() => {
+                format!("{}/{}", namespace, pod.name_any())
+            }
+            _ => pod.name_any(),
+        }
+    }
+
+    /// The context of the namespace of the pod with `id` (see `pod_id`), and
+    /// the pod's name
+    fn for_pod(&self, id: &str) -> (Self, String) {
+        match id.split_once('/') {
+            Some((namespace, name)) if self.all_namespaces() => (
+                Self {
+                    scope: Scope::Namespace(namespace.to_string()),
+                    ..self.clone()
+                },
+                name.to_string(),
+            ),
+            _ => (self.clone(), id.to_string()),
+        }
+    }
+
+    /// Add a step to the debug timeline of one of this namespace's pods (by
+    /// `pod_id`), or of its watchers when `pod` is None
+    fn timeline(&self, pod: Option<&str>, event: impl Into<String>) {
+        let target = match pod {
+            Some(pod) if self.all_namespaces() => format!("{}/{}", self.cluster, pod),
+            Some(pod) => format!("{}/{}/{}", self.cluster, self.scope, pod),
+            None => format!("{}/{}", self.cluster, self.scope),
+        };
+        self.options
+            .stream_states
+            .timeline
+            .record(target, None, event);
+    }
+}
+
+/// Containers tailed besides a pod's regular ones
+#[derive(Debug, Clone, Copy, Default)]
+struct ExtraContainers {
+    /// --include-init
+    init: bool,
+    /// --include-ephemeral
+    ephemeral: bool,
+}
+
+impl ExtraContainers {
+    /// Name, image and kind of a pod's regular containers, followed by the
+    /// init and ephemeral ones that are included
+    fn of(self, pod: &Pod) -> Vec<(String, Option<String>, ContainerKind)> {
+        let Some(spec) = &pod.spec else {
+            return Vec::new();
+        };
+        let mut containers: Vec<_> = spec
+            .containers
+            .iter()
+            .map(|c| (c.name.clone(), c.image.clone(), ContainerKind::Regular))
+            .collect();
+        if self.init {
+            containers.extend(
+                spec.init_containers
+                    .iter()
+                    .flatten()
+                    .map(|c| (c.name.clone(), c.image.clone(), ContainerKind::Init)),
+            );
+        }
+        if self.ephemeral {
+            containers.extend(
+                spec.ephemeral_containers
+                    .iter()
+                    .flatten()
+                    .map(|c| (c.name.clone(), c.image.clone(), ContainerKind::Ephemeral)),
+            );
+        }
+        containers
+    }
+}
+
+/// The namespaces a watch config covers
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Scope {
+    Namespace(String),
+    /// -A: watchers span every namespace
+    All,
+}
+
+impl Scope {
+    /// The API of resources of this namespace, or of every namespace
+    fn api<K>(&self, client: Client) -> Api<K>
+    where
+        K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope>,
+        K::DynamicType: Default,
+    {
+        match self {
+            Scope::Namespace(namespace) => Api::namespaced(client, namespace),
+            Scope::All => Api::all(client),
+        }
+    }
+
+    /// Whether pods of `namespace` are in scope
+    fn covers(&self, namespace: &str) -> bool {
+        match self {
+            Scope::Namespace(ns) => ns == namespace,
+            Scope::All => true,
+        }
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Namespace(namespace) => f.write_str(namespace),
+            Scope::All => f.write_str("*"),
+        }
+    }
 }
 
+/// Upper bound for the automatic --compact prefix width
+const MAX_COMPACT_WIDTH: usize = 48;
+
+/// Capacity of the stdout buffer between --flush-interval flushes
+const STDOUT_BUFFER: usize = 64 * 1024;
+
+/// How long to keep reading logs after a pod terminates, so its last lines
+/// are not cut off
+const LOG_DRAIN: Duration = Duration::from_secs(2);
+
 /// Configuration for watching pods in a specific context/namespace
 #[derive(Debug, Clone)]
 struct WatchConfig {
     context: String,
-    namespace: String,
-    label_selectors: Vec<String>,
-    explicit_pods: std::collections::HashSet<String>,
+    scope: Scope,
+    /// Label selectors to watch, each with the container its resource targets
+    label_selectors: Vec<(String, Option<String>)>,
+    /// Pods watched by name, each with the containers its resources target
+    /// (`pod/web-0:app pod/web-0:proxy`), one watcher per container
+    explicit_pods: HashMap<String, Vec<Option<String>>>,
+    /// Resources whose selector is re-resolved with --watch-selector-change
+    followed: Vec<FollowedResource>,
+}
+
+/// A resource whose pods are found through its selector, tracked across edits
+#[derive(Debug, Clone)]
+struct FollowedResource {
+    kind: String,
+    name: String,
+    container: Option<String>,
+    /// Selector as last resolved; None until the resource exists
+    selector: Option<String>,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+async fn main() {
+    if let Err(err) = run().await {
+        let code = exit::code_of(&err);
+        let err = match err.downcast::<exit::Failure>() {
+            Ok(failure) => failure.error,
+            Err(err) => err,
+        };
+        eprintln!("Error: {:?}", err);
+        std::process::exit(code as i32);
+    }
+}
+
+async fn run() -> anyhow::Result<()> {
+    let mut cli = Cli::parse();
+    if let Some(name) = &cli.profile {
+        let config = Config::load(cli.config.as_deref()).exit_code(ExitCode::Usage)?;
+        let mut argv: Vec<std::ffi::OsString> = std::env::args_os().collect();
+        let profile = config.profile(name).exit_code(ExitCode::Usage)?.to_args();
+        argv.splice(1..1, profile.into_iter().map(Into::into));
+        cli = Cli::parse_from(argv);
+    }
+    if let Some(cli::Subcommand::Doctor) = cli.command {
+        return doctor::run(&cli).await;
+    }
 
     // Determine if we'll use TUI mode (needed to configure logging appropriately)
-    let use_tui = !cli.no_tui && std::io::stdout().is_terminal();
+    let use_tui =
+        !cli.no_tui && cli.output == OutputFormat::Text && std::io::stdout().is_terminal();
 
     // Initialize tracing subscriber - configure differently for TUI vs stdout mode
     let filter = if cli.verbose { "debug" } else { "info" };
@@ -93,22 +390,49 @@ async fn main() -> anyhow::Result<()> {
             .init();
     }
 
-    if cli.resources.is_empty() && cli.selector.is_none() {
+    if cli.resources.is_empty()
+        && cli.selector.is_none()
+        && cli.annotation.is_empty()
+        && cli.kinds.is_empty()
+    {
         if use_tui {
-            eprintln!("Error: Must specify at least one resource or a label selector (--selector)");
+            eprintln!(
+                "Error: Must specify at least one resource (or `all`), a label selector (--selector), an annotation filter (--annotation) or workload kinds (--kinds)"
+            );
         } else {
-            error!("Must specify at least one resource or a label selector (--selector)");
+            error!(
+                "Must specify at least one resource (or `all`), a label selector (--selector), an annotation filter (--annotation) or workload kinds (--kinds)"
+            );
         }
-        std::process::exit(1);
+        std::process::exit(ExitCode::Usage as i32);
+    }
+
+    let config = Config::load(cli.config.as_deref()).exit_code(ExitCode::Usage)?;
+    let environments = config.context_environments().exit_code(ExitCode::Usage)?;
+    if let Some(env) = &cli.env
+        && !config.environments.contains_key(env)
+    {
+        return Err(anyhow::anyhow!(
+            "Unknown environment '{env}': add it under `environments` in the config file"
+        ))
+        .exit_code(ExitCode::Usage);
+    }
+
+    // Expand --context globs (e.g. `prod-*`) into the matching kubeconfig contexts
+    if cli.context.iter().any(|ctx| ctx.contains(['*', '?'])) {
+        let kubeconfig = kube::config::Kubeconfig::read()?;
+        let names: Vec<String> = kubeconfig.contexts.into_iter().map(|c| c.name).collect();
+        cli.context = expand_context_globs(&cli.context, &names)
+            .map_err(|e| anyhow::anyhow!(e))
+            .exit_code(ExitCode::Usage)?;
+        info!("Expanded --context to: {}", cli.context.join(", "));
     }
 
     // Extract contexts from resource specs
     let mut contexts_to_init = std::collections::HashSet::new();
 
-    // Add explicit --context flag if present
-    if let Some(ctx) = &cli.context {
-        contexts_to_init.insert(ctx.clone());
-    }
+    // Add every explicit --context
+    contexts_to_init.extend(cli.context.iter().cloned());
 
     // Parse resource specs to extract contexts
     for res in &cli.resources {
@@ -120,47 +444,139 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Initialize clients for all contexts
-    let clients = initialize_clients(contexts_to_init.into_iter().collect()).await?;
+    let inferred = contexts_to_init.is_empty();
+    let clients = initialize_clients(
+        contexts_to_init.into_iter().collect(),
+        cli.refuse_insecure,
+        cli.server.as_deref(),
+    )
+    .await?;
+
+    // Rebuilt from the same kubeconfig context when credentials expire
+    let credentials = Arc::new(Credentials::new(cli.server.clone()));
+    for (name, client) in &clients {
+        credentials.register(name, (!inferred).then(|| name.clone()), client.clone());
+    }
 
     // Parse resources and selectors (common for both modes)
-    let watch_configs = parse_resources_and_selectors(&clients, &cli).await?;
+    let watch_configs =
+        parse_resources_and_selectors(&clients, &cli, (!use_tui).then_some(&environments)).await?;
 
-    // Channel for log messages
+    // Channel for log messages. Each container is tailed by exactly one task that
+    // awaits every send, and mpsc is FIFO per sender, so a container's lines arrive
+    // in the order the API server streamed them (lines of different containers
+    // may interleave arbitrarily).
     let (log_tx, log_rx) = mpsc::channel::<LogMessage>(cli.buffer_size);
+    let since_seconds = cli.since.map(since_seconds);
+    if since_seconds.is_some() && cli.tail.is_some() {
+        warn!("--tail is ignored when --since is given");
+    }
+    let options = TailOptions {
+        tail: cli.tail.filter(|_| since_seconds.is_none()),
+        since_seconds,
+        previous: cli.previous,
+        once: false,
+        redactions: Arc::new(cli.redact.clone()),
+        max_line_bytes: cli.max_line_bytes.map(|max| max as usize),
+        gzip: cli.gzip,
+        channel_stats: Arc::new(ChannelStats::default()),
+        stream_states: Arc::new(StreamStates::default()),
+        delete_grace: cli.delete_grace,
+        credentials,
+        watchers: Arc::new(WatcherHealth::default()),
+    };
+
+    if !use_tui && cli.tee_stdout.is_some() {
+        warn!("--tee-stdout only applies in TUI mode; lines already go to stdout");
+    }
 
     // Branch between TUI and stdout mode
     if use_tui {
-        run_tui_mode(clients, cli, watch_configs, log_tx, log_rx).await
+        run_tui_mode(
+            clients,
+            cli,
+            config,
+            environments,
+            watch_configs,
+            log_tx,
+            log_rx,
+            options,
+        )
+        .await
     } else {
-        run_stdout_mode(clients, cli, watch_configs, log_tx, log_rx).await
+        run_stdout_mode(
+            clients,
+            cli,
+            environments,
+            watch_configs,
+            log_tx,
+            log_rx,
+            options,
+        )
+        .await
     }
 }
 
-async fn initialize_clients(context_names: Vec<String>) -> anyhow::Result<Vec<(String, Client)>> {
+async fn initialize_clients(
+    context_names: Vec<String>,
+    refuse_insecure: bool,
+    server: Option<&str>,
+) -> anyhow::Result<Vec<(String, Client)>> {
+    // One URL can only stand in for one cluster
+    if server.is_some() && context_names.len() > 1 {
+        return Err(anyhow::anyhow!(
+            "--server can only be used with a single context, got {}",
+            context_names.join(", ")
+        ))
+        .exit_code(ExitCode::Usage);
+    }
+    let with_server = |context: &str, config: kube::Config| match server {
+        Some(server) => {
+            let had_credentials = kubernetes::has_credentials(&config.auth_info);
+            let config = kubernetes::override_server(config, server)?;
+            if had_credentials && kubernetes::is_plain_http(&config) {
+                // Printed before the TUI starts, so it is seen in both modes
+                eprintln!(
+                    "WARNING: --server {} is plain http; not sending the credentials of context '{}'",
+                    server, context
+                );
+                warn!(
+                    "--server {} is plain http; dropped the credentials of context '{}'",
+                    server, context
+                );
+            }
+            Ok::<_, anyhow::Error>(config)
+        }
+        None => Ok(config),
+    };
     let mut clients = Vec::new();
 
     if context_names.is_empty() {
         // Use current context - read kubeconfig to get the actual context name
-        let kubeconfig = config::Kubeconfig::read()?;
+        let kubeconfig = kube::config::Kubeconfig::read()?;
         let current_context_name = kubeconfig
             .current_context
             .as_deref()
             .unwrap_or("default")
             .to_string();
 
-        let config = config::Config::infer().await?;
+        let config = with_server(&current_context_name, kube::Config::infer().await?)?;
+        check_tls_verification(&current_context_name, &config, refuse_insecure)?;
         let client = Client::try_from(config)?;
         info!("Using current context: {}", current_context_name);
         clients.push((current_context_name, client));
     } else {
         // Use specified contexts - validate they exist
         for ctx in context_names {
-            let config = config::Config::from_kubeconfig(&config::KubeConfigOptions {
+            let config = kube::Config::from_kubeconfig(&kube::config::KubeConfigOptions {
                 context: Some(ctx.clone()),
                 ..Default::default()
             })
             .await
-            .map_err(|e| anyhow::anyhow!("Context '{}' not found in kubeconfig: {}", ctx, e))?;
+            .map_err(|e| anyhow::anyhow!("Context '{}' not found in kubeconfig: {}", ctx, e))
+            .exit_code(ExitCode::Usage)?;
+            let config = with_server(&ctx, config)?;
+            check_tls_verification(&ctx, &config, refuse_insecure)?;
             let client = Client::try_from(config)?;
             info!("Initialized client for context: {}", ctx);
             clients.push((ctx, client));
@@ -170,87 +586,159 @@ async fn initialize_clients(context_names: Vec<String>) -> anyhow::Result<Vec<(S
     Ok(clients)
 }
 
+/// Warn about (or with --refuse-insecure, reject) a context whose cluster has
+/// `insecure-skip-tls-verify` set, as the client would trust any certificate
+fn check_tls_verification(
+    context: &str,
+    config: &kube::Config,
+    refuse_insecure: bool,
+) -> anyhow::Result<()> {
+    if !config.accept_invalid_certs {
+        return Ok(());
+    }
+    if refuse_insecure {
+        anyhow::bail!(
+            "Context '{}' skips TLS certificate verification (insecure-skip-tls-verify); refusing to connect because of --refuse-insecure",
+            context
+        );
+    }
+    // Printed before the TUI starts, so it is seen in both modes
+    eprintln!(
+        "WARNING: context '{}' skips TLS certificate verification (insecure-skip-tls-verify); the connection to {} can be intercepted",
+        context, config.cluster_url
+    );
+    warn!(
+        "Context '{}' skips TLS certificate verification (insecure-skip-tls-verify)",
+        context
+    );
+    Ok(())
+}
+
 async fn parse_resources_and_selectors(
     clients: &[(String, Client)],
     cli: &Cli,
+    environments: Option<&HashMap<String, String>>,
 ) -> anyhow::Result<Vec<WatchConfig>> {
-    use std::collections::{HashMap, HashSet};
-    use types::ResourceSpec;
-
     // Parse all resource specs
     let mut parsed_specs = Vec::new();
     for res in &cli.resources {
         let spec = parse_resource_spec(res)
-            .map_err(|e| anyhow::anyhow!("Failed to parse resource '{}': {}", res, e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to parse resource '{}': {}", res, e))
+            .exit_code(ExitCode::Usage)?;
         parsed_specs.push(spec);
     }
 
+    for container in cli.container.iter().chain(
+        parsed_specs
+            .iter()
+            .filter_map(|spec| spec.container.as_ref()),
+    ) {
+        if let Some(Err(e)) = container_regex(container) {
+            return Err(anyhow::anyhow!(
+                "Invalid container regex '{}': {}",
+                container,
+                e
+            ))
+            .exit_code(ExitCode::Usage);
+        }
+    }
+
+    if !cli.ordinals.is_empty()
+        && !parsed_specs
+            .iter()
+            .any(|spec| spec.kind.as_deref() == Some("statefulset"))
+    {
+        return Err(anyhow::anyhow!(
+            "--ordinals requires a statefulset resource"
+        ))
+        .exit_code(ExitCode::Usage);
+    }
+
     // Validate: if --context or --namespace flags are used, resource specs can't override them
-    if cli.context.is_some() {
+    if !cli.context.is_empty() {
         for spec in &parsed_specs {
-            if spec.context.is_some() {
-                anyhow::bail!(
+            if let Some(spec_ctx) = &spec.context {
+                return Err(anyhow::anyhow!(
                     "Cannot use both --context flag and context in resource spec '{}/{}/{}/{}'",
-                    spec.context.as_ref().unwrap(),
+                    spec_ctx,
                     spec.namespace.as_deref().unwrap_or("?"),
                     spec.kind.as_deref().unwrap_or("pod"),
                     spec.name
-                );
+                ))
+                .exit_code(ExitCode::Usage);
             }
         }
     }
 
-    if cli.namespace.is_some() {
+    // Resources are found by name, which only identifies them within a namespace
+    if cli.all_namespaces
+        && let Some(spec) = parsed_specs.iter().find(|spec| !is_all_spec(spec))
+    {
+        return Err(anyhow::anyhow!(
+            "Cannot tail '{}' with --all-namespaces: names are ambiguous across namespaces; use -n <NAMESPACE>, or select pods with --selector, --annotation, --kinds or `all`",
+            spec.name
+        ))
+        .exit_code(ExitCode::Usage);
+    }
+
+    if !cli.namespace.is_empty() {
         for spec in &parsed_specs {
-            if spec.namespace.is_some() {
-                anyhow::bail!(
+            if let Some(spec_ns) = &spec.namespace {
+                return Err(anyhow::anyhow!(
                     "Cannot use both --namespace flag and namespace in resource spec '{}/{}'",
-                    spec.namespace.as_ref().unwrap(),
+                    spec_ns,
                     spec.name
-                );
+                ))
+                .exit_code(ExitCode::Usage);
             }
         }
     }
 
-    // Determine default context and namespace
-    let default_context = if let Some(ctx) = &cli.context {
-        ctx.clone()
+    // Determine the default contexts: every --context, else the first client
+    let default_contexts = if !cli.context.is_empty() {
+        cli.context.clone()
     } else if !clients.is_empty() {
-        clients[0].0.clone()
+        vec![clients[0].0.clone()]
     } else {
-        anyhow::bail!("No context available");
+        return Err(anyhow::anyhow!("No context available")).exit_code(ExitCode::Usage);
     };
+    let namespaces = if cli.all_namespaces {
+        vec![Scope::All]
+    } else {
+        cli.namespace
+            .iter()
+            .cloned()
+            .map(Scope::Namespace)
+            .collect()
+    };
+    let defaults = default_namespaces(clients, &default_contexts, &namespaces);
 
-    let default_namespace = cli.namespace.as_deref().unwrap_or("default");
-
-    // Group resources by (context, namespace)
-    let mut grouped: HashMap<(String, String), Vec<ResourceSpec>> = HashMap::new();
-
-    for spec in parsed_specs {
-        let ctx = spec
-            .context
-            .as_deref()
-            .unwrap_or(&default_context)
-            .to_string();
-        let ns = spec
-            .namespace
-            .as_deref()
-            .unwrap_or(default_namespace)
-            .to_string();
-        grouped.entry((ctx, ns)).or_default().push(spec);
-    }
+    // An annotation or kinds filter on its own watches every pod in the namespace
+    // and filters client-side, since neither can be used in API selectors
+    let default_selector = cli.selector.clone().or_else(|| {
+        (cli.resources.is_empty() && (!cli.annotation.is_empty() || !cli.kinds.is_empty()))
+            .then(String::new)
+    });
 
-    // Add label selector as a separate entry if provided
-    if cli.selector.is_some() {
-        grouped
-            .entry((default_context.clone(), default_namespace.to_string()))
-            .or_default();
-    }
+    let grouped = group_resource_specs(parsed_specs, &defaults, default_selector.is_some());
 
     // Build WatchConfig for each (context, namespace) group
     let mut configs = Vec::new();
+    // Set with --strict: explicitly named pods that don't exist
+    let mut missing_pods = Vec::new();
+
+    // Given in stdout mode, where --env can't be changed while running:
+    // contexts outside the environment are never watched
+    let env_scope = cli.env.as_ref().zip(environments);
+
+    for ((ctx, scope), specs) in grouped {
+        if let Some((env, environments)) = env_scope
+            && environments.get(&ctx) != Some(env)
+        {
+            debug!("[{}] Not in environment '{}'; not watching", ctx, env);
+            continue;
+        }
 
-    for ((ctx, ns), specs) in grouped {
         // Find the client for this context
         let client = clients
             .iter()
@@ -259,18 +747,71 @@ async fn parse_resources_and_selectors(
             .ok_or_else(|| anyhow::anyhow!("No client found for context '{}'", ctx))?;
 
         let mut label_selectors = Vec::new();
-        let mut explicit_pods = HashSet::new();
+        let mut explicit_pods: HashMap<String, Vec<Option<String>>> = HashMap::new();
+        let mut add_explicit_pod = |pod: String, container: &Option<String>| {
+            let containers = explicit_pods.entry(pod).or_default();
+            if !containers.contains(container) {
+                containers.push(container.clone());
+            }
+        };
+        let mut followed = Vec::new();
 
         // Process each resource spec in this group
         for spec in specs {
+            // `all` discovers every pod in the namespace
+            if is_all_spec(&spec) {
+                label_selectors.push((String::new(), spec.container.clone()));
+                continue;
+            }
+
+            // -A takes no named resources (checked above)
+            let Scope::Namespace(ns) = &scope else {
+                continue;
+            };
             let kind = spec.kind.as_deref().unwrap_or("pod");
-            let name = &spec.name;
+            let (name, ordinals) = split_ordinals(&spec.name)
+                .map_err(|e| anyhow::anyhow!("Failed to parse resource: {}", e))
+                .exit_code(ExitCode::Usage)?;
+
+            // StatefulSet pods are named <name>-<ordinal>, so selected ordinals are
+            // watched by pod name instead of through the StatefulSet's selector
+            let ordinals = match ordinals {
+                Some(_) if kind != "statefulset" => {
+                    return Err(anyhow::anyhow!(
+                        "Ordinals can only be selected for a statefulset: '{}'",
+                        spec.name
+                    ))
+                    .exit_code(ExitCode::Usage);
+                }
+                Some(ordinals) => Some(ordinals),
+                None if kind == "statefulset" && !cli.ordinals.is_empty() => {
+                    Some(cli.ordinals.clone())
+                }
+                None => None,
+            };
+            if let Some(ordinals) = ordinals {
+                for o in &ordinals {
+                    add_explicit_pod(format!("{}-{}", name, o), &spec.container);
+                }
+                continue;
+            }
+
+            let follow = |selector: Option<String>| FollowedResource {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                container: spec.container.clone(),
+                selector,
+            };
 
             // Try to get selector - be resilient to errors
-            match get_selector_from_resource(client, kind, name, &ns).await {
+            match get_selector_from_resource(client, kind, name, ns, &cli.crd_selector_path).await {
                 Ok(Some(sel)) => {
                     if let Some(sel_str) = selector_to_labels_string(&sel) {
-                        label_selectors.push(sel_str);
+                        if cli.watch_selector_change {
+                            followed.push(follow(Some(sel_str)));
+                        } else {
+                            label_selectors.push((sel_str, spec.container.clone()));
+                        }
                     } else {
                         debug!(
                             "[{}] Selector for {}/{} is empty; skipping",
@@ -280,11 +821,49 @@ async fn parse_resources_and_selectors(
                 }
                 Ok(None) => {
                     if kind == "pod" {
-                        explicit_pods.insert(name.clone());
+                        if cli.strict && !pod_exists(client, name, ns).await? {
+                            let suggestions =
+                                kubernetes::suggest_resource_names(client, kind, name, ns)
+                                    .await
+                                    .unwrap_or_default();
+                            missing_pods.push(match suggestions.first() {
+                                Some(s) => format!("{ctx}/{ns}/{name} (did you mean '{s}'?)"),
+                                None => format!("{ctx}/{ns}/{name}"),
+                            });
+                        }
+                        add_explicit_pod(name.to_string(), &spec.container);
                     }
                 }
                 Err(e) => {
+                    // A name close to an existing one is almost certainly a typo
+                    let not_found = matches!(
+                        e.downcast_ref::<kube::Error>(),
+                        Some(kube::Error::Api(err)) if err.code == 404
+                    );
+                    if not_found {
+                        let suggestions =
+                            kubernetes::suggest_resource_names(client, kind, name, ns)
+                                .await
+                                .unwrap_or_default();
+                        if !suggestions.is_empty() {
+                            return Err(anyhow::anyhow!(
+                                "{} '{}' not found in namespace {}; did you mean {}?",
+                                kind,
+                                name,
+                                ns,
+                                suggestions
+                                    .iter()
+                                    .map(|s| format!("'{}'", s))
+                                    .collect::<Vec<_>>()
+                                    .join(" or ")
+                            ))
+                            .exit_code(ExitCode::NoMatch);
+                        }
+                    }
                     // Don't fail - just log and continue
+                    if cli.watch_selector_change && kind != "pod" {
+                        followed.push(follow(None));
+                    }
                     warn!(
                         "[{}] Could not get selector for {}/{} in namespace {}: {}. Will wait for it to appear.",
                         ctx, kind, name, ns, e
@@ -293,324 +872,2196 @@ async fn parse_resources_and_selectors(
             }
         }
 
-        // Add label selector from CLI if this is the default context/namespace
-        if ctx == default_context
-            && ns == default_namespace
-            && let Some(sel_str) = &cli.selector
+        // Add label selector from CLI if this is a default context/namespace
+        if defaults
+            .iter()
+            .any(|(c, namespaces)| c == &ctx && namespaces.contains(&scope))
+            && let Some(sel_str) = &default_selector
         {
-            label_selectors.push(sel_str.clone());
+            label_selectors.push((sel_str.clone(), None));
         }
 
         configs.push(WatchConfig {
             context: ctx,
-            namespace: ns,
+            scope,
             label_selectors,
             explicit_pods,
+            followed,
         });
     }
 
+    if !missing_pods.is_empty() {
+        return Err(anyhow::anyhow!(
+            "--strict: pods not found: {}",
+            missing_pods.join(", ")
+        ))
+        .exit_code(ExitCode::NoMatch);
+    }
+    if let Some((env, _)) = env_scope
+        && configs.is_empty()
+    {
+        return Err(anyhow::anyhow!(
+            "No context to watch is in environment '{}'",
+            env
+        ))
+        .exit_code(ExitCode::Usage);
+    }
+
     Ok(configs)
 }
 
-async fn run_stdout_mode(
-    clients: Vec<(String, Client)>,
-    cli: Cli,
-    watch_configs: Vec<WatchConfig>,
-    log_tx: mpsc::Sender<LogMessage>,
-    mut log_rx: mpsc::Receiver<LogMessage>,
-) -> anyhow::Result<()> {
-    // Compile grep regex if provided
-    let grep_regex = if let Some(pattern) = &cli.grep {
-        match Regex::new(pattern) {
-            Ok(re) => Some(Arc::new(re)),
-            Err(e) => {
-                error!("Invalid regex pattern '{}': {}", pattern, e);
-                std::process::exit(1);
+/// A --parse json line for stdout: `time LEVEL message key=value …`, with the
+/// level colored and the time and other fields dimmed
+fn styled_json_line(entry: &JsonLine) -> String {
+    let dim = crossterm::style::Color::DarkGrey;
+    let mut parts: Vec<String> = Vec::new();
+    parts.extend(
+        entry
+            .time
+            .as_ref()
+            .map(|t| t.as_str().with(dim).to_string()),
+    );
+    if let Some(level) = &entry.level {
+        let color = entry
+            .severity()
+            .map_or(crossterm::style::Color::Reset, LogLevel::crossterm_color);
+        parts.push(level.to_uppercase().with(color).to_string());
+    }
+    parts.push(entry.message.clone());
+    parts.extend(
+        entry
+            .fields
+            .iter()
+            .map(|f| f.as_str().with(dim).to_string()),
+    );
+    parts.join(" ")
+}
+
+/// Whether the pod exists right now (for --strict)
+async fn pod_exists(client: &Client, name: &str, namespace: &str) -> anyhow::Result<bool> {
+    let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let pod = api
+        .get_opt(name)
+        .await
+        .with_context(|| format!("Could not look up pod {} in namespace {}", name, namespace))?;
+    Ok(pod.is_some())
+}
+
+/// The `all` resource: a bare `all` rather than a pod name (`pod/all` still
+/// names a pod)
+fn is_all_spec(spec: &types::ResourceSpec) -> bool {
+    spec.kind.is_none() && spec.name == "all"
+}
+
+/// Default namespaces of each default context: the -n list if given (every
+/// listed namespace gets the same resources and selector), otherwise the
+/// namespace the context sets in the kubeconfig, which may differ per cluster
+fn default_namespaces(
+    clients: &[(String, Client)],
+    contexts: &[String],
+    cli_namespaces: &[Scope],
+) -> Vec<(String, Vec<Scope>)> {
+    contexts
+        .iter()
+        .map(|ctx| {
+            let namespaces = if !cli_namespaces.is_empty() {
+                cli_namespaces.to_vec()
+            } else {
+                let namespace = clients
+                    .iter()
+                    .find(|(c, _)| c == ctx)
+                    .map_or("default", |(_, client)| client.default_namespace());
+                vec![Scope::Namespace(namespace.to_string())]
+            };
+            (ctx.clone(), namespaces)
+        })
+        .collect()
+}
+
+/// Group resource specs by (context, namespace). Specs without an explicit
+/// context are expanded into every default context, those without an explicit
+/// namespace into each of that context's default namespaces, and the CLI label
+/// selector (if any) gets an entry in each default context/namespace.
+fn group_resource_specs(
+    specs: Vec<types::ResourceSpec>,
+    defaults: &[(String, Vec<Scope>)],
+    has_selector: bool,
+) -> HashMap<(String, Scope), Vec<types::ResourceSpec>> {
+    let mut grouped: HashMap<(String, Scope), Vec<types::ResourceSpec>> = HashMap::new();
+
+    for spec in specs {
+        // A full path names its own context and namespace
+        if let (Some(ctx), Some(ns)) = (&spec.context, &spec.namespace) {
+            grouped
+                .entry((ctx.clone(), Scope::Namespace(ns.clone())))
+                .or_default()
+                .push(spec);
+            continue;
+        }
+        for (ctx, default_namespaces) in defaults {
+            let namespaces = match &spec.namespace {
+                Some(ns) => &[Scope::Namespace(ns.clone())],
+                None => default_namespaces.as_slice(),
+            };
+            for scope in namespaces {
+                grouped
+                    .entry((ctx.clone(), scope.clone()))
+                    .or_default()
+                    .push(spec.clone());
             }
         }
-    } else {
-        None
-    };
+    }
 
-    // Spawn task to print logs
-    tokio::spawn(async move {
-        while let Some(msg) = log_rx.recv().await {
-            if let Some(ref regex) = grep_regex
-                && !regex.is_match(&msg.line)
-            {
-                continue;
+    // Add label selector as a separate entry if provided
+    if has_selector {
+        for (ctx, namespaces) in defaults {
+            for scope in namespaces {
+                grouped.entry((ctx.clone(), scope.clone())).or_default();
             }
-
-            let color_key = format!("{}/{}", msg.cluster, msg.pod_name);
-            let color = get_crossterm_color(&color_key);
-            let prefix = format!(
-                "[{}.{}/{}/{}]",
-                msg.cluster, msg.namespace, msg.pod_name, msg.container_name
-            )
-            .with(color);
-            println!("{} {}", prefix, msg.line);
         }
-    });
+    }
 
-    let handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>> =
-        Arc::new(Mutex::new(HashMap::new()));
+    grouped
+}
 
-    spawn_all_watchers(clients, cli, watch_configs, log_tx, handles, None).await;
+/// Compile the --notify regex
+fn notify_regex(cli: &Cli) -> anyhow::Result<Option<Regex>> {
+    cli.notify
+        .as_deref()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid --notify pattern '{}': {}", pattern, e))
+                .exit_code(ExitCode::Usage)
+        })
+        .transpose()
+}
 
-    tokio::signal::ctrl_c().await?;
-    Ok(())
+fn exclude_regex(cli: &Cli) -> anyhow::Result<Option<Regex>> {
+    cli.exclude
+        .as_deref()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid --exclude pattern '{}': {}", pattern, e))
+                .exit_code(ExitCode::Usage)
+        })
+        .transpose()
 }
 
-async fn run_tui_mode(
+/// Print logs through a buffer flushed every --flush-interval (zero flushes
+/// each line). On shutdown, lines already received are written and flushed.
+async fn print_lines<W: Write>(
+    mut out: std::io::BufWriter<W>,
+    mut log_rx: mpsc::Receiver<LogMessage>,
+    mut write_line: impl FnMut(&mut dyn Write, LogMessage) -> std::io::Result<()>,
+    flush_interval: Duration,
+    mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+) -> std::io::Result<()> {
+    let mut ticker = (!flush_interval.is_zero()).then(|| {
+        let mut ticker = tokio::time::interval(flush_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker
+    });
+    loop {
+        tokio::select! {
+            msg = log_rx.recv() => {
+                let Some(msg) = msg else { break };
+                write_line(&mut out, msg)?;
+                if ticker.is_none() {
+                    out.flush()?;
+                }
+            }
+            _ = async {
+                match ticker.as_mut() {
+                    Some(ticker) => ticker.tick().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                out.flush()?;
+            }
+            _ = &mut shutdown_rx => {
+                while let Ok(msg) = log_rx.try_recv() {
+                    write_line(&mut out, msg)?;
+                }
+                break;
+            }
+        }
+    }
+    out.flush()
+}
+
+async fn run_stdout_mode(
     clients: Vec<(String, Client)>,
     cli: Cli,
+    environments: HashMap<String, String>,
     watch_configs: Vec<WatchConfig>,
     log_tx: mpsc::Sender<LogMessage>,
     log_rx: mpsc::Receiver<LogMessage>,
+    options: TailOptions,
 ) -> anyhow::Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Compile grep regex if provided
+    let grep_regex = cli
+        .grep
+        .as_deref()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map(Arc::new)
+                .map_err(|e| anyhow::anyhow!("Invalid --grep pattern '{}': {}", pattern, e))
+                .exit_code(ExitCode::Usage)
+        })
+        .transpose()?;
+
+    // With --compact and no explicit width, the column grows to fit the
+    // longest prefix seen so far, up to a cap
+    let compact = cli.compact;
+    let mut compact_width = cli.compact_width.unwrap_or(0);
+    let fixed_width = cli.compact_width.is_some();
+    let template = cli.template.clone();
+    let prefix_format = cli.prefix_format.clone();
+    let parse_json = cli.parse == Some(cli::ParseFormat::Json);
+    let json_message_key = cli.json_message_key.clone();
+    let json = cli.output == OutputFormat::Json;
+    let exclude_regex = exclude_regex(&cli)?;
+    let mut notifier = notify_regex(&cli)?.map(Notifier::new);
+    let mut sequence = cli.seq.then(LineSequence::default);
+    let min_level = cli.level;
+
+    let write_line = move |out: &mut dyn Write, msg: LogMessage| -> std::io::Result<()> {
+        // On stderr, so the bell doesn't end up in piped output
+        if let Some(notifier) = &mut notifier
+            && notifier.check(&msg.line, std::time::Instant::now())
+        {
+            eprint!("\x07");
+        }
 
-    // Create app state
-    let mut app = App::new(cli.buffer_size);
+        if let Some(ref regex) = grep_regex
+            && !regex.is_match(&msg.line)
+        {
+            return Ok(());
+        }
+        if let Some(regex) = &exclude_regex
+            && regex.is_match(&msg.line)
+        {
+            return Ok(());
+        }
+        // Lines without a level, such as stack frames, are kept
+        if let Some(min) = min_level
+            && detect_level(&msg.line).is_some_and(|level| level < min)
+        {
+            return Ok(());
+        }
+        let env = environments.get(&msg.cluster);
 
-    // Create event channel
-    let (event_tx, mut event_rx) = mpsc::channel::<AppEvent>(100);
+        // Numbered after filtering, so only lost lines leave gaps
+        let seq = sequence.as_mut().map(|sequence| sequence.next(&msg));
+        if json {
+            return writeln!(out, "{}", msg.to_json(seq));
+        }
+        if let Some(seq) = seq {
+            write!(out, "{} ", seq)?;
+        }
 
-    // Spawn keyboard event loop
-    let event_tx_clone = event_tx.clone();
-    tokio::spawn(async move {
-        ui::events::event_loop(event_tx_clone).await;
-    });
+        if let Some(template) = &template {
+            return writeln!(out, "{}", template.render(&msg));
+        }
 
-    // Convert log messages to app events
-    let event_tx_clone = event_tx.clone();
-    tokio::spawn(async move {
-        let mut log_rx = log_rx;
-        while let Some(msg) = log_rx.recv().await {
-            if event_tx_clone
-                .send(AppEvent::LogMessage(msg))
-                .await
-                .is_err()
-            {
-                break;
+        let color_key = format!("{}/{}", msg.cluster, msg.pod_name);
+        let color = get_crossterm_color(&color_key);
+        let mut prefix = match &prefix_format {
+            Some(format) => format.render(&msg),
+            None => format!(
+                "[{}{}.{}/{}/{}]",
+                env_tag(env.map(String::as_str)),
+                msg.cluster,
+                msg.namespace,
+                msg.pod_name,
+                msg.container_name
+            ),
+        };
+        if compact {
+            if !fixed_width {
+                compact_width = compact_width
+                    .max(prefix.chars().count())
+                    .min(MAX_COMPACT_WIDTH);
             }
+            prefix = fit_to_width(&prefix, compact_width);
+        }
+        match parse_json
+            .then(|| parse_json_line(&msg.line, json_message_key.as_deref()))
+            .flatten()
+        {
+            Some(entry) => writeln!(out, "{} {}", prefix.with(color), styled_json_line(&entry)),
+            None => writeln!(out, "{} {}", prefix.with(color), msg.line),
+        }
+    };
+
+    let flush_interval = cli.flush_interval;
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let printer = tokio::spawn(async move {
+        let out = std::io::BufWriter::with_capacity(STDOUT_BUFFER, std::io::stdout());
+        let result = print_lines(out, log_rx, write_line, flush_interval, shutdown_rx).await;
+        // A closed pipe (e.g. `| head`) just ends the output
+        if let Err(e) = result {
+            debug!("Stopped writing to stdout: {}", e);
         }
     });
 
     let handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>> =
         Arc::new(Mutex::new(HashMap::new()));
-
+    let completion = cli.wait.then(|| Arc::new(CompletionTracker::default()));
+
+    let channel_stats = options.channel_stats.clone();
+    let stream_states = options.stream_states.clone();
+    let watchers = options.watchers.clone();
+    let tail_options = options.clone();
+    let exit_when_done = cli.exit_when_done;
+    let deadline = session_deadline(cli.duration);
     spawn_all_watchers(
         clients,
         cli,
         watch_configs,
         log_tx,
+        options,
         handles,
-        Some(event_tx.clone()),
+        None,
+        completion.clone(),
+        None,
     )
     .await;
 
-    // Main TUI event loop with render throttling
-    let mut should_quit = false;
-    let mut render_interval = tokio::time::interval(std::time::Duration::from_millis(16)); // ~60 FPS
-    render_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
-    while !should_quit {
+    let mut watchers_failed = None;
+    let completed = tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            false
+        }
+        _ = wait_for_completion(&completion) => true,
+        _ = deadline => {
+            info!("--duration reached, stopping");
+            false
+        }
+        err = watchers.wait_all_failed() => {
+            watchers_failed = Some(err);
+            false
+        }
+        _ = async {
+            wait_nothing_left(&tail_options).await;
+            eprintln!("{}", NOTHING_LEFT);
+            // Without --exit-when-done, wait for Ctrl-C as before
+            if !exit_when_done {
+                std::future::pending::<()>().await;
+            }
+        } => false,
+    };
+    let _ = shutdown_tx.send(());
+    let _ = printer.await;
+    report_truncated(&channel_stats);
+    report_received(&stream_states);
+    if let Some(err) = watchers_failed {
+        return Err(err);
+    }
+    if completed {
+        exit_with_summary(&completion);
+    }
+    Ok(())
+}
+
+/// Tell how many lines --max-line-bytes cut short, if any
+fn report_truncated(stats: &ChannelStats) {
+    let truncated = stats.truncated();
+    if truncated > 0 {
+        eprintln!(
+            "{} line{} longer than --max-line-bytes truncated",
+            truncated,
+            if truncated == 1 { " was" } else { "s were" }
+        );
+    }
+}
+
+/// Total log data read, for the exit summary
+fn report_received(states: &StreamStates) {
+    let (bytes, streams) = states.received_total();
+    if bytes > 0 {
+        let wire = states
+            .wire_total()
+            .map(|wire| format!(" ({} on the wire)", format_bytes(wire)))
+            .unwrap_or_default();
+        eprintln!(
+            "Received {} of logs{} from {} stream{}",
+            format_bytes(bytes),
+            wire,
+            streams,
+            if streams == 1 { "" } else { "s" }
+        );
+    }
+}
+
+/// --since as whole seconds for `sinceSeconds`, which must be positive;
+/// fractions round up so nothing newer is left out
+fn since_seconds(since: Duration) -> i64 {
+    (since.as_secs_f64().ceil() as i64).max(1)
+}
+
+/// Resolves once the --duration has passed; never without --duration
+async fn session_deadline(duration: Option<Duration>) {
+    match duration {
+        Some(duration) => tokio::time::sleep(duration).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Resolves when every pod tracked for --wait has finished; never without --wait
+async fn wait_for_completion(completion: &Option<Arc<CompletionTracker>>) {
+    match completion {
+        Some(tracker) => tracker.wait(LOG_DRAIN).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// How often to check whether anything is left to tail
+const NOTHING_LEFT_CHECK: Duration = Duration::from_secs(1);
+
+/// Shown once tailing has ended for good
+const NOTHING_LEFT: &str = "All streams ended, nothing left to tail";
+
+/// Whether tailing has ended for good: no stream is open or reconnecting and
+/// no watcher can find more pods (e.g. every named pod was deleted). A
+/// selector watcher with no pods yet is idle, not ended.
+fn nothing_left(options: &TailOptions) -> bool {
+    options.watchers.waiting() == 0 && options.stream_states.active() == 0
+}
+
+/// Resolves once there is nothing left to tail
+async fn wait_nothing_left(options: &TailOptions) {
+    let mut ticker = tokio::time::interval(NOTHING_LEFT_CHECK);
+    loop {
+        ticker.tick().await;
+        if nothing_left(options) {
+            return;
+        }
+    }
+}
+
+/// Print how each container ended and exit with the combined result
+fn exit_with_summary(completion: &Option<Arc<CompletionTracker>>) {
+    let Some(tracker) = completion else {
+        return;
+    };
+    let (lines, success) = tracker.summary();
+    for line in lines {
+        eprintln!("{}", line);
+    }
+    let code = if success {
+        ExitCode::Success
+    } else {
+        ExitCode::Failure
+    };
+    std::process::exit(code as i32);
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_tui_mode(
+    clients: Vec<(String, Client)>,
+    cli: Cli,
+    config: Config,
+    environments: HashMap<String, String>,
+    watch_configs: Vec<WatchConfig>,
+    log_tx: mpsc::Sender<LogMessage>,
+    log_rx: mpsc::Receiver<LogMessage>,
+    options: TailOptions,
+) -> anyhow::Result<()> {
+    // Compile highlight rules before taking over the terminal so errors stay visible
+    let highlights = config.highlight_rules()?;
+    let access_logs = if cli.access_logs {
+        Some(config.access_log_schemas()?)
+    } else {
+        None
+    };
+    // --tee-stdout lines are written off the UI path, by a blocking task
+    let mut tee = match &cli.tee_stdout {
+        Some(target) => {
+            let out = std::io::LineWriter::new(open_tee(target.as_deref())?);
+            let (tee_tx, tee_rx) = mpsc::channel::<LogMessage>(cli.buffer_size);
+            let environments = environments.clone();
+            tokio::task::spawn_blocking(move || write_tee(out, tee_rx, &environments));
+            Some(tee_tx)
+        }
+        None => None,
+    };
+    let resumed = match (&cli.checkpoint, cli.resume) {
+        (Some(path), true) => checkpoint::Checkpoint::load(path)?,
+        _ => None,
+    };
+    let notifier = notify_regex(&cli)?.map(Notifier::new);
+    let exclude = exclude_regex(&cli)?;
+
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create app state
+    let mut app = App::new(cli.buffer_size);
+    app.show_image = cli.show_image;
+    app.show_qos = cli.show_qos;
+    app.show_sparklines = cli.sparklines;
+    app.max_line_width = cli.max_line_width;
+    app.level_colors = !cli.no_level_colors;
+    app.prefix_format = cli.prefix_format.clone();
+    if cli.parse == Some(cli::ParseFormat::Json) {
+        app.json_logs = Some(JsonLogFormat {
+            message_key: cli.json_message_key.clone(),
+        });
+    }
+    app.exclude_pattern = exclude;
+    if let Some(width) = cli.wrap_width {
+        app.wrap_width = width;
+        app.fixed_wrap = true;
+    }
+    app.tombstone_retention = cli
+        .tombstones
+        .map(|retention| chrono::Duration::from_std(retention).unwrap_or(chrono::Duration::MAX));
+    app.tombstone_lines = cli.tombstone_lines;
+    app.verbose = cli.verbose;
+    app.tail_lines = options.tail;
+    app.since_seconds = options.since_seconds;
+    app.auto_scroll_resume = cli.auto_scroll_resume;
+    app.trace_field = Some(cli.trace_field.clone());
+    app.elide_frames = cli.elide_frames.clone();
+    app.previous = cli.previous;
+    app.environments = environments;
+    app.env_filter = cli.env.clone();
+    if let Some(level) = cli.level {
+        app.level_threshold = level;
+        app.min_level = Some(level);
+    }
+    if let Some(resumed) = resumed {
+        let restored = resumed.restore(&mut app);
+        app.set_status_message(format!("Restored {} lines from checkpoint", restored));
+    }
+    let channel_stats = options.channel_stats.clone();
+    app.channel_stats = Some(channel_stats.clone());
+    let watchers = options.watchers.clone();
+    let stream_states = options.stream_states.clone();
+    app.stream_states = Some(stream_states.clone());
+    app.watch_summary = watch_configs
+        .iter()
+        .map(|c| {
+            // `target:container` for targets with their own container
+            let with_container = |target: &str, container: &Option<String>| match container {
+                Some(container) => format!("{}:{}", target, container),
+                None => target.to_string(),
+            };
+            let selectors: Vec<_> = c
+                .label_selectors
+                .iter()
+                .map(|(s, container)| with_container(s, container))
+                .collect();
+            let pods: Vec<_> = c
+                .explicit_pods
+                .iter()
+                .flat_map(|(p, containers)| containers.iter().map(|c| with_container(p, c)))
+                .collect();
+            let followed: Vec<_> = c
+                .followed
+                .iter()
+                .map(|r| with_container(&format!("{}/{}", r.kind, r.name), &r.container))
+                .collect();
+            format!(
+                "{}/{} selectors={:?} pods={:?} followed={:?}",
+                c.context, c.scope, selectors, pods, followed
+            )
+        })
+        .collect();
+
+    // Failed streams restarted from the sidebar
+    let (respawn_tx, respawn_rx) = mpsc::unbounded_channel::<PodKey>();
+    app.respawn_tx = Some(respawn_tx);
+    app.highlights = highlights;
+    app.access_logs = access_logs;
+    app.notifier = notifier;
+
+    // Create event channel
+    let (event_tx, mut event_rx) = mpsc::channel::<AppEvent>(100);
+
+    // Lines piped to --pipe-cmd with `|`
+    if let Some(cmd) = cli.pipe_cmd.clone() {
+        let (pipe_tx, pipe_rx) = mpsc::unbounded_channel::<Vec<String>>();
+        app.pipe_cmd = Some(cmd.clone());
+        app.pipe_tx = Some(pipe_tx);
+        tokio::spawn(run_pipe_commands(cmd, pipe_rx, event_tx.clone()));
+    }
+    app.open_cmd = cli.open_cmd.clone();
+
+    // Spawn keyboard event loop
+    let event_tx_clone = event_tx.clone();
+    let (suspend_tx, suspend_rx) = tokio::sync::watch::channel(false);
+    let (paused_tx, mut paused_rx) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        ui::events::event_loop(event_tx_clone, suspend_rx, paused_tx).await;
+    });
+
+    // Convert log messages to app events
+    let event_tx_clone = event_tx.clone();
+    tokio::spawn(async move {
+        let mut log_rx = log_rx;
+        while let Some(msg) = log_rx.recv().await {
+            // A tee that can't keep up loses lines rather than slowing the TUI
+            if let Some(tee_tx) = &tee
+                && let Err(mpsc::error::TrySendError::Closed(_)) = tee_tx.try_send(msg.clone())
+            {
+                tee = None;
+            }
+            if event_tx_clone
+                .send(AppEvent::LogMessage(msg))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let checkpoint_path = cli.checkpoint.clone();
+    let checkpoint_every = cli.checkpoint_interval;
+    let session_duration = cli.duration;
+    let handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let completion = cli.wait.then(|| Arc::new(CompletionTracker::default()));
+
+    let tail_options = options.clone();
+    let nothing_left_tx = event_tx.clone();
+    spawn_all_watchers(
+        clients,
+        cli,
+        watch_configs,
+        log_tx,
+        options,
+        handles,
+        Some(event_tx.clone()),
+        completion.clone(),
+        Some(respawn_rx),
+    )
+    .await;
+
+    // Tell "idle but watching" apart from "nothing left to tail", once the
+    // watchers are registered: before that there is nothing to wait for yet
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(NOTHING_LEFT_CHECK);
+        let mut ended = false;
+        loop {
+            ticker.tick().await;
+            if nothing_left(&tail_options) == ended {
+                continue;
+            }
+            ended = !ended;
+            if nothing_left_tx
+                .send(AppEvent::NothingLeft(ended))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    // Polled across loop iterations so the settle delay is not restarted by every event
+    let completion_wait = wait_for_completion(&completion);
+    tokio::pin!(completion_wait);
+    let mut completed = false;
+    let watchers_failed_wait = watchers.wait_all_failed();
+    tokio::pin!(watchers_failed_wait);
+    let mut watchers_failed = None;
+    let deadline = session_deadline(session_duration);
+    tokio::pin!(deadline);
+
+    // Periodic snapshots of the buffer with --checkpoint
+    let mut checkpoint_interval =
+        tokio::time::interval(checkpoint_every.max(std::time::Duration::from_secs(1)));
+    checkpoint_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    checkpoint_interval.tick().await;
+    // The snapshot being written, off the UI loop
+    let mut checkpoint_write: Option<tokio::task::JoinHandle<anyhow::Result<()>>> = None;
+
+    // Main TUI event loop. Frames are drawn at up to 60 FPS while the buffer
+    // is changing and at a few FPS when idle, so clocks and ages still move.
+    let mut should_quit = false;
+    let mut last_render = tokio::time::Instant::now();
+    let render_due = tokio::time::sleep(IDLE_RENDER_INTERVAL);
+    tokio::pin!(render_due);
+
+    while !should_quit {
         tokio::select! {
-            _ = render_interval.tick() => {
-                // Render at fixed interval
+            _ = &mut render_due => {
+                app.take_changed();
                 ui::renderer::render(&mut terminal, &mut app)?;
+                last_render = tokio::time::Instant::now();
+                render_due.as_mut().reset(last_render + IDLE_RENDER_INTERVAL);
+                if app.take_bell() {
+                    execute!(terminal.backend_mut(), crossterm::style::Print("\x07"))?;
+                }
+                // OSC 52: the terminal puts the text on the system clipboard
+                if let Some(text) = app.take_clipboard() {
+                    let osc = format!("\x1b]52;c;{}\x07", BASE64_STANDARD.encode(text));
+                    execute!(terminal.backend_mut(), crossterm::style::Print(osc))?;
+                }
             }
             event = event_rx.recv() => {
                 if let Some(event) = event {
-                    // Process this event
-                    match event {
-                        AppEvent::Key(key) => {
-                            should_quit = !ui::events::handle_key_event(&mut app, key);
-                            // Render immediately after keyboard input for responsiveness
-                            ui::renderer::render(&mut terminal, &mut app)?;
-                        }
-                        AppEvent::LogMessage(msg) => {
-                            app.add_log(msg);
-                            // Batch process additional log messages without blocking
-                            while let Ok(AppEvent::LogMessage(msg)) = event_rx.try_recv() {
-                                app.add_log(msg);
-                            }
-                        }
-                        AppEvent::PodUpdate(update) => match update.event_type {
-                            ui::events::PodEventType::Added | ui::events::PodEventType::Updated => {
-                                app.add_pod(update.info);
+                    should_quit = !handle_app_event(&mut terminal, &mut app, event)?;
+                    // Batch process already-queued events without blocking. Events are
+                    // handled strictly in channel order (nothing is skipped or set aside),
+                    // so lines from one container reach add_log in emission order.
+                    while !should_quit && let Ok(event) = event_rx.try_recv() {
+                        should_quit = !handle_app_event(&mut terminal, &mut app, event)?;
+                    }
+                    if let Some(cmd) = app.take_open_command() {
+                        let msg =
+                            run_suspended(&mut terminal, &suspend_tx, &mut paused_rx, cmd).await?;
+                        app.set_status_message(msg);
+                        ui::renderer::render(&mut terminal, &mut app)?;
+                    }
+                    // Pull the next frame forward while the buffer is changing
+                    let due = last_render + render_interval(app.take_changed());
+                    if due < render_due.deadline() {
+                        render_due.as_mut().reset(due);
+                    }
+                }
+            }
+            // A write slower than the interval delays the next snapshot
+            _ = checkpoint_interval.tick(), if checkpoint_path.is_some() && checkpoint_write.is_none() => {
+                if let Some(path) = checkpoint_path.clone() {
+                    let snapshot = checkpoint::Snapshot::of(&app);
+                    checkpoint_write =
+                        Some(tokio::task::spawn_blocking(move || snapshot.write(&path)));
+                }
+            }
+            written = async { checkpoint_write.as_mut().unwrap().await }, if checkpoint_write.is_some() => {
+                checkpoint_write = None;
+                if let Err(e) = written.map_err(anyhow::Error::from).and_then(|r| r) {
+                    warn!("Could not write checkpoint: {:#}", e);
+                    app.set_status_message(format!("Could not write checkpoint: {:#}", e));
+                }
+            }
+            _ = &mut completion_wait => {
+                completed = true;
+                should_quit = true;
+            }
+            err = &mut watchers_failed_wait => {
+                watchers_failed = Some(err);
+                should_quit = true;
+            }
+            _ = &mut deadline => {
+                info!("--duration reached, stopping");
+                should_quit = true;
+            }
+        }
+    }
+
+    // A last snapshot on the way out, once any periodic one is done with the file
+    if let Some(write) = checkpoint_write {
+        let _ = write.await;
+    }
+    if let Some(path) = &checkpoint_path
+        && let Err(e) = checkpoint::write(path, &app)
+    {
+        warn!("Could not write checkpoint: {:#}", e);
+    }
+
+    // Cleanup terminal
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    report_truncated(&channel_stats);
+    report_received(&stream_states);
+    if let Some(err) = watchers_failed {
+        return Err(err);
+    }
+    if completed {
+        exit_with_summary(&completion);
+    }
+    Ok(())
+}
+
+/// Write the lines received for --tee-stdout until the TUI exits, or stop at
+/// the first failed write
+fn write_tee(
+    mut out: impl Write,
+    mut tee_rx: mpsc::Receiver<LogMessage>,
+    environments: &HashMap<String, String>,
+) {
+    while let Some(msg) = tee_rx.blocking_recv() {
+        let written = writeln!(
+            out,
+            "[{}{}.{}/{}/{}] {}",
+            env_tag(environments.get(&msg.cluster).map(String::as_str)),
+            msg.cluster,
+            msg.namespace,
+            msg.pod_name,
+            msg.container_name,
+            msg.line
+        );
+        if let Err(e) = written {
+            warn!("Could not write to --tee-stdout, stopping it: {}", e);
+            return;
+        }
+    }
+}
+
+/// Where --tee-stdout writes: the given file (truncated), or file descriptor 3,
+/// which the TUI leaves alone, when no path is given
+fn open_tee(path: Option<&std::path::Path>) -> anyhow::Result<std::fs::File> {
+    match path {
+        Some(path) => std::fs::File::create(path)
+            .with_context(|| format!("Cannot open --tee-stdout file {}", path.display())),
+        None => std::fs::OpenOptions::new()
+            .append(true)
+            .open("/dev/fd/3")
+            .context(
+                "--tee-stdout writes to file descriptor 3, which is not open; redirect it (e.g. `3>capture.log`) or use --tee-stdout=PATH",
+            ),
+    }
+}
+
+/// Shortest gap between frames while the buffer is changing (~60 FPS)
+const ACTIVE_RENDER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+/// Gap between frames when nothing changed (4 FPS)
+const IDLE_RENDER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// How long after the last frame the next one is due
+fn render_interval(changed: bool) -> std::time::Duration {
+    if changed {
+        ACTIVE_RENDER_INTERVAL
+    } else {
+        IDLE_RENDER_INTERVAL
+    }
+}
+
+/// Apply a single event to the app state. Returns false when the app should quit.
+fn handle_app_event<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    event: AppEvent,
+) -> std::io::Result<bool> {
+    match event {
+        AppEvent::Key(key) => {
+            let keep_running = ui::events::handle_key_event(app, key);
+            // Keeps a pending bell or clipboard write on the fast cadence
+            app.mark_changed();
+            // Render immediately after keyboard input for responsiveness
+            ui::renderer::render(terminal, app)?;
+            return Ok(keep_running);
+        }
+        AppEvent::LogMessage(msg) => {
+            app.add_log(msg);
+            app.mark_changed();
+        }
+        AppEvent::PodUpdate(update) => match update.event_type {
+            ui::events::PodEventType::Added | ui::events::PodEventType::Updated => {
+                app.add_pod(update.info);
+                app.mark_changed();
+            }
+            ui::events::PodEventType::Deleted(key) => {
+                app.remove_pod(&key);
+                app.mark_changed();
+            }
+        },
+        AppEvent::PodWarning(warning) => {
+            app.add_pod_warning(warning);
+            app.mark_changed();
+        }
+        AppEvent::NodeZone {
+            cluster,
+            node,
+            zone,
+        } => {
+            app.set_node_zone(&cluster, &node, zone);
+            app.mark_changed();
+        }
+        AppEvent::PipeFinished(msg) => {
+            app.set_status_message(msg);
+            app.mark_changed();
+        }
+        AppEvent::WatchIdle { watch, idle } => {
+            app.set_watch_idle(watch, idle);
+            app.mark_changed();
+        }
+        AppEvent::NothingLeft(ended) => {
+            app.set_nothing_left(ended);
+            app.mark_changed();
+        }
+        AppEvent::WatchReconnecting {
+            cluster,
+            watch,
+            reconnecting,
+        } => {
+            app.set_watch_reconnecting(cluster, watch, reconnecting);
+            app.mark_changed();
+        }
+        AppEvent::Tick => {
+            app.update_stats();
+        }
+        AppEvent::Quit => {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Run an --open-cmd in the terminal, with the TUI put away and keyboard
+/// reading paused until it exits. The command starts only once the keyboard
+/// reader is gone, so it gets every key. Returns the message for the status bar.
+async fn run_suspended<B: ratatui::backend::Backend + Write>(
+    terminal: &mut Terminal<B>,
+    suspend_tx: &tokio::sync::watch::Sender<bool>,
+    paused_rx: &mut tokio::sync::watch::Receiver<bool>,
+    cmd: String,
+) -> anyhow::Result<String> {
+    let _ = suspend_tx.send(true);
+    // An error means the keyboard task is gone, which reads nothing either
+    let _ = paused_rx.wait_for(|paused| *paused).await;
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    let command = cmd.clone();
+    let status = tokio::task::spawn_blocking(move || {
+        std::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .status()
+    })
+    .await
+    .map_err(std::io::Error::other)
+    .and_then(|status| status);
+
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+    let _ = suspend_tx.send(false);
+
+    Ok(match status {
+        Ok(status) if status.success() => format!("Ran `{}`", cmd),
+        Ok(status) => match status.code() {
+            Some(code) => format!("`{}` exited with {}", cmd, code),
+            None => format!("`{}` was killed", cmd),
+        },
+        Err(e) => format!("Could not run `{}`: {}", cmd, e),
+    })
+}
+
+/// Run the --pipe-cmd for every batch of lines piped from the TUI, one at a
+/// time, reporting each result to the status bar
+async fn run_pipe_commands(
+    cmd: String,
+    mut pipe_rx: mpsc::UnboundedReceiver<Vec<String>>,
+    event_tx: mpsc::Sender<AppEvent>,
+) {
+    while let Some(lines) = pipe_rx.recv().await {
+        let msg = pipe_to_command(&cmd, lines).await;
+        if event_tx.send(AppEvent::PipeFinished(msg)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Feed lines to a shell command on stdin and describe the outcome. Output of a
+/// single short line is shown inline; anything longer is saved to a file
+/// rather than rendered in the status bar.
+async fn pipe_to_command(cmd: &str, lines: Vec<String>) -> String {
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = match tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return format!("Could not run `{}`: {}", cmd, e),
+    };
+
+    // Write from a separate task so a command that doesn't read all of its
+    // input can't deadlock against its own output
+    let count = lines.len();
+    if let Some(mut stdin) = child.stdin.take() {
+        tokio::spawn(async move {
+            for line in lines {
+                if stdin.write_all(line.as_bytes()).await.is_err()
+                    || stdin.write_all(b"\n").await.is_err()
+                {
+                    break;
+                }
+            }
+        });
+    }
+
+    let output = match child.wait_with_output().await {
+        Ok(output) => output,
+        Err(e) => return format!("`{}` failed: {}", cmd, e),
+    };
+    let status = match output.status.code() {
+        Some(code) => format!("exit {}", code),
+        None => "killed".to_string(),
+    };
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    let text = text.trim();
+
+    if text.is_empty() {
+        format!("`{}`: {} ({} lines piped)", cmd, status, count)
+    } else if !text.contains('\n') && text.chars().count() <= 80 {
+        format!("`{}`: {} ({})", cmd, text, status)
+    } else {
+        let path = std::env::temp_dir().join(format!(
+            "kubectl-tail-pipe-{}.txt",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        match std::fs::write(&path, text) {
+            Ok(()) => format!(
+                "`{}`: {}, {} lines of output saved to {}",
+                cmd,
+                status,
+                text.lines().count(),
+                path.display()
+            ),
+            Err(e) => format!("`{}`: {}, could not save output: {}", cmd, status, e),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn spawn_all_watchers(
+    clients: Vec<(String, Client)>,
+    cli: Cli,
+    watch_configs: Vec<WatchConfig>,
+    log_tx: mpsc::Sender<LogMessage>,
+    options: TailOptions,
+    handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    event_tx: Option<mpsc::Sender<AppEvent>>,
+    completion: Option<Arc<CompletionTracker>>,
+    respawn_rx: Option<mpsc::UnboundedReceiver<PodKey>>,
+) {
+    // List-based polling replaces the watch API when --poll is given
+    let poll_interval = cli.poll.then_some(cli.poll_interval);
+
+    let annotations = Arc::new(cli.annotation.clone());
+    let kinds = Arc::new(
+        cli.kinds
+            .iter()
+            .map(|k| k.to_lowercase())
+            .collect::<Vec<_>>(),
+    );
+    let qos = Arc::new(cli.qos.clone());
+    let crd_selectors = Arc::new(cli.crd_selector_path.clone());
+    let exclude_containers = Arc::new(cli.exclude_container.clone());
+    let group_workloads = !cli.kinds.is_empty()
+        || cli
+            .resources
+            .iter()
+            .any(|r| parse_resource_spec(r).is_ok_and(|spec| is_all_spec(&spec)));
+
+    // Group configs by context for easy client lookup
+    let client_map: std::collections::HashMap<_, _> = clients.into_iter().collect();
+
+    // Warning events are only shown as sidebar badges, so only watched in TUI mode.
+    // Several configs may share a namespace; watch each namespace once.
+    let mut event_namespaces = std::collections::HashSet::new();
+
+    // Tail context of every watched cluster/namespace, for restarting streams
+    let mut contexts: HashMap<(String, Scope), TailContext> = HashMap::new();
+
+    // Node zones are only shown in the TUI; one resolver (and cache) per cluster
+    let mut zone_resolvers: HashMap<String, Arc<ZoneResolver>> = HashMap::new();
+
+    for config in watch_configs {
+        let client = match client_map.get(&config.context) {
+            Some(c) => c.clone(),
+            None => {
+                warn!("[{}] No client found for context, skipping", config.context);
+                continue;
+            }
+        };
+        let zones = event_tx.as_ref().map(|event_tx| {
+            zone_resolvers
+                .entry(config.context.clone())
+                .or_insert_with(|| {
+                    Arc::new(ZoneResolver::new(
+                        client.clone(),
+                        config.context.clone(),
+                        event_tx.clone(),
+                    ))
+                })
+                .clone()
+        });
+
+        let ctx = TailContext {
+            client: client.clone(),
+            cluster: config.context.clone(),
+            scope: config.scope.clone(),
+            container: cli.container.clone(),
+            tx: log_tx.clone(),
+            options: options.clone(),
+            annotations: annotations.clone(),
+            completion: completion.clone(),
+            newest: cli.newest.map(|n| n as usize),
+            running_only: cli.container_state == cli::ContainerState::Running,
+            extra: ExtraContainers {
+                init: cli.include_init,
+                ephemeral: cli.include_ephemeral,
+            },
+            exclude_containers: exclude_containers.clone(),
+            workloads: group_workloads.then(|| Arc::new(WorkloadResolver::new(client.clone()))),
+            kinds: kinds.clone(),
+            qos: qos.clone(),
+            zones: zones.clone(),
+            crd_selectors: crd_selectors.clone(),
+            named_pod: None,
+        };
+        contexts.insert((config.context.clone(), config.scope.clone()), ctx.clone());
+
+        if cli.watch_events
+            && let Some(event_tx) = &event_tx
+            && event_namespaces.insert((config.context.clone(), config.scope.clone()))
+        {
+            let events_api: Api<K8sEvent> = config.scope.api(client.clone());
+            let cluster = config.context.clone();
+            let event_tx = event_tx.clone();
+            tokio::spawn(async move {
+                if let Err(err) = watch_warning_events(events_api, cluster.clone(), event_tx).await
+                {
+                    warn!("[{}] Event watcher stopped: {}", cluster, err);
+                }
+            });
+        }
+
+        // Spawn watchers for label selectors
+        for (selector, container) in &config.label_selectors {
+            spawn_selector_watcher(
+                ctx.for_container(container),
+                selector.clone(),
+                handles.clone(),
+                event_tx.clone(),
+                poll_interval,
+            );
+        }
+
+        // Resources whose selector may be edited while tailing
+        for resource in &config.followed {
+            let following = ctx.options.watchers.following();
+            let follow = follow_resource_selector(
+                resource.clone(),
+                ctx.for_container(&resource.container),
+                handles.clone(),
+                event_tx.clone(),
+                poll_interval,
+                cli.selector_refresh,
+            );
+            tokio::spawn(async move {
+                follow.await;
+                drop(following);
+            });
+        }
+
+        // Spawn watchers for explicit pods, one per container targeted
+        let explicit_pods = config
+            .explicit_pods
+            .iter()
+            .flat_map(|(pod, containers)| containers.iter().map(move |c| (pod, c)));
+        for (pod_name, container) in explicit_pods {
+            let handles = handles.clone();
+            let watcher = ctx.options.watchers.started();
+            let ctx = TailContext {
+                named_pod: Some(watcher.id()),
+                ..ctx.for_container(container)
+            };
+            let pod_name = pod_name.clone();
+            let event_tx = event_tx.clone();
+
+            tokio::spawn(async move {
+                let field_selector = format!("metadata.name={}", pod_name);
+                let target = format!("pod {}", pod_name);
+                let cluster_name = ctx.cluster.clone();
+                debug!("[{}] Starting watcher for pod: {}", cluster_name, pod_name);
+                let result = with_credential_refresh(ctx, watcher, |ctx| {
+                    let pods_api = ctx.pods_api();
+                    let (field_selector, target, handles, event_tx) = (
+                        field_selector.clone(),
+                        target.clone(),
+                        handles.clone(),
+                        event_tx.clone(),
+                    );
+                    async move {
+                        match poll_interval {
+                            Some(interval) => {
+                                let lp = ListParams::default().fields(&field_selector);
+                                poll_pods(pods_api, lp, interval, target, handles, ctx, event_tx)
+                                    .await
                             }
-                            ui::events::PodEventType::Deleted(key) => {
-                                app.remove_pod(&key);
+                            None => {
+                                let cfg = WatcherConfig::default().fields(&field_selector);
+                                watch_pods(pods_api, cfg, target, handles, ctx, event_tx).await
                             }
-                        },
-                        AppEvent::Tick => {
-                            app.update_stats();
-                        }
-                        AppEvent::Quit => {
-                            should_quit = true;
                         }
                     }
+                })
+                .await;
+                if let Err(err) = result {
+                    warn!(
+                        "[{}] Watcher for pod {} stopped: {}",
+                        cluster_name, pod_name, err
+                    );
+                }
+            });
+        }
+    }
+
+    if let Some(respawn_rx) = respawn_rx {
+        tokio::spawn(respawn_streams(respawn_rx, contexts, handles));
+    }
+}
+
+/// What a selector watcher waits for, in its messages
+fn selector_target(selector: &str) -> String {
+    format!("pods matching {}", selector)
+}
+
+/// Watch (or with --poll, list) the pods matching a label selector
+fn spawn_selector_watcher(
+    ctx: TailContext,
+    selector: String,
+    handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    event_tx: Option<mpsc::Sender<AppEvent>>,
+    poll_interval: Option<Duration>,
+) -> tokio::task::JoinHandle<()> {
+    let watcher = ctx.options.watchers.started();
+    tokio::spawn(async move {
+        let cluster_name = ctx.cluster.clone();
+        debug!(
+            "[{}] Starting watcher for selector: {}",
+            cluster_name, selector
+        );
+        let result = with_credential_refresh(ctx, watcher, |ctx| {
+            let pods_api = ctx.pods_api();
+            let (selector, handles, event_tx) =
+                (selector.clone(), handles.clone(), event_tx.clone());
+            let target = selector_target(&selector);
+            async move {
+                match poll_interval {
+                    Some(interval) => {
+                        let lp = ListParams::default().labels(&selector);
+                        poll_pods(pods_api, lp, interval, target, handles, ctx, event_tx).await
+                    }
+                    None => {
+                        let cfg = WatcherConfig::default().labels(&selector);
+                        watch_pods(pods_api, cfg, target, handles, ctx, event_tx).await
+                    }
                 }
             }
+        })
+        .await;
+        if let Err(err) = result {
+            warn!(
+                "[{}] Watcher with selector {} stopped: {}",
+                cluster_name, selector, err
+            );
+        }
+    })
+}
+
+/// Shortest run after a credential refresh for another 401 to be worth
+/// refreshing again; sooner means the fresh credentials are rejected too
+const MIN_CREDENTIAL_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Run a pod watcher with the context's newest client, and when the API server
+/// rejects its credentials, rebuild the client (re-running an exec plugin such
+/// as `aws eks get-token`) and start the watcher over. How it ends is recorded
+/// on the watcher's entry in the session's watcher health, which the caller
+/// registers when spawning it.
+async fn with_credential_refresh<F, Fut>(
+    ctx: TailContext,
+    watcher: Watcher,
+    run: F,
+) -> anyhow::Result<()>
+where
+    F: Fn(TailContext) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<()>>,
+{
+    let result = run_with_credential_refresh(ctx, run).await;
+    watcher.stopped(result.as_ref().err());
+    result
+}
+
+async fn run_with_credential_refresh<F, Fut>(mut ctx: TailContext, run: F) -> anyhow::Result<()>
+where
+    F: Fn(TailContext) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<()>>,
+{
+    let Some((mut generation, client)) = ctx.options.credentials.current(&ctx.cluster) else {
+        return run(ctx).await;
+    };
+    ctx.client = client;
+    let mut refreshed_at: Option<std::time::Instant> = None;
+    loop {
+        let err = match run(ctx.clone()).await {
+            Err(err) if watch_unauthorized(&err) => err,
+            result => return result,
+        };
+        if refreshed_at.is_some_and(|at| at.elapsed() < MIN_CREDENTIAL_LIFETIME) {
+            return Err(err.context("rejected again after a credential refresh"));
+        }
+        warn!(
+            "[{}] Watcher credentials rejected ({}), refreshing",
+            ctx.cluster, err
+        );
+        ctx.timeline(None, "401: refreshing credentials");
+        let (fresh, client) = ctx
+            .options
+            .credentials
+            .refresh(&ctx.cluster, generation)
+            .await
+            .map_err(|e| e.context("credential refresh failed"))?;
+        generation = fresh;
+        ctx.client = client;
+        refreshed_at = Some(std::time::Instant::now());
+    }
+}
+
+/// Whether a watcher stopped because its credentials were rejected
+fn watch_unauthorized(err: &anyhow::Error) -> bool {
+    use kube::runtime::watcher::Error as WatchError;
+    match err.downcast_ref::<WatchError>() {
+        Some(
+            WatchError::InitialListFailed(e)
+            | WatchError::WatchStartFailed(e)
+            | WatchError::WatchFailed(e),
+        ) => kubernetes::is_unauthorized(e),
+        Some(WatchError::WatchError(response)) => response.code == 401,
+        _ => err
+            .downcast_ref::<kube::Error>()
+            .is_some_and(kubernetes::is_unauthorized),
+    }
+}
+
+/// With --watch-selector-change: re-resolve a resource's selector every
+/// `refresh` and swap its watcher when the selector changes. The new watcher
+/// picks up pods that now match; pods that only matched the old selector are
+/// dropped here.
+async fn follow_resource_selector(
+    resource: FollowedResource,
+    ctx: TailContext,
+    handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    event_tx: Option<mpsc::Sender<AppEvent>>,
+    poll_interval: Option<Duration>,
+    refresh: Duration,
+) {
+    let spawn = |selector: &String| {
+        spawn_selector_watcher(
+            ctx.clone(),
+            selector.clone(),
+            handles.clone(),
+            event_tx.clone(),
+            poll_interval,
+        )
+    };
+    let mut selector = resource.selector;
+    let mut watcher = selector.as_ref().map(spawn);
+
+    let mut ticker = tokio::time::interval(refresh);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let resolved = match get_selector_from_resource(
+            &ctx.current_client(),
+            &resource.kind,
+            &resource.name,
+            ctx.namespace(),
+            &ctx.crd_selectors,
+        )
+        .await
+        {
+            Ok(sel) => sel.as_ref().and_then(selector_to_labels_string),
+            Err(e) => {
+                // Keep the current watcher; the resource may be back next time
+                debug!(
+                    "[{}] Could not re-resolve selector for {}/{}: {}",
+                    ctx.cluster, resource.kind, resource.name, e
+                );
+                continue;
+            }
+        };
+        if resolved == selector {
+            continue;
+        }
+
+        info!(
+            "[{}] Selector of {}/{} changed: {} -> {}",
+            ctx.cluster,
+            resource.kind,
+            resource.name,
+            selector.as_deref().unwrap_or("(none)"),
+            resolved.as_deref().unwrap_or("(none)")
+        );
+        ctx.timeline(
+            None,
+            format!(
+                "selector of {}/{} changed to {}",
+                resource.kind,
+                resource.name,
+                resolved.as_deref().unwrap_or("(none)")
+            ),
+        );
+        if let Some(watcher) = watcher.take() {
+            watcher.abort();
+            // The old selector's watcher is gone, and with it any waiting
+            if let Some(old) = &selector {
+                send_watch_idle(&ctx, &selector_target(old), false, &event_tx).await;
+            }
+        }
+        if let Some(old) = &selector {
+            let old_pods = list_pod_names(&ctx, Some(old)).await;
+            let new_pods = list_pod_names(&ctx, resolved.as_ref()).await;
+            for name in pods_leaving_selector(&old_pods, &new_pods) {
+                ctx.timeline(Some(&name), "no longer matches the selector");
+                drop_pod(name, &handles, &ctx, &event_tx).await;
+            }
+        }
+        watcher = resolved.as_ref().map(spawn);
+        selector = resolved;
+    }
+}
+
+/// Names of the pods matching a selector (none for no selector)
+async fn list_pod_names(
+    ctx: &TailContext,
+    selector: Option<&String>,
+) -> std::collections::HashSet<String> {
+    let Some(selector) = selector else {
+        return Default::default();
+    };
+    let pods_api: Api<Pod> = ctx.scope.api(ctx.current_client());
+    match pods_api.list(&ListParams::default().labels(selector)).await {
+        Ok(list) => list.items.iter().map(|p| p.name_any()).collect(),
+        Err(e) => {
+            warn!(
+                "[{}] Failed to list pods for selector {}: {}",
+                ctx.cluster, selector, e
+            );
+            Default::default()
+        }
+    }
+}
+
+/// Pods matched by the old selector but not the new one, in name order
+fn pods_leaving_selector(
+    old: &std::collections::HashSet<String>,
+    new: &std::collections::HashSet<String>,
+) -> Vec<String> {
+    let mut leaving: Vec<String> = old.difference(new).cloned().collect();
+    leaving.sort();
+    leaving
+}
+
+/// Stop tailing a pod that is still running but no longer selected, and take
+/// it off the sidebar
+async fn drop_pod(
+    name: String,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    let key = PodKey {
+        cluster: ctx.cluster.clone(),
+        namespace: ctx.namespace().to_string(),
+        pod_name: name,
+        container_name: String::new(), // Will match all containers
+    };
+    info!("[{}] Pod left the selector: {}", ctx.cluster, key.pod_name);
+    stop_tailing_pod(&key, handles).await;
+
+    if let Some(tx) = event_tx {
+        let _ = tx
+            .send(AppEvent::PodUpdate(Box::new(ui::events::PodUpdateEvent {
+                info: PodInfo {
+                    key: key.clone(),
+                    phase: "Deleted".to_string(),
+                    created: None,
+                    restarts: 0,
+                    image: None,
+                    skipped: false,
+                    workload: None,
+                    qos_class: None,
+                    priority: None,
+                    node: None,
+                    zone: None,
+                    resources: None,
+                    kind: ContainerKind::Regular,
+                },
+                event_type: ui::events::PodEventType::Deleted(key),
+            })))
+            .await;
+    }
+}
+
+/// Restart the tail task of each container sent from the TUI, replacing its
+/// handle. Containers of pods that are no longer tracked are ignored.
+async fn respawn_streams(
+    mut respawn_rx: mpsc::UnboundedReceiver<PodKey>,
+    contexts: HashMap<(String, Scope), TailContext>,
+    handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+) {
+    while let Some(key) = respawn_rx.recv().await {
+        let Some(ctx) = contexts
+            .iter()
+            .find(|((cluster, scope), _)| *cluster == key.cluster && scope.covers(&key.namespace))
+            .map(|(_, ctx)| ctx)
+        else {
+            continue;
+        };
+        let mut guard = handles.lock().await;
+        let Some(old) = guard.get_mut(&key) else {
+            continue;
+        };
+        info!(
+            "[{}] Restarting log stream for {}/{}",
+            key.cluster, key.pod_name, key.container_name
+        );
+        for handle in old.drain(..) {
+            handle.abort();
+        }
+        old.push(kubernetes::spawn_tail_task(
+            ctx.client.clone(),
+            key.clone(),
+            None,
+            ctx.tx.clone(),
+            ctx.options.clone(),
+        ));
+    }
+}
+
+/// `target` names what is watched, e.g. `pods matching app=web`, for the
+/// messages shown while nothing matches
+async fn watch_pods(
+    pods_api: Api<Pod>,
+    cfg: WatcherConfig,
+    target: String,
+    handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: TailContext,
+    event_tx: Option<mpsc::Sender<AppEvent>>,
+) -> anyhow::Result<()> {
+    let mut newest = ctx.newest.map(NewestSelection::new);
+    let mut presence = WatchPresence::default();
+    let mut heartbeat = tokio::time::interval(IDLE_HEARTBEAT);
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut stream = watcher(pods_api, cfg).boxed();
+    // Set while the watch is failing after it had listed pods: the next delay
+    let mut retry: Option<Duration> = None;
+    loop {
+        let event = tokio::select! {
+            event = stream.try_next() => match event {
+                Ok(Some(event)) => event,
+                Ok(None) => break,
+                Err(err) if watch_gives_up(&err, presence.listed, retry) => {
+                    if retry.is_some() {
+                        send_watch_reconnecting(&ctx, &target, false, &event_tx).await;
+                    }
+                    return Err(err.into());
+                }
+                // The watcher resumes (or relists, e.g. after a 410 Gone) when
+                // polled again
+                Err(err) => {
+                    let delay = next_watch_retry(retry);
+                    warn!(
+                        "[{}] Watch of {} in namespace {} failed: {}, retrying in {:?}",
+                        ctx.cluster, target, ctx.scope, err, delay
+                    );
+                    ctx.timeline(None, format!("watch failed: {}, retrying in {:?}", err, delay));
+                    if retry.is_none() {
+                        send_watch_reconnecting(&ctx, &target, true, &event_tx).await;
+                    }
+                    retry = Some(delay);
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+            },
+            _ = heartbeat.tick() => {
+                if let Some(idle) = presence.heartbeat_due(Instant::now()) {
+                    report_still_idle(&ctx, &target, idle);
+                }
+                continue;
+            }
+        };
+        if retry.take().is_some() {
+            info!(
+                "[{}] Watch of {} in namespace {} reconnected",
+                ctx.cluster, target, ctx.scope
+            );
+            ctx.timeline(None, "watch reconnected");
+            send_watch_reconnecting(&ctx, &target, false, &event_tx).await;
+        }
+        let now = Instant::now();
+        let change = match event {
+            Event::Apply(pod) => {
+                let id = ctx.pod_id(&pod);
+                ctx.timeline(Some(&id), format!("Apply ({})", pod_phase(&pod)));
+                observe_pod(
+                    pod,
+                    &mut presence,
+                    &mut newest,
+                    &handles,
+                    &ctx,
+                    &event_tx,
+                    now,
+                )
+                .await
+            }
+            Event::InitApply(pod) => {
+                let id = ctx.pod_id(&pod);
+                ctx.timeline(Some(&id), format!("InitApply ({})", pod_phase(&pod)));
+                observe_pod(
+                    pod,
+                    &mut presence,
+                    &mut newest,
+                    &handles,
+                    &ctx,
+                    &event_tx,
+                    now,
+                )
+                .await
+            }
+            Event::Delete(pod) => {
+                let id = ctx.pod_id(&pod);
+                ctx.timeline(Some(&id), "Delete");
+                let change = presence.delete(&id, now);
+                delete_pod(id, &mut newest, &handles, &ctx, &event_tx).await;
+                change
+            }
+            Event::Init => {
+                ctx.timeline(None, "Init: watch (re)started");
+                info!(
+                    "[{}] Initializing pod watcher for namespace: {}",
+                    ctx.cluster, ctx.scope
+                );
+                presence.relist();
+                None
+            }
+            Event::InitDone => {
+                ctx.timeline(None, "InitDone: initial listing complete");
+                info!(
+                    "[{}] Pod watcher initialization complete for namespace: {}",
+                    ctx.cluster, ctx.scope
+                );
+                presence.listed(now)
+            }
+        };
+        if let Some(idle) = change {
+            report_idle(&ctx, &target, idle, &event_tx).await;
+        }
+    }
+    Ok(())
+}
+
+/// Handle an added or updated pod from a watch, counting it as present only
+/// when it passes the filters, so a watcher whose pods are all filtered out
+/// keeps waiting. Returns the watcher's idle change, if any.
+async fn observe_pod(
+    pod: Pod,
+    presence: &mut WatchPresence,
+    newest: &mut Option<NewestSelection>,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+    now: Instant,
+) -> Option<bool> {
+    let id = ctx.pod_id(&pod);
+    let change = if ctx.matches_filters(&pod).await {
+        presence.apply(&id, now)
+    } else {
+        presence.delete(&id, now)
+    };
+    apply_pod(pod, newest, handles, ctx, event_tx).await;
+    change
+}
+
+/// How often a watcher with no matching pods says it is still waiting
+const IDLE_HEARTBEAT: Duration = Duration::from_secs(60);
+
+/// First and longest wait before a failed watch is resumed
+const WATCH_RETRY_MIN: Duration = Duration::from_secs(1);
+const WATCH_RETRY_MAX: Duration = Duration::from_secs(30);
+
+/// Delay before the next attempt to resume a failing watch, doubling from
+/// `WATCH_RETRY_MIN` up to `WATCH_RETRY_MAX`
+fn next_watch_retry(previous: Option<Duration>) -> Duration {
+    previous.map_or(WATCH_RETRY_MIN, |d| (d * 2).min(WATCH_RETRY_MAX))
+}
+
+/// Whether resuming a failed watch can help: not when the API server rejected
+/// the credentials (a 401 goes to the credential refresh) or permissions
+fn watch_error_retryable(err: &kube::runtime::watcher::Error) -> bool {
+    use kube::runtime::watcher::Error as WatchError;
+    match err {
+        WatchError::InitialListFailed(e)
+        | WatchError::WatchStartFailed(e)
+        | WatchError::WatchFailed(e) => exit::kube_error_code(e) != ExitCode::Forbidden,
+        WatchError::WatchError(response) => !matches!(response.code, 401 | 403),
+        _ => true,
+    }
+}
+
+/// Whether a failed watch stops instead of being resumed after `retry`, the
+/// last delay. Rejected credentials won't fix themselves; other errors are
+/// retried, also before the first listing (`listed`), but a cluster that was
+/// never reached is given up on once the backoff peaks.
+fn watch_gives_up(
+    err: &kube::runtime::watcher::Error,
+    listed: bool,
+    retry: Option<Duration>,
+) -> bool {
+    !watch_error_retryable(err) || (!listed && retry == Some(WATCH_RETRY_MAX))
+}
+
+/// The pods one watcher currently matches, to tell when it has none (e.g. a
+/// Deployment scaled to zero) and when some appear again. Methods return
+/// `Some(idle)` when the watcher becomes idle or stops being idle.
+#[derive(Debug, Default)]
+struct WatchPresence {
+    pods: std::collections::HashSet<String>,
+    /// Pods seen since a (re)list started, replacing `pods` once it is done
+    relisting: Option<std::collections::HashSet<String>>,
+    /// Set once a listing completed; until then no pods means nothing yet
+    listed: bool,
+    idle_since: Option<Instant>,
+    last_heartbeat: Option<Instant>,
+}
+
+impl WatchPresence {
+    fn relist(&mut self) {
+        self.relisting = Some(std::collections::HashSet::new());
+    }
+
+    fn apply(&mut self, pod: &str, now: Instant) -> Option<bool> {
+        if let Some(relisting) = &mut self.relisting {
+            relisting.insert(pod.to_string());
+        }
+        self.pods.insert(pod.to_string());
+        self.update(now)
+    }
+
+    fn delete(&mut self, pod: &str, now: Instant) -> Option<bool> {
+        if let Some(relisting) = &mut self.relisting {
+            relisting.remove(pod);
+        }
+        self.pods.remove(pod);
+        self.update(now)
+    }
+
+    /// A listing completed; pods it didn't include are gone
+    fn listed(&mut self, now: Instant) -> Option<bool> {
+        if let Some(relisting) = self.relisting.take() {
+            self.pods = relisting;
+        }
+        self.listed = true;
+        self.update(now)
+    }
+
+    /// Replace the matched pods with a complete listing (--poll)
+    fn replace(&mut self, pods: std::collections::HashSet<String>, now: Instant) -> Option<bool> {
+        self.pods = pods;
+        self.listed = true;
+        self.update(now)
+    }
+
+    fn update(&mut self, now: Instant) -> Option<bool> {
+        let idle = self.listed && self.pods.is_empty();
+        match (idle, self.idle_since) {
+            (true, None) => {
+                self.idle_since = Some(now);
+                self.last_heartbeat = Some(now);
+                Some(true)
+            }
+            (false, Some(_)) => {
+                self.idle_since = None;
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+
+    /// How long the watcher has been idle, once per IDLE_HEARTBEAT while it is
+    fn heartbeat_due(&mut self, now: Instant) -> Option<Duration> {
+        let since = self.idle_since?;
+        if self
+            .last_heartbeat
+            .is_some_and(|last| now.duration_since(last) < IDLE_HEARTBEAT)
+        {
+            return None;
+        }
+        self.last_heartbeat = Some(now);
+        Some(now.duration_since(since))
+    }
+}
+
+/// Say that a watcher has no matching pods, or has some again
+async fn report_idle(
+    ctx: &TailContext,
+    target: &str,
+    idle: bool,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    if idle {
+        info!(
+            "[{}] No {} in namespace {}, waiting for some to appear",
+            ctx.cluster, target, ctx.scope
+        );
+        ctx.timeline(None, format!("no {}, waiting", target));
+    } else {
+        info!(
+            "[{}] Found {} in namespace {} again",
+            ctx.cluster, target, ctx.scope
+        );
+        ctx.timeline(None, format!("found {} again", target));
+    }
+    if let Some(watcher) = ctx.named_pod {
+        ctx.options.watchers.named_pod_gone(watcher, idle);
+    }
+    send_watch_idle(ctx, target, idle, event_tx).await;
+}
+
+/// Tell the TUI a watcher is failing and being resumed, or reconnected
+async fn send_watch_reconnecting(
+    ctx: &TailContext,
+    target: &str,
+    reconnecting: bool,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    if let Some(tx) = event_tx {
+        let _ = tx
+            .send(AppEvent::WatchReconnecting {
+                cluster: ctx.cluster.clone(),
+                watch: format!("{} in {}", target, ctx.scope),
+                reconnecting,
+            })
+            .await;
+    }
+}
+
+/// Keep the TUI's list of idle watchers up to date
+async fn send_watch_idle(
+    ctx: &TailContext,
+    target: &str,
+    idle: bool,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    if let Some(tx) = event_tx {
+        let watch = format!("{} in {}/{}", target, ctx.cluster, ctx.scope);
+        let _ = tx.send(AppEvent::WatchIdle { watch, idle }).await;
+    }
+}
+
+fn report_still_idle(ctx: &TailContext, target: &str, idle: Duration) {
+    info!(
+        "[{}] Still no {} in namespace {} after {}, waiting",
+        ctx.cluster,
+        target,
+        ctx.scope,
+        format_duration_short(chrono::Duration::from_std(idle).unwrap_or_default())
+    );
+}
+
+/// Forward Warning events about pods to the TUI
+async fn watch_warning_events(
+    events_api: Api<K8sEvent>,
+    cluster: String,
+    event_tx: mpsc::Sender<AppEvent>,
+) -> anyhow::Result<()> {
+    let cfg = WatcherConfig::default().fields("type=Warning,involvedObject.kind=Pod");
+    let mut stream = watcher(events_api, cfg).boxed();
+    while let Some(event) = stream.try_next().await? {
+        let (Event::Apply(ev) | Event::InitApply(ev)) = event else {
+            continue;
+        };
+        let (Some(namespace), Some(pod_name)) = (
+            ev.involved_object.namespace.clone(),
+            ev.involved_object.name.clone(),
+        ) else {
+            continue;
+        };
+        let Some(last_seen) = ev
+            .last_timestamp
+            .as_ref()
+            .map(|t| t.0)
+            .or_else(|| ev.event_time.as_ref().map(|t| t.0))
+            .or_else(|| ev.metadata.creation_timestamp.as_ref().map(|t| t.0))
+        else {
+            continue;
+        };
+        let warning = ui::events::PodWarningEvent {
+            cluster: cluster.clone(),
+            namespace,
+            pod_name,
+            event_name: ev.name_any(),
+            reason: ev.reason.clone().unwrap_or_else(|| "Warning".to_string()),
+            last_seen,
+        };
+        if event_tx.send(AppEvent::PodWarning(warning)).await.is_err() {
+            break;
         }
     }
-
-    // Cleanup terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn spawn_all_watchers(
-    clients: Vec<(String, Client)>,
-    cli: Cli,
-    watch_configs: Vec<WatchConfig>,
-    log_tx: mpsc::Sender<LogMessage>,
+/// Discover pods by periodically listing them instead of watching, for
+/// environments where the watch verb is forbidden. Pods missing from a listing
+/// are treated as deleted.
+async fn poll_pods(
+    pods_api: Api<Pod>,
+    lp: ListParams,
+    interval: Duration,
+    target: String,
     handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: TailContext,
     event_tx: Option<mpsc::Sender<AppEvent>>,
-) {
-    // Group configs by context for easy client lookup
-    let client_map: std::collections::HashMap<_, _> = clients.into_iter().collect();
-
-    for config in watch_configs {
-        let client = match client_map.get(&config.context) {
-            Some(c) => c.clone(),
-            None => {
-                warn!("[{}] No client found for context, skipping", config.context);
+) -> anyhow::Result<()> {
+    let mut newest = ctx.newest.map(NewestSelection::new);
+    let mut presence = WatchPresence::default();
+    let mut known: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        let pods = match pods_api.list(&lp).await {
+            Ok(list) => list.items,
+            // Retrying won't help; the caller refreshes the credentials
+            Err(e) if kubernetes::is_unauthorized(&e) => return Err(e.into()),
+            Err(e) => {
+                // Keep polling; the next listing may succeed
+                warn!(
+                    "[{}] Failed to list pods in namespace {}: {}",
+                    ctx.cluster, ctx.scope, e
+                );
                 continue;
             }
         };
 
-        let ctx = TailContext {
-            client: client.clone(),
-            cluster: config.context.clone(),
-            namespace: config.namespace.clone(),
-            container: cli.container.clone(),
-            tx: log_tx.clone(),
-            tail: cli.tail,
-        };
+        let current: std::collections::HashSet<String> =
+            pods.iter().map(|p| ctx.pod_id(p)).collect();
+        for id in known.difference(&current) {
+            ctx.timeline(Some(id), "gone from listing (poll)");
+            delete_pod(id.clone(), &mut newest, &handles, &ctx, &event_tx).await;
+        }
+        // Only pods that pass the filters end the wait
+        let mut matching = std::collections::HashSet::new();
+        for pod in pods {
+            let id = ctx.pod_id(&pod);
+            if !known.contains(&id) {
+                ctx.timeline(Some(&id), format!("listed ({}) (poll)", pod_phase(&pod)));
+            }
+            if ctx.matches_filters(&pod).await {
+                matching.insert(id);
+            }
+            apply_pod(pod, &mut newest, &handles, &ctx, &event_tx).await;
+        }
+        let now = Instant::now();
+        if let Some(idle) = presence.replace(matching, now) {
+            report_idle(&ctx, &target, idle, &event_tx).await;
+        } else if let Some(idle) = presence.heartbeat_due(now) {
+            report_still_idle(&ctx, &target, idle);
+        }
+        known = current;
+    }
+}
 
-        // Spawn watchers for label selectors
-        for selector in &config.label_selectors {
-            let pods_api: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
-            let handles = handles.clone();
-            let ctx = ctx.clone();
-            let selector = selector.clone();
-            let event_tx = event_tx.clone();
+/// The most recently created pods of one watcher, for --newest, by the
+/// watcher's `pod_id`. Only pods that could be tailed (Pending or Running,
+/// matching --annotation, --qos and --kinds) are candidates.
+struct NewestSelection {
+    limit: usize,
+    candidates: HashMap<String, Pod>,
+}
 
-            tokio::spawn(async move {
-                let cfg = WatcherConfig::default().labels(&selector);
-                let cluster_name = ctx.cluster.clone();
-                debug!(
-                    "[{}] Starting watcher for selector: {}",
-                    cluster_name, selector
-                );
-                if let Err(err) = watch_pods(pods_api, cfg, handles, ctx, event_tx).await {
-                    warn!(
-                        "[{}] Watcher with selector {} stopped: {}",
-                        cluster_name, selector, err
-                    );
-                }
-            });
+impl NewestSelection {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            candidates: HashMap::new(),
         }
+    }
 
-        // Spawn watchers for explicit pods
-        for pod_name in &config.explicit_pods {
-            let pods_api: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
-            let handles = handles.clone();
-            let ctx = ctx.clone();
-            let pod_name = pod_name.clone();
-            let event_tx = event_tx.clone();
-
-            tokio::spawn(async move {
-                let field_selector = format!("metadata.name={}", pod_name);
-                let cfg = WatcherConfig::default().fields(&field_selector);
-                let cluster_name = ctx.cluster.clone();
-                debug!("[{}] Starting watcher for pod: {}", cluster_name, pod_name);
-                if let Err(err) = watch_pods(pods_api, cfg, handles, ctx, event_tx).await {
-                    warn!(
-                        "[{}] Watcher for pod {} stopped: {}",
-                        cluster_name, pod_name, err
-                    );
-                }
-            });
+    /// Add, update or drop a pod owned by `workload`, as resolved for --kinds
+    fn update(&mut self, id: &str, pod: &Pod, workload: Option<&str>, ctx: &TailContext) {
+        let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+        if matches!(phase, Some("Running" | "Pending"))
+            && matches_annotations(pod, &ctx.annotations)
+            && matches_qos(pod, &ctx.qos)
+            && matches_kinds(workload, &ctx.kinds)
+        {
+            self.candidates.insert(id.to_string(), pod.clone());
+        } else {
+            self.candidates.remove(id);
         }
     }
+
+    fn remove(&mut self, id: &str) {
+        self.candidates.remove(id);
+    }
+
+    /// IDs of the `limit` newest candidates (ties broken by ID)
+    fn selected(&self) -> std::collections::HashSet<String> {
+        let mut pods: Vec<(&String, &Pod)> = self.candidates.iter().collect();
+        pods.sort_by(|(a_id, a), (b_id, b)| {
+            b.metadata
+                .creation_timestamp
+                .cmp(&a.metadata.creation_timestamp)
+                .then_with(|| a_id.cmp(b_id))
+        });
+        pods.iter()
+            .take(self.limit)
+            .map(|(id, _)| (*id).clone())
+            .collect()
+    }
 }
 
-async fn watch_pods(
-    pods_api: Api<Pod>,
-    cfg: WatcherConfig,
-    handles: Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
-    ctx: TailContext,
-    event_tx: Option<mpsc::Sender<AppEvent>>,
-) -> anyhow::Result<()> {
-    let mut stream = watcher(pods_api, cfg).boxed();
-    while let Some(event) = stream.try_next().await? {
-        match event {
-            Event::Apply(pod) | Event::InitApply(pod) => {
-                handle_pod_event(pod, &handles, ctx.clone(), event_tx.clone()).await;
-            }
-            Event::Delete(pod) => {
-                let name = pod.name_any();
-                let key = PodKey {
-                    cluster: ctx.cluster.clone(),
-                    namespace: ctx.namespace.clone(),
-                    pod_name: name.clone(),
-                    container_name: String::new(), // Will match all containers
-                };
+/// Handle an added or updated pod, honoring --newest
+async fn apply_pod(
+    pod: Pod,
+    newest: &mut Option<NewestSelection>,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    let Some(selection) = newest else {
+        handle_pod_event(pod, handles, ctx.clone(), event_tx.clone(), true).await;
+        return;
+    };
+    let id = ctx.pod_id(&pod);
+    let before = selection.selected();
+    let workload = match &ctx.workloads {
+        Some(resolver) => resolver.resolve(&pod).await,
+        None => None,
+    };
+    selection.update(&id, &pod, workload.as_deref(), ctx);
+    let after = selection.selected();
+    let selected = after.contains(&id);
+    handle_pod_event(pod, handles, ctx.clone(), event_tx.clone(), selected).await;
+    reselect_pods(selection, &before, &after, handles, ctx, event_tx).await;
+}
 
-                info!("[{}] POD DELETED: {}", ctx.cluster, name);
-                stop_tailing_pod(&key, &handles).await;
-
-                if let Some(ref tx) = event_tx {
-                    let _ = tx
-                        .send(AppEvent::PodUpdate(ui::events::PodUpdateEvent {
-                            info: PodInfo {
-                                key: key.clone(),
-                                phase: "Deleted".to_string(),
-                                age: String::new(),
-                                restarts: 0,
-                            },
-                            event_type: ui::events::PodEventType::Deleted(key),
-                        }))
-                        .await;
-                }
-            }
-            Event::Init => {
-                info!(
-                    "[{}] Initializing pod watcher for namespace: {}",
-                    ctx.cluster, ctx.namespace
-                );
-            }
-            Event::InitDone => {
-                info!(
-                    "[{}] Pod watcher initialization complete for namespace: {}",
-                    ctx.cluster, ctx.namespace
-                );
-            }
+/// Handle a deleted pod, known by its `pod_id`, honoring --newest
+async fn delete_pod(
+    id: String,
+    newest: &mut Option<NewestSelection>,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    let Some(selection) = newest else {
+        handle_pod_deleted(id, handles, ctx, event_tx).await;
+        return;
+    };
+    let before = selection.selected();
+    selection.remove(&id);
+    let after = selection.selected();
+    handle_pod_deleted(id, handles, ctx, event_tx).await;
+    reselect_pods(selection, &before, &after, handles, ctx, event_tx).await;
+}
+
+/// Start or stop tailing the pods whose --newest membership changed
+async fn reselect_pods(
+    selection: &NewestSelection,
+    before: &std::collections::HashSet<String>,
+    after: &std::collections::HashSet<String>,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    for id in before.symmetric_difference(after) {
+        if let Some(pod) = selection.candidates.get(id) {
+            let selected = after.contains(id);
+            handle_pod_event(
+                pod.clone(),
+                handles,
+                ctx.clone(),
+                event_tx.clone(),
+                selected,
+            )
+            .await;
         }
     }
-    Ok(())
 }
 
+async fn handle_pod_deleted(
+    id: String,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    let (ctx, name) = ctx.for_pod(&id);
+    let ctx = &ctx;
+    let key = PodKey {
+        cluster: ctx.cluster.clone(),
+        namespace: ctx.namespace().to_string(),
+        pod_name: name.clone(),
+        container_name: String::new(), // Will match all containers
+    };
+
+    info!("[{}] POD DELETED: {}", ctx.cluster, name);
+    drain_deleted_pod(&key, handles, ctx).await;
+    ctx.options.stream_states.pod_deleted(&key);
+
+    // A pod deleted before reaching a terminal phase counts as a failure
+    if let Some(tracker) = &ctx.completion {
+        tracker.finish(
+            &format!("{}/{}/{}", ctx.cluster, ctx.namespace(), name),
+            vec![ContainerExit {
+                container: "*".to_string(),
+                exit_code: -1,
+                reason: Some("deleted before completion".to_string()),
+            }],
+        );
+    }
+
+    if let Some(tx) = event_tx {
+        let _ = tx
+            .send(AppEvent::PodUpdate(Box::new(ui::events::PodUpdateEvent {
+                info: PodInfo {
+                    key: key.clone(),
+                    phase: "Deleted".to_string(),
+                    created: None,
+                    restarts: 0,
+                    image: None,
+                    skipped: false,
+                    workload: None,
+                    qos_class: None,
+                    priority: None,
+                    node: None,
+                    zone: None,
+                    resources: None,
+                    kind: ContainerKind::Regular,
+                },
+                event_type: ui::events::PodEventType::Deleted(key),
+            })))
+            .await;
+    }
+}
+
+/// `selected` is false for pods left out by --newest: they are listed but not tailed.
 async fn handle_pod_event(
     pod: Pod,
     handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
     ctx: TailContext,
     event_tx: Option<mpsc::Sender<AppEvent>>,
+    selected: bool,
 ) {
-    let name = pod.name_any();
+    // With -A, the pod's own namespace rather than the watcher's
+    let (ctx, name) = ctx.for_pod(&ctx.pod_id(&pod));
     let phase = pod
         .status
         .as_ref()
@@ -618,13 +3069,42 @@ async fn handle_pod_event(
         .unwrap_or(&"Unknown".to_string())
         .clone();
 
+    let workload = match &ctx.workloads {
+        Some(resolver) => resolver.resolve(&pod).await,
+        None => None,
+    };
+    let zone = ctx.zone_of(&pod);
+    // Pods owned by other workload kinds than --kinds asks for are ignored
+    if !matches_kinds(workload.as_deref(), &ctx.kinds) {
+        ctx.timeline(
+            Some(&name),
+            format!(
+                "ignored: {} is not one of --kinds",
+                workload.as_deref().unwrap_or("bare pod")
+            ),
+        );
+        return;
+    }
+    // Likewise pods of other QoS classes than --qos asks for
+    if !matches_qos(&pod, &ctx.qos) {
+        ctx.timeline(Some(&name), "ignored: QoS class is not one of --qos");
+        return;
+    }
+
     let is_running = phase == "Running";
+    let is_terminal = phase == "Succeeded" || phase == "Failed";
     let _is_terminating = pod.metadata.deletion_timestamp.is_some();
+    let pod_id = format!("{}/{}/{}", ctx.cluster, ctx.namespace(), name);
+    // With --wait, pods that already completed are tailed too, for their exit code
+    let wants_completed = ctx
+        .completion
+        .as_ref()
+        .is_some_and(|t| !t.is_finished(&pod_id));
 
     // Create a base key for this pod (container_name will be added per container)
     let base_key = PodKey {
         cluster: ctx.cluster.clone(),
-        namespace: ctx.namespace.clone(),
+        namespace: ctx.namespace().to_string(),
         pod_name: name.clone(),
         container_name: String::new(), // Temporary, will be set per container
     };
@@ -638,71 +3118,462 @@ async fn handle_pod_event(
     });
     drop(guard);
 
-    if !was_tracking && (is_running || phase == "Pending") {
+    // Pods that fail the --annotation filters are never tailed, and one that
+    // stops matching (its annotation was removed) is no longer tailed
+    if !matches_annotations(&pod, &ctx.annotations) {
+        ctx.timeline(Some(&name), "ignored: --annotation filters do not match");
+        if was_tracking {
+            stop_tailing_pod(&base_key, handles).await;
+            ctx.options.stream_states.remove_pod(&base_key);
+        }
+        return;
+    }
+
+    // Left out by --newest: stop tailing it if it was, and list it as skipped
+    if !selected && (is_running || phase == "Pending") {
+        if was_tracking {
+            info!(
+                "[{}] SKIPPED POD: {} | Not among the {} newest, stopped tailing",
+                ctx.cluster,
+                name,
+                ctx.newest.unwrap_or_default()
+            );
+            stop_tailing_pod(&base_key, handles).await;
+            ctx.options.stream_states.remove_pod(&base_key);
+        } else {
+            debug!("[{}] Skipping older pod {}", ctx.cluster, name);
+        }
+        ctx.timeline(
+            Some(&name),
+            format!(
+                "skipped: not among the {} newest",
+                ctx.newest.unwrap_or_default()
+            ),
+        );
+        if let Some(ref tx) = event_tx
+            && let Some(spec) = &pod.spec
+        {
+            for (container_name, image, kind) in ctx.containers_of(&pod, ctx.running_only) {
+                let resources = container_resources(&pod, &container_name);
+                let info = PodInfo {
+                    key: PodKey {
+                        container_name,
+                        ..base_key.clone()
+                    },
+                    phase: phase.clone(),
+                    created: pod.metadata.creation_timestamp.as_ref().map(|t| t.0),
+                    restarts: get_restart_count(&pod),
+                    image,
+                    skipped: true,
+                    workload: workload.clone(),
+                    qos_class: qos_class(&pod),
+                    priority: spec.priority,
+                    node: spec.node_name.clone(),
+                    zone: zone.clone(),
+                    resources,
+                    kind,
+                };
+                let _ = tx
+                    .send(AppEvent::PodUpdate(Box::new(ui::events::PodUpdateEvent {
+                        info,
+                        event_type: ui::events::PodEventType::Updated,
+                    })))
+                    .await;
+            }
+        }
+        return;
+    }
+
+    if !was_tracking && (is_running || phase == "Pending" || (is_terminal && wants_completed)) {
+        // Completed pods are read to the end whatever their containers' state
+        let containers = containers_to_tail(
+            &pod,
+            &ctx.container,
+            ctx.running_only && !is_terminal,
+            ctx.extra,
+            &ctx.exclude_containers,
+        );
+        if containers.is_empty() {
+            if let Some(container) = &ctx.container
+                && containers_to_tail(&pod, &ctx.container, false, ctx.extra, &[]).is_empty()
+            {
+                debug!(
+                    "[{}] No container matching '{}' in pod {}, skipping",
+                    ctx.cluster, container, name
+                );
+                ctx.timeline(
+                    Some(&name),
+                    format!("skipped: no container matching '{}'", container),
+                );
+                return;
+            }
+            debug!(
+                "[{}] No running containers in pod {} yet, waiting",
+                ctx.cluster, name
+            );
+            ctx.timeline(Some(&name), "no running containers yet, waiting");
+            return;
+        }
+
         // New pod - start tailing
         info!("[{}] NEW POD: {} | Phase: {}", ctx.cluster, name, phase);
+        if let Some(tracker) = &ctx.completion {
+            tracker.register(&pod_id);
+        }
 
-        let pod_handles = spawn_tail_tasks_for_pod(
-            ctx.client.clone(),
-            ctx.cluster.clone(),
-            name.clone(),
-            ctx.namespace.clone(),
-            ctx.container.clone(),
-            ctx.tx.clone(),
-            ctx.tail,
+        tail_containers(
+            &pod, &workload, containers, &phase, handles, &ctx, &event_tx,
         )
         .await;
+        debug!("[{}] Started tailing pod {}", ctx.cluster, name);
 
-        // Get container names and create pod info
-        if let Some(spec) = &pod.spec {
-            for (handle, container) in pod_handles.iter().zip(&spec.containers) {
-                let key = PodKey {
-                    cluster: ctx.cluster.clone(),
-                    namespace: ctx.namespace.clone(),
-                    pod_name: name.clone(),
-                    container_name: container.name.clone(),
+        if is_terminal {
+            finish_tailing_pod(&pod, &pod_id, &base_key, handles, &ctx).await;
+        }
+    } else if was_tracking {
+        if is_terminal && ctx.completion.is_some() {
+            info!(
+                "[{}] POD COMPLETED: {} | Phase: {}",
+                ctx.cluster, name, phase
+            );
+            finish_tailing_pod(&pod, &pod_id, &base_key, handles, &ctx).await;
+        } else if !is_running {
+            info!(
+                "[{}] POD STATUS CHANGED: {} | Phase: {} | Stopped tailing",
+                ctx.cluster, name, phase
+            );
+            stop_tailing_pod(&base_key, handles).await;
+            ctx.options.stream_states.remove_pod(&base_key);
+        } else {
+            // Pick up containers that started, or ephemeral containers that were
+            // added, since the pod was first seen, and those another watcher of
+            // the pod targets (`pod/web-0:app pod/web-0:proxy`)
+            let guard = handles.lock().await;
+            let started: Vec<(String, ContainerKind)> = containers_to_tail(
+                &pod,
+                &ctx.container,
+                ctx.running_only,
+                ctx.extra,
+                &ctx.exclude_containers,
+            )
+            .into_iter()
+            .filter(|(c, _)| {
+                !guard.contains_key(&PodKey {
+                    container_name: c.clone(),
+                    ..base_key.clone()
+                })
+            })
+            .collect();
+            drop(guard);
+            if !started.is_empty() {
+                info!(
+                    "[{}] CONTAINERS STARTED: {} | {}",
+                    ctx.cluster,
+                    name,
+                    started
+                        .iter()
+                        .map(|(c, _)| c.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                tail_containers(&pod, &workload, started, &phase, handles, &ctx, &event_tx).await;
+            }
+        }
+
+        // Keep the TUI's view of a tracked pod current (phase transitions etc.)
+        if let Some(ref tx) = event_tx
+            && let Some(spec) = &pod.spec
+        {
+            for (container_name, image, kind) in
+                ctx.containers_of(&pod, ctx.running_only && !is_terminal)
+            {
+                let resources = container_resources(&pod, &container_name);
+                let info = PodInfo {
+                    key: PodKey {
+                        container_name,
+                        ..base_key.clone()
+                    },
+                    phase: phase.clone(),
+                    created: pod.metadata.creation_timestamp.as_ref().map(|t| t.0),
+                    restarts: get_restart_count(&pod),
+                    image,
+                    skipped: false,
+                    workload: workload.clone(),
+                    qos_class: qos_class(&pod),
+                    priority: spec.priority,
+                    node: spec.node_name.clone(),
+                    zone: zone.clone(),
+                    resources,
+                    kind,
                 };
+                let _ = tx
+                    .send(AppEvent::PodUpdate(Box::new(ui::events::PodUpdateEvent {
+                        info,
+                        event_type: ui::events::PodEventType::Updated,
+                    })))
+                    .await;
+            }
+        }
+    }
+}
 
-                handles
-                    .lock()
-                    .await
-                    .insert(key.clone(), vec![handle.clone()]);
-
-                // Send pod info to TUI
-                if let Some(ref tx) = event_tx {
-                    let info = PodInfo {
-                        key,
-                        phase: phase.clone(),
-                        age: format_age(&pod),
-                        restarts: get_restart_count(&pod),
-                    };
-                    let _ = tx
-                        .send(AppEvent::PodUpdate(ui::events::PodUpdateEvent {
-                            info,
-                            event_type: ui::events::PodEventType::Added,
-                        }))
-                        .await;
-                }
+/// Names and kinds of the containers of a pod to tail: the --container one(s)
+/// or all of them, optionally only those currently running. Init and ephemeral
+/// containers that already finished count as running, as they never run again.
+/// A pod without a container matching --container gets none, so a pattern
+/// picks the same container out of every pod of a workload.
+fn containers_to_tail(
+    pod: &Pod,
+    container: &Option<String>,
+    running_only: bool,
+    extra: ExtraContainers,
+    exclude: &[String],
+) -> Vec<(String, ContainerKind)> {
+    let spec = extra.of(pod);
+    // Every container whose name matches; the pattern was validated up front
+    let regex = container
+        .as_deref()
+        .and_then(container_regex)
+        .and_then(Result::ok);
+    let mut names: Vec<(String, ContainerKind)> = match (container, regex) {
+        (_, Some(regex)) => spec
+            .into_iter()
+            .filter(|(name, ..)| regex.is_match(name))
+            .map(|(name, _, kind)| (name, kind))
+            .collect(),
+        (Some(c), None) => {
+            let kind = spec
+                .iter()
+                .find(|(name, ..)| name == c)
+                .map_or(ContainerKind::Regular, |(.., kind)| *kind);
+            // Named explicitly, it is tailed even if of a kind not included
+            let all = ExtraContainers {
+                init: true,
+                ephemeral: true,
+            };
+            if all.of(pod).iter().any(|(name, ..)| name == c) {
+                vec![(c.clone(), kind)]
+            } else {
+                Vec::new()
             }
         }
+        (None, None) => spec
+            .into_iter()
+            .map(|(name, _, kind)| (name, kind))
+            .collect(),
+    };
+    // Exclusion wins over --container
+    names.retain(|(name, _)| !is_excluded_container(name, exclude));
+    if running_only {
+        let statuses: Vec<_> = pod
+            .status
+            .iter()
+            .flat_map(|s| {
+                s.container_statuses
+                    .iter()
+                    .chain(s.init_container_statuses.iter())
+                    .chain(s.ephemeral_container_statuses.iter())
+                    .flatten()
+            })
+            .collect();
+        names.retain(|(name, kind)| {
+            (*kind != ContainerKind::Regular && finished_container(pod, name).is_some())
+                || statuses.iter().any(|cs| {
+                    &cs.name == name && cs.state.as_ref().is_some_and(|st| st.running.is_some())
+                })
+        });
+    }
+    names
+}
 
-        debug!("[{}] Started tailing pod {}", ctx.cluster, name);
-    } else if was_tracking && !is_running {
-        info!(
-            "[{}] POD STATUS CHANGED: {} | Phase: {} | Stopped tailing",
-            ctx.cluster, name, phase
-        );
-        stop_tailing_pod(&base_key, handles).await;
+/// Start tailing the given containers of a pod and announce them to the TUI
+async fn tail_containers(
+    pod: &Pod,
+    workload: &Option<String>,
+    containers: Vec<(String, ContainerKind)>,
+    phase: &str,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+    event_tx: &Option<mpsc::Sender<AppEvent>>,
+) {
+    let name = pod.name_any();
+    let base_key = PodKey {
+        cluster: ctx.cluster.clone(),
+        namespace: ctx.namespace().to_string(),
+        pod_name: name.clone(),
+        container_name: String::new(),
+    };
+    // A new instance under a recently deleted pod's name: note it in each
+    // container's log ahead of the new instance's lines
+    if ctx.options.stream_states.take_recreated(&base_key) {
+        info!("[{}] POD RE-CREATED: {}", ctx.cluster, name);
+        for (container, _) in &containers {
+            let _ = ctx
+                .tx
+                .send(LogMessage {
+                    cluster: ctx.cluster.clone(),
+                    namespace: ctx.namespace().to_string(),
+                    pod_name: name.clone(),
+                    container_name: container.clone(),
+                    line: MarkerKind::POD_RECREATED.to_string(),
+                    timestamp: chrono::Utc::now(),
+                })
+                .await;
+        }
+    }
+    // Init and ephemeral containers that already finished are read once
+    let (finished, running): (Vec<_>, Vec<_>) = containers.iter().partition(|(c, kind)| {
+        *kind != ContainerKind::Regular && finished_container(pod, c).is_some()
+    });
+    let target = |containers: Vec<&(String, ContainerKind)>| TailTarget {
+        cluster: ctx.cluster.clone(),
+        namespace: ctx.namespace().to_string(),
+        pod_name: name.clone(),
+        pod_uid: pod.metadata.uid.clone(),
+        containers: containers.into_iter().map(|(c, _)| c.clone()).collect(),
+    };
+    let mut pod_handles = spawn_tail_tasks_for_pod(
+        ctx.client.clone(),
+        target(running),
+        ctx.tx.clone(),
+        ctx.options.clone(),
+    );
+    if !finished.is_empty() {
+        pod_handles.extend(spawn_tail_tasks_for_pod(
+            ctx.client.clone(),
+            target(finished),
+            ctx.tx.clone(),
+            TailOptions {
+                once: true,
+                ..ctx.options.clone()
+            },
+        ));
+    }
+    let zone = ctx.zone_of(pod);
+    let spec = ctx.extra.of(pod);
+
+    for (container_name, handle) in pod_handles {
+        let key = PodKey {
+            cluster: ctx.cluster.clone(),
+            namespace: ctx.namespace().to_string(),
+            pod_name: name.clone(),
+            container_name,
+        };
+
+        handles.lock().await.insert(key.clone(), vec![handle]);
+
+        // Send pod info to TUI
+        if let Some(tx) = event_tx {
+            let (image, kind) = spec
+                .iter()
+                .find(|(c, ..)| *c == key.container_name)
+                .map_or((None, ContainerKind::Regular), |(_, image, kind)| {
+                    (image.clone(), *kind)
+                });
+            let resources = container_resources(pod, &key.container_name);
+            let info = PodInfo {
+                key,
+                phase: phase.to_string(),
+                created: pod.metadata.creation_timestamp.as_ref().map(|t| t.0),
+                restarts: get_restart_count(pod),
+                image,
+                skipped: false,
+                workload: workload.clone(),
+                qos_class: qos_class(pod),
+                priority: pod.spec.as_ref().and_then(|s| s.priority),
+                node: pod.spec.as_ref().and_then(|s| s.node_name.clone()),
+                zone: zone.clone(),
+                resources,
+                kind,
+            };
+            let _ = tx
+                .send(AppEvent::PodUpdate(Box::new(ui::events::PodUpdateEvent {
+                    info,
+                    event_type: ui::events::PodEventType::Added,
+                })))
+                .await;
+        }
+    }
+}
+
+/// Record a terminated pod's exit codes (--wait) and stop tailing it once its
+/// remaining log lines had time to arrive
+async fn finish_tailing_pod(
+    pod: &Pod,
+    pod_id: &str,
+    base_key: &PodKey,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+) {
+    if let Some(tracker) = &ctx.completion {
+        tracker.finish(pod_id, container_exits(pod));
+    }
+
+    let base_key = base_key.clone();
+    let handles = handles.clone();
+    let stream_states = ctx.options.stream_states.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(LOG_DRAIN).await;
+        stop_tailing_pod(&base_key, &handles).await;
+        stream_states.remove_pod(&base_key);
+    });
+}
+
+/// Stop tailing a deleted pod after the --delete-grace period, so the lines it
+/// writes while shutting down are not lost. The handles are taken right away:
+/// a pod re-created under the same name gets tailed afresh. Tail tasks also
+/// stop by themselves once their stream ends or fails during the grace period.
+async fn drain_deleted_pod(
+    base_key: &PodKey,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+    ctx: &TailContext,
+) {
+    let taken = take_pod_handles(base_key, handles).await;
+    let grace = ctx.options.delete_grace;
+    let stream_states = ctx.options.stream_states.clone();
+    if grace.is_zero() {
+        for handle in taken.into_values().flatten() {
+            handle.abort();
+        }
+        stream_states.remove_pod(base_key);
+        return;
+    }
+
+    for key in taken.keys() {
+        stream_states.set_draining(key);
     }
+    tokio::spawn(async move {
+        tokio::time::sleep(grace).await;
+        for (key, pod_handles) in taken {
+            for handle in pod_handles {
+                handle.abort();
+            }
+            if stream_states.is_draining(&key) {
+                stream_states.remove(&key);
+            }
+        }
+    });
 }
 
 async fn stop_tailing_pod(
     base_key: &PodKey,
     handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
 ) {
-    let mut guard = handles.lock().await;
+    for handle in take_pod_handles(base_key, handles)
+        .await
+        .into_values()
+        .flatten()
+    {
+        handle.abort();
+    }
+}
 
-    // Find and remove all handles for this pod (all containers)
+/// Remove the handles of all containers of a pod
+async fn take_pod_handles(
+    base_key: &PodKey,
+    handles: &Arc<Mutex<HashMap<PodKey, Vec<AbortHandle>>>>,
+) -> HashMap<PodKey, Vec<AbortHandle>> {
+    let mut guard = handles.lock().await;
     let keys_to_remove: Vec<PodKey> = guard
         .keys()
         .filter(|k| {
@@ -713,35 +3584,43 @@ async fn stop_tailing_pod(
         .cloned()
         .collect();
 
-    for key in keys_to_remove {
-        if let Some(pod_handles) = guard.remove(&key) {
-            for handle in pod_handles {
-                handle.abort();
-            }
-        }
+    keys_to_remove
+        .into_iter()
+        .filter_map(|key| guard.remove(&key).map(|h| (key, h)))
+        .collect()
+}
+
+/// Whether a pod's workload (`kind/name`, None for bare pods) is one of the
+/// --kinds. An empty list matches everything.
+fn matches_kinds(workload: Option<&str>, kinds: &[String]) -> bool {
+    if kinds.is_empty() {
+        return true;
     }
+    let kind = workload.map_or("pod", |w| w.split('/').next().unwrap_or_default());
+    kinds.iter().any(|k| k == kind)
+}
+
+/// QoS class the kubelet assigned to a pod (Guaranteed, Burstable, BestEffort)
+fn qos_class(pod: &Pod) -> Option<String> {
+    pod.status.as_ref().and_then(|s| s.qos_class.clone())
 }
 
-fn format_age(pod: &Pod) -> String {
-    pod.metadata
-        .creation_timestamp
+/// Whether a pod is of one of the --qos classes. An empty list matches everything;
+/// a pod whose class is not known yet matches nothing.
+fn matches_qos(pod: &Pod, classes: &[String]) -> bool {
+    classes.is_empty() || qos_class(pod).is_some_and(|qos| classes.contains(&qos))
+}
+
+fn matches_annotations(pod: &Pod, filters: &[AnnotationFilter]) -> bool {
+    let annotations = pod.annotations();
+    filters.iter().all(|f| f.matches(annotations))
+}
+
+fn pod_phase(pod: &Pod) -> &str {
+    pod.status
         .as_ref()
-        .map(|t| {
-            let now = chrono::Utc::now();
-            let created = t.0;
-            let duration = now.signed_duration_since(created);
-            let total_secs = duration.num_seconds();
-            if total_secs < 60 {
-                format!("{}s", total_secs)
-            } else if total_secs < 3600 {
-                format!("{}m", total_secs / 60)
-            } else if total_secs < 86400 {
-                format!("{}h", total_secs / 3600)
-            } else {
-                format!("{}d", total_secs / 86400)
-            }
-        })
-        .unwrap_or_else(|| "unknown".to_string())
+        .and_then(|s| s.phase.as_deref())
+        .unwrap_or("Unknown")
 }
 
 fn get_restart_count(pod: &Pod) -> i32 {
@@ -1,10 +1,134 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::cli::Cli;
+    use crate::types::LogMessage;
+    use crate::ui::App;
+    use crate::ui::app::{LogEntry, TimestampStyle};
     use crate::utils;
     use clap::Parser;
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, LabelSelectorRequirement};
 
+    fn log_line(pod: &str, container: &str, line: &str) -> LogMessage {
+        LogMessage {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: pod.to_string(),
+            container_name: container.to_string(),
+            line: line.to_string(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// A running regular container with nothing else known about it
+    fn pod_info(key: crate::ui::app::PodKey) -> crate::ui::app::PodInfo {
+        crate::ui::app::PodInfo {
+            key,
+            phase: "Running".to_string(),
+            created: None,
+            restarts: 0,
+            image: None,
+            skipped: false,
+            workload: None,
+            qos_class: None,
+            priority: None,
+            node: None,
+            zone: None,
+            resources: None,
+            kind: crate::ui::app::ContainerKind::Regular,
+        }
+    }
+
+    /// The scope of a single namespace
+    fn namespace(name: &str) -> crate::Scope {
+        crate::Scope::Namespace(name.to_string())
+    }
+
+    /// A context of cluster `prod`, namespace `default` with default options.
+    /// Its client points where nothing listens, and its log lines go nowhere.
+    fn test_context() -> crate::TailContext {
+        let config = kube::Config::new("https://127.0.0.1:1".parse().unwrap());
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        crate::TailContext {
+            client: kube::Client::try_from(config).unwrap(),
+            cluster: "prod".to_string(),
+            scope: namespace("default"),
+            container: None,
+            tx,
+            options: crate::kubernetes::TailOptions::default(),
+            annotations: Default::default(),
+            completion: None,
+            newest: None,
+            running_only: false,
+            extra: Default::default(),
+            exclude_containers: Default::default(),
+            workloads: None,
+            kinds: Default::default(),
+            qos: Default::default(),
+            zones: None,
+            crd_selectors: Default::default(),
+            named_pod: None,
+        }
+    }
+
+    /// A running pod in `default` whose containers are running or not yet
+    /// started, as given
+    fn running_pod(name: &str, containers: &[(&str, bool)]) -> k8s_openapi::api::core::v1::Pod {
+        use k8s_openapi::api::core::v1::{
+            Container, ContainerState, ContainerStateRunning, ContainerStatus, Pod, PodSpec,
+            PodStatus,
+        };
+        Pod {
+            metadata: kube::api::ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("default".to_string()),
+                uid: Some(format!("{}-uid", name)),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                containers: containers
+                    .iter()
+                    .map(|(n, _)| Container {
+                        name: n.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".to_string()),
+                container_statuses: Some(
+                    containers
+                        .iter()
+                        .filter(|(_, running)| *running)
+                        .map(|(n, _)| ContainerStatus {
+                            name: n.to_string(),
+                            state: Some(ContainerState {
+                                running: Some(ContainerStateRunning::default()),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// The containers of the pod updates received so far
+    fn received_pod_updates(
+        event_rx: &mut tokio::sync::mpsc::Receiver<crate::ui::events::AppEvent>,
+    ) -> Vec<(String, bool)> {
+        let mut updates = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let crate::ui::events::AppEvent::PodUpdate(update) = event {
+                updates.push((update.info.key.container_name, update.info.skipped));
+            }
+        }
+        updates
+    }
+
     #[test]
     fn test_cli_parsing_deployment() {
         let args = vec!["kubectl-tail", "deployment/my-deployment"];
@@ -58,6 +182,180 @@ mod tests {
         assert!(cli.verbose);
     }
 
+    #[test]
+    fn test_cli_parsing_show_image() {
+        let args = vec!["kubectl-tail", "pod/my-pod", "--show-image"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(cli.show_image);
+    }
+
+    #[test]
+    fn test_image_tag() {
+        assert_eq!(utils::image_tag("nginx:1.25"), "1.25");
+        assert_eq!(utils::image_tag("nginx"), "latest");
+        assert_eq!(
+            utils::image_tag("registry.local:5000/team/api:v1.2.3"),
+            "v1.2.3"
+        );
+        assert_eq!(utils::image_tag("registry.local:5000/team/api"), "latest");
+        assert_eq!(
+            utils::image_tag("api@sha256:0123456789abcdef0123"),
+            "0123456789ab"
+        );
+    }
+
+    fn detail_test_line(line: &str) -> crate::types::LogMessage {
+        log_line("web", "app", line)
+    }
+
+    #[test]
+    fn test_max_line_width_truncates_by_chars() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        // Multi-byte characters: the width is in characters, the marker counts
+        // the bytes cut
+        let msg = LogEntry::new(detail_test_line("héllo wörld ünïcode"));
+        let area = Rect::new(0, 0, 40, 2);
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            vec![&msg],
+            LogViewOptions {
+                max_line_width: Some(5),
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(row.trim_end(), "héllo…(+17 bytes)");
+
+        // Lines within the limit are left alone
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            vec![&msg],
+            LogViewOptions {
+                max_line_width: Some(19),
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(row.trim_end(), "héllo wörld ünïcode");
+    }
+
+    #[test]
+    fn test_follow_keeps_newest_line_on_bottom_row() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        // The first line wraps onto three rows of a 10-column pane
+        let long = LogEntry::new(detail_test_line(&"a".repeat(25)));
+        let newest = LogEntry::new(detail_test_line("newest"));
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            vec![&long, &newest],
+            LogViewOptions {
+                follow: true,
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row = |y: u16| -> String {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect()
+        };
+        assert_eq!(row(1), "aaaaa     ");
+        assert_eq!(row(2), "newest    ");
+    }
+
+    #[test]
+    fn test_neutral_prefix_color() {
+        use crate::ui::events::handle_key_event;
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+        let msg = LogEntry::new(detail_test_line("hello"));
+        let render = |app: &App| {
+            let area = Rect::new(0, 0, 60, 1);
+            let mut buf = Buffer::empty(area);
+            LogView::new(
+                vec![&msg],
+                LogViewOptions {
+                    show_prefix: true,
+                    colorize_prefix: app.colorize_prefix,
+                    ..Default::default()
+                },
+            )
+            .render(area, &mut buf);
+            let row: String = (0..area.width)
+                .map(|x| buf[(x, 0)].symbol().to_string())
+                .collect();
+            (row.trim_end().to_string(), buf[(0, 0)].fg)
+        };
+
+        let mut app = App::new(100);
+        let (colored_row, colored) = render(&app);
+        assert_ne!(colored, Color::Gray);
+
+        // X keeps the prefix but drops its per-pod color
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT),
+        );
+        let (row, neutral) = render(&app);
+        assert_eq!(neutral, Color::Gray);
+        assert_eq!(row, colored_row);
+        assert!(row.contains("web/app"));
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('X'), KeyModifiers::SHIFT),
+        );
+        assert_eq!(render(&app).1, colored);
+    }
+
+    #[test]
+    fn test_line_detail_expand_and_collapse() {
+        use crate::ui::app::{App, AppMode};
+        use crate::ui::events::handle_key_event;
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut app = App::new(100);
+        app.max_line_width = Some(10);
+
+        // Nothing to expand yet
+        handle_key_event(&mut app, key(KeyCode::Enter));
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.detail_line.is_none());
+
+        app.add_log(detail_test_line("first"));
+        app.add_log(detail_test_line(&"x".repeat(50)));
+
+        // Enter expands the newest line while following, in full
+        handle_key_event(&mut app, key(KeyCode::Enter));
+        assert_eq!(app.mode, AppMode::LineDetail);
+        assert_eq!(app.detail_line.as_ref().unwrap().line, "x".repeat(50));
+
+        // Any key collapses it again
+        handle_key_event(&mut app, key(KeyCode::Char('q')));
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.detail_line.is_none());
+
+        // When scrolled, the top visible line is expanded
+        app.auto_scroll = false;
+        app.scroll_offset = 0;
+        handle_key_event(&mut app, key(KeyCode::Enter));
+        assert_eq!(app.detail_line.as_ref().unwrap().line, "first");
+    }
+
     #[test]
     fn test_selector_to_labels_string() {
         let mut labels = std::collections::BTreeMap::new();
@@ -86,4 +384,4514 @@ mod tests {
         let result = utils::selector_to_labels_string(&selector);
         assert_eq!(result, Some("env".to_string()));
     }
+
+    #[test]
+    fn test_add_log_preserves_container_order() {
+        let mut app = App::new(50);
+        // Interleave two containers and overflow the ring buffer
+        for i in 0..100 {
+            app.add_log(log_line("web-1", "app", &format!("app {}", i)));
+            if i % 3 == 0 {
+                app.add_log(log_line("web-2", "sidecar", &format!("sidecar {}", i)));
+            }
+        }
+
+        let app_lines: Vec<usize> = app
+            .log_buffer
+            .iter()
+            .filter(|m| m.container_name == "app")
+            .map(|m| m.line[4..].parse().unwrap())
+            .collect();
+        assert!(!app_lines.is_empty());
+        assert!(app_lines.windows(2).all(|w| w[1] == w[0] + 1));
+        assert_eq!(*app_lines.last().unwrap(), 99);
+    }
+
+    #[test]
+    fn test_cli_parsing_namespace_list() {
+        let args = vec!["kubectl-tail", "-l", "app=web", "-n", "ns1,ns2,ns3"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.namespace, vec!["ns1", "ns2", "ns3"]);
+    }
+
+    #[test]
+    fn test_group_resource_specs_expands_namespaces() {
+        let specs = vec![
+            utils::parse_resource_spec("deployment/api").unwrap(),
+            utils::parse_resource_spec("web-1").unwrap(),
+        ];
+        let namespaces = vec![namespace("ns1"), namespace("ns2")];
+        let grouped =
+            crate::group_resource_specs(specs, &[("ctx".to_string(), namespaces.clone())], true);
+
+        assert_eq!(grouped.len(), 2);
+        for ns in &namespaces {
+            let names: Vec<&str> = grouped[&("ctx".to_string(), ns.clone())]
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["api", "web-1"]);
+        }
+    }
+
+    #[test]
+    fn test_group_resource_specs_selector_only() {
+        let namespaces = vec![namespace("a"), namespace("b"), namespace("c")];
+        let grouped =
+            crate::group_resource_specs(Vec::new(), &[("ctx".to_string(), namespaces)], true);
+        assert_eq!(grouped.len(), 3);
+        assert!(grouped.values().all(|specs| specs.is_empty()));
+    }
+
+    #[test]
+    fn test_group_resource_specs_keeps_explicit_namespace() {
+        let specs = vec![utils::parse_resource_spec("prod/deployment/api").unwrap()];
+        let namespaces = vec![namespace("ns1"), namespace("ns2")];
+        let grouped = crate::group_resource_specs(specs, &[("ctx".to_string(), namespaces)], false);
+        assert_eq!(grouped.len(), 1);
+        assert!(grouped.contains_key(&("ctx".to_string(), namespace("prod"))));
+    }
+
+    #[tokio::test]
+    async fn test_selector_uses_each_contexts_default_namespace() {
+        let client = |namespace: &str| {
+            let mut config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+            config.default_namespace = namespace.to_string();
+            kube::Client::try_from(config).unwrap()
+        };
+        let clients = vec![
+            ("us".to_string(), client("team-a")),
+            ("eu".to_string(), client("team-b")),
+        ];
+        let contexts = vec!["us".to_string(), "eu".to_string()];
+
+        let defaults = crate::default_namespaces(&clients, &contexts, &[]);
+        assert_eq!(
+            defaults,
+            vec![
+                ("us".to_string(), vec![namespace("team-a")]),
+                ("eu".to_string(), vec![namespace("team-b")]),
+            ]
+        );
+        let grouped = crate::group_resource_specs(
+            vec![utils::parse_resource_spec("deployment/api").unwrap()],
+            &defaults,
+            true,
+        );
+        let mut keys: Vec<_> = grouped.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                ("eu".to_string(), namespace("team-b")),
+                ("us".to_string(), namespace("team-a")),
+            ]
+        );
+
+        // -n applies to every context alike
+        let defaults = crate::default_namespaces(&clients, &contexts, &[namespace("shared")]);
+        assert!(
+            defaults
+                .iter()
+                .all(|(_, ns)| ns == &vec![namespace("shared")])
+        );
+
+        let cli = Cli::try_parse_from([
+            "kubectl-tail",
+            "-l",
+            "app=x",
+            "--context",
+            "us",
+            "--context",
+            "eu",
+        ])
+        .unwrap();
+        assert_eq!(cli.context, contexts);
+    }
+
+    #[tokio::test]
+    async fn test_all_namespaces_watches_cluster_wide() {
+        let config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+        let clients = vec![("us".to_string(), kube::Client::try_from(config).unwrap())];
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "-A", "-l", "app=x"]).unwrap();
+        let configs = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].scope, crate::Scope::All);
+        assert_eq!(
+            configs[0].label_selectors,
+            vec![("app=x".to_string(), None)]
+        );
+
+        // A pod name doesn't say which namespace it is in
+        let cli = Cli::try_parse_from(["kubectl-tail", "-A", "my-pod"]).unwrap();
+        let err = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ambiguous across namespaces"));
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--all-namespaces", "all"]).unwrap();
+        let configs = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap();
+        assert_eq!(configs[0].scope, crate::Scope::All);
+
+        assert!(Cli::try_parse_from(["kubectl-tail", "-A", "-n", "prod", "-l", "app=x"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_env_leaves_out_other_contexts() {
+        let client = || {
+            let config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+            kube::Client::try_from(config).unwrap()
+        };
+        let clients = vec![("us".to_string(), client()), ("eu".to_string(), client())];
+        let environments = std::collections::HashMap::from([
+            ("us".to_string(), "prod".to_string()),
+            ("eu".to_string(), "staging".to_string()),
+        ]);
+
+        let args = [
+            "kubectl-tail",
+            "-l",
+            "app=x",
+            "--context",
+            "us",
+            "--context",
+            "eu",
+        ];
+        let cli = Cli::try_parse_from(args.iter().chain(&["--env", "prod"])).unwrap();
+        let configs = crate::parse_resources_and_selectors(&clients, &cli, Some(&environments))
+            .await
+            .unwrap();
+        let contexts: Vec<&str> = configs.iter().map(|c| c.context.as_str()).collect();
+        assert_eq!(contexts, vec!["us"]);
+
+        // The TUI can switch environments, so it watches every context
+        let configs = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap();
+        assert_eq!(configs.len(), 2);
+
+        let cli = Cli::try_parse_from(args.iter().chain(&["--env", "dev"])).unwrap();
+        let err = crate::parse_resources_and_selectors(&clients, &cli, Some(&environments))
+            .await
+            .unwrap_err();
+        assert_eq!(crate::exit::code_of(&err), crate::exit::ExitCode::Usage);
+    }
+
+    #[tokio::test]
+    async fn test_strict_checks_explicit_pods() {
+        // Nothing listens here, so every request fails fast
+        let config = kube::Config::new("https://127.0.0.1:1".parse().unwrap());
+        let clients = vec![("us".to_string(), kube::Client::try_from(config).unwrap())];
+
+        // Without --strict a pod name is watched for, whether it exists or not
+        let cli = Cli::try_parse_from(["kubectl-tail", "-n", "prod", "pod/web-0"]).unwrap();
+        let configs = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap();
+        assert_eq!(configs[0].explicit_pods["web-0"], vec![None]);
+
+        // With it, the pod is looked up first
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "--strict", "-n", "prod", "pod/web-0"]).unwrap();
+        let err = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Could not look up pod web-0"));
+        // A cluster that can't be reached is not a usage error
+        assert_eq!(
+            crate::exit::code_of(&err),
+            crate::exit::ExitCode::Unreachable
+        );
+
+        // Arguments that don't make sense are
+        let cli = Cli::try_parse_from(["kubectl-tail", "-n", "prod", "deploy/web[0-1]"]).unwrap();
+        let err = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap_err();
+        assert_eq!(crate::exit::code_of(&err), crate::exit::ExitCode::Usage);
+
+        // Selectors are never checked
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "--strict", "-n", "prod", "-l", "app=x"]).unwrap();
+        assert!(
+            crate::parse_resources_and_selectors(&clients, &cli, None)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_redaction() {
+        let redactions = vec![
+            utils::parse_redaction(r"(token=)\S+=${1}***").unwrap(),
+            utils::parse_redaction(r"\d+\.\d+\.\d+\.\d+=<ip>").unwrap(),
+        ];
+        assert_eq!(
+            utils::apply_redactions("login from 10.0.0.1 token=abc123 ok", &redactions),
+            "login from <ip> token=*** ok"
+        );
+        assert!(utils::parse_redaction("no-separator").is_err());
+        assert!(utils::parse_redaction("(unclosed=x").is_err());
+    }
+
+    #[test]
+    fn test_cli_parsing_redact_repeatable() {
+        let args = vec![
+            "kubectl-tail",
+            "pod/my-pod",
+            "--redact",
+            "secret=***",
+            "--redact",
+            "[a-z]+@example\\.com=<email>",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.redact.len(), 2);
+    }
+
+    #[test]
+    fn test_cli_parsing_poll_interval_rejects_zero() {
+        let cli = Cli::try_parse_from(["kubectl-tail", "app", "--poll", "--poll-interval", "30s"])
+            .unwrap();
+        assert_eq!(cli.poll_interval, std::time::Duration::from_secs(30));
+        for zero in ["0", "0s", "0ms"] {
+            let err = Cli::try_parse_from(["kubectl-tail", "app", "--poll-interval", zero])
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        use std::time::Duration;
+        assert_eq!(utils::parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(utils::parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(utils::parse_duration("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(
+            utils::parse_duration("1h30m"),
+            Ok(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            utils::parse_duration("250ms"),
+            Ok(Duration::from_millis(250))
+        );
+        assert!(utils::parse_duration("").is_err());
+        assert!(utils::parse_duration("5x").is_err());
+        assert!(utils::parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn test_annotation_filter() {
+        let mut annotations = std::collections::BTreeMap::new();
+        annotations.insert("team".to_string(), "payments".to_string());
+        annotations.insert("tier".to_string(), "backend".to_string());
+
+        let eq = utils::parse_annotation_filter("team=payments").unwrap();
+        let ne = utils::parse_annotation_filter("team=search").unwrap();
+        let exists = utils::parse_annotation_filter("tier").unwrap();
+        let missing = utils::parse_annotation_filter("owner").unwrap();
+        assert!(eq.matches(&annotations));
+        assert!(!ne.matches(&annotations));
+        assert!(exists.matches(&annotations));
+        assert!(!missing.matches(&annotations));
+        assert!(utils::parse_annotation_filter("=x").is_err());
+    }
+
+    #[test]
+    fn test_completion_tracker_summary() {
+        use crate::completion::{CompletionTracker, ContainerExit};
+
+        let tracker = CompletionTracker::default();
+        assert!(!tracker.all_finished());
+        tracker.register("test/default/job-a");
+        tracker.register("test/default/job-b");
+        tracker.finish(
+            "test/default/job-a",
+            vec![ContainerExit {
+                container: "main".to_string(),
+                exit_code: 0,
+                reason: Some("Completed".to_string()),
+            }],
+        );
+        // Unregistered pods are ignored
+        tracker.finish("test/default/other", vec![]);
+        assert!(tracker.is_finished("test/default/job-a"));
+        assert!(!tracker.all_finished());
+
+        tracker.finish(
+            "test/default/job-b",
+            vec![ContainerExit {
+                container: "main".to_string(),
+                exit_code: 2,
+                reason: Some("Error".to_string()),
+            }],
+        );
+        assert!(tracker.all_finished());
+        let (lines, success) = tracker.summary();
+        assert!(!success);
+        assert_eq!(
+            lines,
+            vec![
+                "test/default/job-a/main: exit code 0 (Completed)".to_string(),
+                "test/default/job-b/main: exit code 2 (Error)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_highlight_rules() {
+        use crate::config::Config;
+        use crate::ui::widgets::highlight_spans;
+        use ratatui::style::{Color, Modifier};
+
+        let config = Config::parse(
+            "highlights:\n  - pattern: panic\n    color: red\n    bold: true\n  - pattern: pan\n    color: blue\n",
+        )
+        .unwrap();
+        let rules = config.highlight_rules().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].style.fg, Some(Color::Red));
+        assert!(rules[0].style.add_modifier.contains(Modifier::BOLD));
+        assert!(Config::parse("").unwrap().highlights.is_empty());
+        assert!(
+            Config::parse("highlights: [{pattern: x, color: nocolor}]")
+                .unwrap()
+                .highlight_rules()
+                .is_err()
+        );
+
+        // The earlier rule claims "panic"; the overlapping "pan" rule is skipped
+        let spans = highlight_spans("a panic here", None, &rules);
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["a ", "panic", " here"]);
+        assert_eq!(spans[1].style.fg, Some(Color::Red));
+
+        // Search takes precedence over highlight rules
+        let search = regex::Regex::new("(?i)ic here").unwrap();
+        let spans = highlight_spans("a panic here", Some(&search), &rules);
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["a ", "pan", "ic here"]);
+        assert_eq!(spans[1].style.fg, Some(Color::Blue));
+        assert_eq!(spans[2].style.bg, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn test_pod_warning_badge() {
+        use crate::ui::app::{PodInfo, PodKey};
+        use crate::ui::events::PodWarningEvent;
+
+        let warning = |event: &str, reason: &str, minutes_ago: i64| PodWarningEvent {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "web-1".to_string(),
+            event_name: event.to_string(),
+            reason: reason.to_string(),
+            last_seen: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+        };
+        let info = |phase: &str| PodInfo {
+            phase: phase.to_string(),
+            ..pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: "web-1".to_string(),
+                container_name: "app".to_string(),
+            })
+        };
+
+        let mut app = App::new(100);
+        app.add_pod(info("Pending"));
+        app.add_pod_warning(warning("web-1.a", "FailedScheduling", 3));
+        app.add_pod_warning(warning("web-1.b", "BackOff", 1));
+        // Repeats of the same event don't inflate the count
+        app.add_pod_warning(warning("web-1.b", "BackOff", 0));
+        // Events outside the window are ignored
+        app.add_pod_warning(warning("web-1.c", "Unhealthy", 60));
+        let badge = app
+            .pod_warnings
+            .get("test/default/web-1")
+            .and_then(|w| w.badge());
+        assert_eq!(badge.as_deref(), Some("⚠2 BackOff"));
+
+        // Recovering to Running clears the badge
+        app.add_pod(info("Running"));
+        assert!(app.pod_warnings.is_empty());
+    }
+
+    #[test]
+    fn test_fit_to_width() {
+        assert_eq!(utils::fit_to_width("[a/b]", 8), "[a/b]   ");
+        assert_eq!(utils::fit_to_width("[a/b]", 5), "[a/b]");
+        assert_eq!(
+            utils::fit_to_width("[prod.default/web-7d9f/app]", 12),
+            "[prod.…/app]"
+        );
+        assert_eq!(
+            utils::fit_to_width("[prod.default/web-7d9f/app]", 12)
+                .chars()
+                .count(),
+            12
+        );
+        assert_eq!(utils::fit_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_restart_failed_streams() {
+        use crate::types::{ConnectionState, StreamStates};
+        use crate::ui::app::PodKey;
+        use std::sync::Arc;
+
+        let key = |container: &str| PodKey {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "web-1".to_string(),
+            container_name: container.to_string(),
+        };
+        let states = Arc::new(StreamStates::default());
+        states.set(
+            &key("app"),
+            ConnectionState::Failed("forbidden".to_string()),
+        );
+        states.set(&key("proxy"), ConnectionState::Connected);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut app = App::new(100);
+        app.stream_states = Some(states);
+        app.respawn_tx = Some(tx);
+        for container in ["app", "proxy"] {
+            app.add_pod(pod_info(key(container)));
+        }
+
+        // Without the sidebar, the current line's container is targeted
+        app.add_log(log_line("web-1", "proxy", "ok"));
+        app.restart_failed_streams();
+        assert!(rx.try_recv().is_err());
+
+        app.add_log(log_line("web-1", "app", "denied"));
+        app.restart_failed_streams();
+        assert_eq!(rx.try_recv().unwrap(), key("app"));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_stream_error_status() {
+        use crate::kubernetes::stream_error_status;
+        use kube::core::ErrorResponse;
+
+        let api_error = |code: u16, reason: &str| {
+            kube::Error::Api(ErrorResponse {
+                status: "Failure".to_string(),
+                message: "something went wrong".to_string(),
+                reason: reason.to_string(),
+                code,
+            })
+        };
+        assert_eq!(
+            stream_error_status(&api_error(500, "InternalError")),
+            "500 InternalError"
+        );
+        assert_eq!(
+            stream_error_status(&api_error(404, "NotFound")),
+            "404 NotFound"
+        );
+        assert_eq!(stream_error_status(&api_error(502, "")), "502");
+    }
+
+    #[tokio::test]
+    async fn test_newest_selection() {
+        use k8s_openapi::api::core::v1::{Pod, PodStatus};
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+
+        let pod = |name: &str, minutes_old: i64, phase: &str| Pod {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                creation_timestamp: Some(Time(
+                    chrono::Utc::now() - chrono::Duration::minutes(minutes_old),
+                )),
+                ..Default::default()
+            },
+            status: Some(PodStatus {
+                phase: Some(phase.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
+
+        let ctx = test_context();
+        let mut selection = crate::NewestSelection::new(2);
+        selection.update("web-a", &pod("web-a", 30, "Running"), None, &ctx);
+        selection.update("web-b", &pod("web-b", 20, "Running"), None, &ctx);
+        selection.update("web-c", &pod("web-c", 10, "Pending"), None, &ctx);
+        assert_eq!(selection.selected(), names(&["web-b", "web-c"]));
+
+        // A newer pod pushes out the oldest selected one
+        selection.update("web-d", &pod("web-d", 1, "Running"), None, &ctx);
+        assert_eq!(selection.selected(), names(&["web-c", "web-d"]));
+
+        // Finished or deleted pods make room for older ones again
+        selection.update("web-d", &pod("web-d", 1, "Succeeded"), None, &ctx);
+        selection.remove("web-c");
+        assert_eq!(selection.selected(), names(&["web-a", "web-b"]));
+
+        // Pods --qos leaves out are not ranked, so they take no one's place
+        let ctx = crate::TailContext {
+            qos: std::sync::Arc::new(vec!["Guaranteed".to_string()]),
+            ..ctx
+        };
+        let guaranteed = |name: &str, minutes_old: i64| {
+            let mut pod = pod(name, minutes_old, "Running");
+            pod.status.as_mut().unwrap().qos_class = Some("Guaranteed".to_string());
+            pod
+        };
+        let mut selection = crate::NewestSelection::new(1);
+        selection.update("web-a", &guaranteed("web-a", 30), None, &ctx);
+        selection.update("web-b", &pod("web-b", 1, "Running"), None, &ctx);
+        assert_eq!(selection.selected(), names(&["web-a"]));
+
+        // Likewise pods of other workload kinds than --kinds
+        let ctx = crate::TailContext {
+            kinds: std::sync::Arc::new(vec!["statefulset".to_string()]),
+            ..test_context()
+        };
+        let mut selection = crate::NewestSelection::new(1);
+        selection.update(
+            "db-0",
+            &pod("db-0", 30, "Running"),
+            Some("statefulset/db"),
+            &ctx,
+        );
+        selection.update(
+            "web-b",
+            &pod("web-b", 1, "Running"),
+            Some("deployment/web"),
+            &ctx,
+        );
+        assert_eq!(selection.selected(), names(&["db-0"]));
+    }
+
+    #[test]
+    fn test_search_wrap_message() {
+        let mut app = App::new(100);
+        for line in ["error one", "ok", "error two"] {
+            app.add_log(log_line("web-1", "app", line));
+        }
+        app.search_pattern = "error".to_string();
+        app.update_search_matches();
+        assert_eq!(app.search_matches, vec![0, 2]);
+
+        app.jump_to_next_match();
+        assert!(app.status_message.is_none());
+        app.jump_to_next_match();
+        assert_eq!(
+            app.status_message.as_ref().unwrap().0,
+            "search wrapped to top"
+        );
+        app.jump_to_prev_match();
+        assert_eq!(
+            app.status_message.as_ref().unwrap().0,
+            "search wrapped to bottom"
+        );
+
+        app.update_stats();
+        assert!(
+            app.active_filters
+                .contains(&"search: error [2/2]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sort_by_time_tiebreak() {
+        let ts = chrono::Utc::now();
+        let line = |pod: &str, container: &str, text: &str, offset_ms: i64| LogMessage {
+            timestamp: ts + chrono::Duration::milliseconds(offset_ms),
+            ..log_line(pod, container, text)
+        };
+
+        let mut app = App::new(100);
+        app.add_log(line("web-b", "app", "b1", 0));
+        app.add_log(line("web-a", "side", "a-side", 0));
+        app.add_log(line("web-a", "app", "a1", 0));
+        app.add_log(line("web-a", "app", "a2", 0));
+        app.add_log(line("web-c", "app", "early", -5));
+
+        app.toggle_sort_by_time();
+        let order: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(order, vec!["early", "a1", "a2", "a-side", "b1"]);
+
+        app.toggle_sort_by_time();
+        let order: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(order, vec!["b1", "a-side", "a1", "a2", "early"]);
+    }
+
+    #[test]
+    fn test_containers_to_tail_running_only() {
+        use k8s_openapi::api::core::v1::{
+            Container, ContainerState, ContainerStateRunning, ContainerStateWaiting,
+            ContainerStatus, Pod, PodSpec, PodStatus,
+        };
+
+        let status = |name: &str, running: bool| ContainerStatus {
+            name: name.to_string(),
+            state: Some(if running {
+                ContainerState {
+                    running: Some(ContainerStateRunning::default()),
+                    ..Default::default()
+                }
+            } else {
+                ContainerState {
+                    waiting: Some(ContainerStateWaiting {
+                        reason: Some("ContainerCreating".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            }),
+            ..Default::default()
+        };
+        let pod = Pod {
+            spec: Some(PodSpec {
+                containers: ["app", "proxy"]
+                    .iter()
+                    .map(|n| Container {
+                        name: n.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                container_statuses: Some(vec![status("app", true), status("proxy", false)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let names = |container: &Option<String>, running_only: bool| -> Vec<String> {
+            crate::containers_to_tail(&pod, container, running_only, Default::default(), &[])
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        assert_eq!(names(&None, false), vec!["app", "proxy"]);
+        assert_eq!(names(&None, true), vec!["app"]);
+        let proxy = Some("proxy".to_string());
+        assert!(names(&proxy, true).is_empty());
+        assert_eq!(names(&proxy, false), vec!["proxy"]);
+
+        // A value that is not a plain name selects every container it matches in full
+        let regex = |r: &str| Some(r.to_string());
+        assert_eq!(names(&regex("app|proxy"), false), vec!["app", "proxy"]);
+        assert_eq!(names(&regex("a.*"), false), vec!["app"]);
+        assert_eq!(names(&regex("app|proxy"), true), vec!["app"]);
+        assert!(names(&regex("istio-.*"), false).is_empty());
+        assert!(utils::container_regex("istio-proxy-2").is_none());
+        assert!(matches!(utils::container_regex("app|("), Some(Err(_))));
+
+        // `~` searches anywhere in the name, so one pattern picks the same
+        // container out of every pod of a workload; pods without one get none
+        assert_eq!(names(&regex("~^ap"), false), vec!["app"]);
+        assert_eq!(names(&regex("~xy$"), false), vec!["proxy"]);
+        assert!(names(&regex("~api$"), false).is_empty());
+        assert!(names(&regex("worker"), false).is_empty());
+        assert!(matches!(utils::container_regex("~app"), Some(Ok(_))));
+
+        // --exclude-container globs win over --container
+        let without = |container: &Option<String>, exclude: &[&str]| -> Vec<String> {
+            let exclude: Vec<String> = exclude.iter().map(|e| e.to_string()).collect();
+            crate::containers_to_tail(&pod, container, false, Default::default(), &exclude)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        assert_eq!(without(&None, &["*xy"]), vec!["app"]);
+        assert!(without(&proxy, &["proxy"]).is_empty());
+        assert_eq!(
+            without(&regex("app|proxy"), &["pr?xy", "other"]),
+            vec!["app"]
+        );
+        let cli = Cli::try_parse_from([
+            "kubectl-tail",
+            "--exclude-container",
+            "istio-proxy",
+            "--exclude-container",
+            "*-proxy",
+            "pod/a",
+        ])
+        .unwrap();
+        assert_eq!(cli.exclude_container, vec!["istio-proxy", "*-proxy"]);
+
+        let cli = Cli::try_parse_from([
+            "kubectl-tail",
+            "-l",
+            "app=x",
+            "--container-state",
+            "running",
+        ])
+        .unwrap();
+        assert_eq!(cli.container_state, crate::cli::ContainerState::Running);
+    }
+
+    #[tokio::test]
+    async fn test_pod_without_matching_container_is_skipped() {
+        use crate::kubernetes::TailOptions;
+        use crate::types::StreamStates;
+        use k8s_openapi::api::core::v1::{
+            Container, ContainerState, ContainerStateRunning, ContainerStatus, Pod, PodSpec,
+            PodStatus,
+        };
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let pod = Pod {
+            metadata: kube::api::ObjectMeta {
+                name: Some("web-1".to_string()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                containers: ["app", "proxy"]
+                    .iter()
+                    .map(|n| Container {
+                        name: n.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".to_string()),
+                // Only `app` has started
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "app".to_string(),
+                    state: Some(ContainerState {
+                        running: Some(ContainerStateRunning::default()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+        };
+        let ctx = crate::TailContext {
+            running_only: true,
+            ..test_context()
+        };
+
+        // Handle the pod with a --container value and report what happened:
+        // whether any stream task was spawned or pod shown, and the timeline
+        let handle = |container: &str| {
+            let ctx = crate::TailContext {
+                container: Some(container.to_string()),
+                options: TailOptions {
+                    stream_states: Arc::new(StreamStates::default()),
+                    ..ctx.options.clone()
+                },
+                ..ctx.clone()
+            };
+            let pod = pod.clone();
+            async move {
+                let handles = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+                let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(16);
+                crate::handle_pod_event(pod, &handles, ctx.clone(), Some(event_tx), true).await;
+                let events: Vec<String> = ctx
+                    .options
+                    .stream_states
+                    .timeline
+                    .snapshot(Some("prod/default/web-1"))
+                    .into_iter()
+                    .map(|e| e.event)
+                    .collect();
+                let spawned = !handles.lock().await.is_empty()
+                    || !ctx.options.stream_states.snapshot().is_empty();
+                (spawned, event_rx.try_recv().is_ok(), events)
+            }
+        };
+
+        // No container matches: the pod is skipped for good rather than
+        // waited on, with no stream spawned and nothing listed
+        let (spawned, listed, events) = handle("~^worker").await;
+        assert!(!spawned);
+        assert!(!listed);
+        assert_eq!(events, vec!["skipped: no container matching '~^worker'"]);
+
+        // A matching container that hasn't started yet is waited for
+        let (spawned, listed, events) = handle("~xy$").await;
+        assert!(!spawned);
+        assert!(!listed);
+        assert_eq!(events, vec!["no running containers yet, waiting"]);
+    }
+
+    #[tokio::test]
+    async fn test_tracked_pod_update_lists_tailed_containers() {
+        use crate::ui::app::PodKey;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        // Tailing `app` of a pod with -c ~^(app|proxy)$ --container-state
+        // running: `proxy` hasn't started and `sidecar` doesn't match
+        let pod = running_pod(
+            "web-1",
+            &[("app", true), ("proxy", false), ("sidecar", true)],
+        );
+        let ctx = crate::TailContext {
+            container: Some("~^(app|proxy)$".to_string()),
+            ..test_context()
+        };
+        let idle = tokio::spawn(std::future::pending::<()>());
+        let handles = Arc::new(tokio::sync::Mutex::new(HashMap::from([(
+            PodKey {
+                cluster: "prod".to_string(),
+                namespace: "default".to_string(),
+                pod_name: "web-1".to_string(),
+                container_name: "app".to_string(),
+            },
+            vec![idle.abort_handle()],
+        )])));
+        let update = |running_only: bool| {
+            let ctx = crate::TailContext {
+                running_only,
+                ..ctx.clone()
+            };
+            let (pod, handles) = (pod.clone(), handles.clone());
+            async move {
+                let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(16);
+                crate::handle_pod_event(pod, &handles, ctx, Some(event_tx), true).await;
+                received_pod_updates(&mut event_rx)
+            }
+        };
+
+        assert_eq!(update(true).await, vec![("app".to_string(), false)]);
+        // Any container state: `proxy` is picked up, then listed with `app`
+        assert_eq!(
+            update(false).await,
+            vec![
+                ("proxy".to_string(), false),
+                ("app".to_string(), false),
+                ("proxy".to_string(), false)
+            ]
+        );
+        for handle in handles.lock().await.values().flatten() {
+            handle.abort();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_named_pod_with_two_containers() {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let config = kube::Config::new("https://127.0.0.1:1".parse().unwrap());
+        let clients = vec![("us".to_string(), kube::Client::try_from(config).unwrap())];
+        let cli = Cli::try_parse_from([
+            "kubectl-tail",
+            "-n",
+            "prod",
+            "pod/web-0:app",
+            "pod/web-0:proxy",
+        ])
+        .unwrap();
+        let configs = crate::parse_resources_and_selectors(&clients, &cli, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            configs[0].explicit_pods["web-0"],
+            vec![Some("app".to_string()), Some("proxy".to_string())]
+        );
+
+        // Each container's watcher tails its own, whichever sees the pod first
+        let pod = running_pod(
+            "web-0",
+            &[("app", true), ("proxy", true), ("sidecar", true)],
+        );
+        let handles = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        for container in ["app", "proxy"] {
+            let ctx = test_context().for_container(&Some(container.to_string()));
+            crate::handle_pod_event(pod.clone(), &handles, ctx, None, true).await;
+        }
+        let mut tailed: Vec<String> = handles
+            .lock()
+            .await
+            .keys()
+            .map(|k| k.container_name.clone())
+            .collect();
+        tailed.sort();
+        assert_eq!(tailed, vec!["app", "proxy"]);
+        for handle in handles.lock().await.values().flatten() {
+            handle.abort();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recreated_pod_is_tailed_once() {
+        use crate::ui::app::PodKey;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let key = PodKey {
+            cluster: "prod".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "web-0".to_string(),
+            container_name: "app".to_string(),
+        };
+        let ctx = test_context();
+        let handles = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let pod = running_pod("web-0", &[("app", true)]);
+        crate::handle_pod_event(pod.clone(), &handles, ctx.clone(), None, true).await;
+        let old = handles.lock().await[&key][0].clone();
+
+        // Deleted, then re-created under the same name as a new instance
+        crate::handle_pod_deleted("web-0".to_string(), &handles, &ctx, &None).await;
+        let mut recreated = pod;
+        recreated.metadata.uid = Some("web-0-uid-2".to_string());
+        for _ in 0..2 {
+            crate::handle_pod_event(recreated.clone(), &handles, ctx.clone(), None, true).await;
+        }
+        tokio::task::yield_now().await;
+        assert!(old.is_finished());
+        let guard = handles.lock().await;
+        assert_eq!(guard.len(), 1);
+        assert_eq!(guard[&key].len(), 1);
+        assert!(!guard[&key][0].is_finished());
+        for handle in guard.values().flatten() {
+            handle.abort();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pod_losing_annotation_is_no_longer_tailed() {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let ctx = crate::TailContext {
+            annotations: Arc::new(vec![utils::parse_annotation_filter("tail=true").unwrap()]),
+            ..test_context()
+        };
+        let handles = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let mut pod = running_pod("web-0", &[("app", true)]);
+        crate::handle_pod_event(pod.clone(), &handles, ctx.clone(), None, true).await;
+        assert!(handles.lock().await.is_empty());
+
+        pod.metadata.annotations = Some([("tail".to_string(), "true".to_string())].into());
+        crate::handle_pod_event(pod.clone(), &handles, ctx.clone(), None, true).await;
+        let tailing = handles
+            .lock()
+            .await
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(tailing.len(), 1);
+
+        // The annotation is removed while the pod keeps running
+        pod.metadata.annotations = None;
+        crate::handle_pod_event(pod, &handles, ctx, None, true).await;
+        tokio::task::yield_now().await;
+        assert!(handles.lock().await.is_empty());
+        assert!(tailing[0].is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_skipped_pod_lists_containers_it_would_tail() {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        // Left out by --newest, with -c ~^(app|proxy)$ --container-state running
+        let pod = running_pod(
+            "web-0",
+            &[("app", true), ("proxy", false), ("sidecar", true)],
+        );
+        let ctx = crate::TailContext {
+            container: Some("~^(app|proxy)$".to_string()),
+            running_only: true,
+            newest: Some(1),
+            ..test_context()
+        };
+        let handles = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(16);
+        crate::handle_pod_event(pod, &handles, ctx, Some(event_tx), false).await;
+        assert!(handles.lock().await.is_empty());
+        assert_eq!(
+            received_pod_updates(&mut event_rx),
+            vec![("app".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_containers_to_tail_init_and_ephemeral() {
+        use crate::ExtraContainers;
+        use crate::kubernetes::finished_container;
+        use crate::ui::app::ContainerKind;
+        use k8s_openapi::api::core::v1::{
+            Container, ContainerState, ContainerStateRunning, ContainerStateTerminated,
+            ContainerStatus, EphemeralContainer, Pod, PodSpec, PodStatus,
+        };
+
+        let container = |name: &str| Container {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let running = |name: &str| ContainerStatus {
+            name: name.to_string(),
+            state: Some(ContainerState {
+                running: Some(ContainerStateRunning::default()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let completed = |name: &str| ContainerStatus {
+            name: name.to_string(),
+            state: Some(ContainerState {
+                terminated: Some(ContainerStateTerminated {
+                    exit_code: 0,
+                    reason: Some("Completed".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let pod = Pod {
+            spec: Some(PodSpec {
+                restart_policy: Some("Always".to_string()),
+                containers: vec![container("app")],
+                init_containers: Some(vec![
+                    container("migrate"),
+                    Container {
+                        restart_policy: Some("Always".to_string()),
+                        ..container("sidecar")
+                    },
+                ]),
+                ephemeral_containers: Some(vec![EphemeralContainer {
+                    name: "debugger".to_string(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".to_string()),
+                container_statuses: Some(vec![running("app")]),
+                init_container_statuses: Some(vec![completed("migrate"), running("sidecar")]),
+                ephemeral_container_statuses: Some(vec![completed("debugger")]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // Only regular containers by default
+        assert_eq!(
+            crate::containers_to_tail(&pod, &None, false, ExtraContainers::default(), &[]),
+            vec![("app".to_string(), ContainerKind::Regular)]
+        );
+        let all = ExtraContainers {
+            init: true,
+            ephemeral: true,
+        };
+        let expected = vec![
+            ("app".to_string(), ContainerKind::Regular),
+            ("migrate".to_string(), ContainerKind::Init),
+            ("sidecar".to_string(), ContainerKind::Init),
+            ("debugger".to_string(), ContainerKind::Ephemeral),
+        ];
+        assert_eq!(
+            crate::containers_to_tail(&pod, &None, false, all, &[]),
+            expected
+        );
+        // Finished init and ephemeral containers never run again, so they are
+        // tailed even when only running containers are
+        assert_eq!(
+            crate::containers_to_tail(&pod, &None, true, all, &[]),
+            expected
+        );
+        assert_eq!(
+            crate::containers_to_tail(&pod, &Some("migrate".to_string()), false, all, &[]),
+            vec![("migrate".to_string(), ContainerKind::Init)]
+        );
+
+        // A completed init container is done despite the pod's restart policy,
+        // and so is an ephemeral one
+        assert_eq!(
+            finished_container(&pod, "migrate").as_deref(),
+            Some("exit code 0 (Completed)")
+        );
+        assert_eq!(
+            finished_container(&pod, "debugger").as_deref(),
+            Some("exit code 0 (Completed)")
+        );
+        assert_eq!(finished_container(&pod, "sidecar"), None);
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--include-init", "pod/a"]).unwrap();
+        assert!(cli.include_init);
+        assert!(!cli.include_ephemeral);
+    }
+
+    #[test]
+    fn test_state_dump_excludes_log_contents() {
+        use crate::types::{ConnectionState, StreamStates};
+        use crate::ui::app::{PodInfo, PodKey};
+        use std::sync::Arc;
+
+        let key = PodKey {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "web-1".to_string(),
+            container_name: "app".to_string(),
+        };
+        let states = Arc::new(StreamStates::default());
+        states.set(
+            &key,
+            ConnectionState::Reconnecting(Some("500 InternalError".to_string())),
+        );
+        states.set(&key, ConnectionState::Connected);
+
+        let mut app = App::new(100);
+        app.stream_states = Some(states);
+        app.watch_summary = vec!["test/default selectors=[\"app=web\"] pods={}".to_string()];
+        app.add_pod(PodInfo {
+            restarts: 2,
+            ..pod_info(key.clone())
+        });
+        app.add_log(log_line("web-1", "app", "password=hunter2"));
+
+        let dump = app.state_dump();
+        assert!(dump.contains("test/default selectors=[\"app=web\"]"));
+        assert!(dump.contains("lines: 1/100"));
+        assert!(dump.contains("test/default/web-1/app phase=Running enabled=true"));
+        assert!(dump.contains("test/default/web-1/app state=Connected reconnects=1"));
+        assert!(dump.contains("last_error=\"500 InternalError\""));
+        assert!(!dump.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_kinds_group_pods_by_workload() {
+        use crate::ui::app::{PodInfo, PodKey, SidebarLayout, SidebarPods, SidebarRow, pod_tree};
+        use std::collections::HashSet;
+
+        let kinds = vec!["deployment".to_string(), "pod".to_string()];
+        assert!(crate::matches_kinds(Some("deployment/web"), &kinds));
+        assert!(crate::matches_kinds(None, &kinds));
+        assert!(!crate::matches_kinds(Some("statefulset/db"), &kinds));
+        assert!(crate::matches_kinds(Some("statefulset/db"), &[]));
+
+        let all = crate::types::ResourceSpec {
+            context: None,
+            namespace: None,
+            kind: None,
+            name: "all".to_string(),
+            container: None,
+        };
+        assert!(crate::is_all_spec(&all));
+        let pod_named_all = crate::types::ResourceSpec {
+            kind: Some("pod".to_string()),
+            ..all
+        };
+        assert!(!crate::is_all_spec(&pod_named_all));
+
+        let pod = |name: &str, workload: Option<&str>| PodInfo {
+            workload: workload.map(str::to_string),
+            ..pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: name.to_string(),
+                container_name: "app".to_string(),
+            })
+        };
+        let pods = vec![
+            pod("web-b", Some("deployment/web")),
+            pod("debug", None),
+            pod("web-a", Some("deployment/web")),
+        ];
+        let tree = pod_tree(&pods, None, &[]);
+        let keys: Vec<_> = tree["test"]["default"]
+            .keys()
+            .map(|slot| (slot.workload.clone(), slot.pod_name.clone()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("".to_string(), "debug".to_string()),
+                ("deployment/web".to_string(), "web-a".to_string()),
+                ("deployment/web".to_string(), "web-b".to_string()),
+            ]
+        );
+
+        // A collapsed workload header stands for both of its pods
+        let expanded: HashSet<String> = ["test", "test/default", "test/default/debug"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let sidebar = SidebarPods::new(&pods, None, &[]);
+        let layout = SidebarLayout::new(sidebar, None, &expanded);
+        let rows: Vec<_> = layout.rows(sidebar, 0..layout.row_count()).collect();
+        assert_eq!(rows.len(), 5);
+        assert!(matches!(
+            rows[2],
+            SidebarRow::Pod {
+                pod_name: "debug",
+                ..
+            }
+        ));
+        assert!(matches!(
+            rows[3],
+            SidebarRow::Container { grouped: false, .. }
+        ));
+        assert!(matches!(
+            rows[4],
+            SidebarRow::Workload {
+                workload: "deployment/web",
+                pod_count: 2,
+                expanded: false,
+                ..
+            }
+        ));
+        let SidebarRow::Namespace { containers, .. } = rows[1] else {
+            panic!("expected the namespace row");
+        };
+        assert_eq!(containers.iter().count(), 3);
+    }
+
+    #[test]
+    fn test_auto_scroll_resume() {
+        use crate::utils::{AutoScrollResume, parse_auto_scroll_resume};
+        use std::time::{Duration, Instant};
+
+        assert_eq!(
+            parse_auto_scroll_resume("timeout:10s"),
+            Ok(AutoScrollResume::Timeout(Duration::from_secs(10)))
+        );
+        assert_eq!(
+            parse_auto_scroll_resume("never"),
+            Ok(AutoScrollResume::Never)
+        );
+        assert!(parse_auto_scroll_resume("timeout:0s").is_err());
+        assert!(parse_auto_scroll_resume("10s").is_err());
+
+        let mut app = App::new(100);
+        for i in 0..10 {
+            app.add_log(log_line("web-1", "app", &format!("line {}", i)));
+        }
+
+        // Never: reaching the bottom does not re-engage, End does
+        app.auto_scroll_resume = AutoScrollResume::Never;
+        app.scroll_up();
+        app.scroll_down();
+        app.scroll_down();
+        assert!(!app.auto_scroll);
+        assert_eq!(app.auto_scroll_indicator().as_deref(), Some("HOLD"));
+        app.scroll_to_bottom();
+        assert!(app.auto_scroll);
+
+        // Timeout: resumes once scrolling has been idle long enough
+        app.auto_scroll_resume = AutoScrollResume::Timeout(Duration::from_secs(10));
+        app.scroll_to_top();
+        app.update_stats();
+        assert!(!app.auto_scroll);
+        assert_eq!(app.auto_scroll_indicator().as_deref(), Some("AUTO in 10s"));
+        app.last_scroll_input = Instant::now().checked_sub(Duration::from_secs(11));
+        app.update_stats();
+        assert!(app.auto_scroll);
+        assert_eq!(app.scroll_offset, 9);
+
+        // Turning auto-scroll off with `a` is not undone by the timeout
+        app.toggle_auto_scroll();
+        app.update_stats();
+        assert!(!app.auto_scroll);
+        assert_eq!(app.auto_scroll_indicator(), None);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_pod_is_drained_for_the_grace_period() {
+        use crate::kubernetes::TailOptions;
+        use crate::types::{ConnectionState, StreamStates};
+        use crate::ui::app::PodKey;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let key = PodKey {
+            cluster: "prod".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "db-0".to_string(),
+            container_name: "postgres".to_string(),
+        };
+        let delete = |grace: Duration| {
+            let key = key.clone();
+            async move {
+                let states = Arc::new(StreamStates::default());
+                let ctx = crate::TailContext {
+                    options: TailOptions {
+                        delete_grace: grace,
+                        stream_states: states.clone(),
+                        ..Default::default()
+                    },
+                    ..test_context()
+                };
+                let task = tokio::spawn(std::future::pending::<()>()).abort_handle();
+                let handles = Arc::new(tokio::sync::Mutex::new(HashMap::from([(
+                    key.clone(),
+                    vec![task.clone()],
+                )])));
+                states.set(&key, ConnectionState::Connected);
+                crate::handle_pod_deleted("db-0".to_string(), &handles, &ctx, &None).await;
+                // Taken right away, so a re-created pod is tailed afresh
+                assert!(handles.lock().await.is_empty());
+                tokio::task::yield_now().await;
+                (states, task)
+            }
+        };
+
+        // The stream keeps being read through the grace period, then stops
+        let (states, task) = delete(Duration::from_millis(50)).await;
+        assert!(!task.is_finished());
+        assert!(states.is_draining(&key));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(task.is_finished());
+        assert_eq!(states.get(&key), None);
+
+        // Without one it stops at once
+        let (states, task) = delete(Duration::ZERO).await;
+        assert!(task.is_finished());
+        assert!(!states.is_draining(&key));
+        assert_eq!(states.get(&key), None);
+    }
+
+    #[test]
+    fn test_line_template() {
+        let template = utils::parse_template("{{.pod}}/{{ .container }} | {{.message}}").unwrap();
+        let msg = log_line("web-1", "app", "hello {{.pod}}");
+        assert_eq!(template.render(&msg), "web-1/app | hello {{.pod}}");
+
+        let template = utils::parse_template("{{.timestamp}}").unwrap();
+        assert_eq!(
+            template.render(&msg),
+            msg.timestamp
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+        );
+
+        assert!(utils::parse_template("{{.image}}").is_err());
+        assert!(utils::parse_template("{{.pod").is_err());
+        assert_eq!(
+            utils::parse_template("plain").unwrap().render(&msg),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn test_prefix_format() {
+        let format = utils::parse_prefix_format("{namespace}/{pod}>").unwrap();
+        let msg = log_line("web-1", "app", "hello {pod}");
+        assert_eq!(format.render(&msg), "default/web-1>");
+        let format = utils::parse_prefix_format("[{ cluster } {container}]").unwrap();
+        assert_eq!(format.render(&msg), "[test app]");
+
+        let err = utils::parse_prefix_format("{node}/{pod}").unwrap_err();
+        assert!(err.contains("'{node}'"));
+        assert!(utils::parse_prefix_format("{pod").is_err());
+        assert!(utils::parse_prefix_format("{message}").is_err());
+
+        // Saves use the same prefix as the view
+        let mut app = App::new(10);
+        app.prefix_format = Some(utils::parse_prefix_format("{pod}>").unwrap());
+        app.add_log(msg);
+        let path = std::env::temp_dir().join(format!("kubectl-tail-prefix-{}", std::process::id()));
+        app.save_logs(&path);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, "web-1> hello {pod}\n");
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--prefix-format", "{pod}", "pod/a"]);
+        assert!(cli.unwrap().prefix_format.is_some());
+        assert!(
+            Cli::try_parse_from([
+                "kubectl-tail",
+                "--prefix-format",
+                "{pod}",
+                "--template",
+                "{{.message}}",
+                "pod/a"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_finished_container_stops_retrying() {
+        use crate::kubernetes::finished_container;
+        use k8s_openapi::api::core::v1::{
+            ContainerState, ContainerStateTerminated, ContainerStatus, Pod, PodSpec, PodStatus,
+        };
+
+        let pod = |phase: &str, restart_policy: &str, exit_code: Option<i32>| Pod {
+            spec: Some(PodSpec {
+                restart_policy: Some(restart_policy.to_string()),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some(phase.to_string()),
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "app".to_string(),
+                    state: Some(ContainerState {
+                        terminated: exit_code.map(|code| ContainerStateTerminated {
+                            exit_code: code,
+                            reason: Some(if code == 0 { "Completed" } else { "Error" }.to_string()),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // Running pods whose stream ended are reconnected
+        assert_eq!(
+            finished_container(&pod("Running", "Always", None), "app"),
+            None
+        );
+        // A crashed container that will be restarted is not finished
+        assert_eq!(
+            finished_container(&pod("Running", "Always", Some(1)), "app"),
+            None
+        );
+        assert_eq!(
+            finished_container(&pod("Running", "OnFailure", Some(1)), "app"),
+            None
+        );
+        // Containers that won't run again are
+        assert_eq!(
+            finished_container(&pod("Running", "Never", Some(1)), "app").as_deref(),
+            Some("exit code 1 (Error)")
+        );
+        assert_eq!(
+            finished_container(&pod("Succeeded", "OnFailure", Some(0)), "app").as_deref(),
+            Some("exit code 0 (Completed)")
+        );
+        assert_eq!(
+            finished_container(&pod("Failed", "Always", None), "app").as_deref(),
+            Some("pod Failed")
+        );
+    }
+
+    #[test]
+    fn test_container_picker_bulk_toggle() {
+        use crate::ui::app::{AppMode, PodKey};
+        use crate::ui::events::handle_key_event;
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        assert!(utils::fuzzy_match("evy", "envoy"));
+        assert!(utils::fuzzy_match("", "app"));
+        assert!(!utils::fuzzy_match("vne", "envoy"));
+
+        let mut app = App::new(100);
+        for container in ["app", "envoy", "istio-proxy", "log-shipper"] {
+            app.add_pod(pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: "web-1".to_string(),
+                container_name: container.to_string(),
+            }));
+        }
+        app.add_log(log_line("web-1", "app", "hello"));
+        app.open_container_picker();
+        assert_eq!(app.mode, AppMode::ContainerPicker);
+        assert_eq!(app.picker_entries().len(), 4);
+
+        // Disable everything, then re-enable just app and envoy
+        app.picker_toggle_all();
+        assert!(app.picker_entries().iter().all(|(_, enabled)| !enabled));
+        app.picker_edit_filter(|f| f.push_str("env"));
+        app.picker_toggle();
+        app.picker_edit_filter(|f| f.clear());
+        app.picker_toggle();
+        let enabled: Vec<_> = app
+            .picker_entries()
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(key, _)| key.container_name)
+            .collect();
+        assert_eq!(enabled, vec!["app", "envoy"]);
+
+        app.close_container_picker();
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.container_picker.is_none());
+
+        // Ctrl keys are not typed into the filter; Ctrl-C still quits
+        app.open_container_picker();
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert!(handle_key_event(&mut app, ctrl('a')));
+        assert!(!handle_key_event(&mut app, ctrl('c')));
+        assert_eq!(app.container_picker.as_ref().unwrap().filter, "");
+    }
+
+    #[test]
+    fn test_refuse_insecure_context() {
+        let mut config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+        assert!(crate::check_tls_verification("prod", &config, true).is_ok());
+
+        config.accept_invalid_certs = true;
+        assert!(crate::check_tls_verification("lab", &config, false).is_ok());
+        let err = crate::check_tls_verification("lab", &config, true).unwrap_err();
+        assert!(err.to_string().contains("'lab'"));
+    }
+
+    #[test]
+    fn test_hidden_pod_activity_does_not_move_scrolled_view() {
+        use crate::ui::app::PodKey;
+
+        let mut app = App::new(20);
+        for pod in ["quiet", "chatty"] {
+            app.add_pod(pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: pod.to_string(),
+                container_name: "app".to_string(),
+            }));
+        }
+        for i in 0..5 {
+            app.add_log(log_line("quiet", "app", &format!("quiet {}", i)));
+            app.add_log(log_line("chatty", "app", &format!("chatty {}", i)));
+        }
+
+        // Focus on the quiet pod and scroll up to read it
+        app.pod_states
+            .iter_mut()
+            .filter(|(key, _)| key.pod_name == "chatty")
+            .for_each(|(_, state)| state.enabled = false);
+        app.scroll_up();
+        app.scroll_up();
+        assert_eq!(app.current_line().unwrap().line, "quiet 2");
+
+        // The chatty neighbour fills the buffer, evicting only its own old lines
+        for i in 5..15 {
+            app.add_log(log_line("chatty", "app", &format!("chatty {}", i)));
+        }
+        assert_eq!(app.log_buffer.len(), 20);
+        assert_eq!(app.current_line().unwrap().line, "quiet 2");
+
+        // Evicting a line of the view ("quiet 0") keeps the same line anchored
+        for i in 15..17 {
+            app.add_log(log_line("chatty", "app", &format!("chatty {}", i)));
+        }
+        assert_eq!(app.current_line().unwrap().line, "quiet 2");
+    }
+
+    #[test]
+    fn test_notify_is_rate_limited() {
+        use std::time::{Duration, Instant};
+
+        let mut notifier = utils::Notifier::new(regex::Regex::new("OOM").unwrap());
+        let start = Instant::now();
+        assert!(!notifier.check("all good", start));
+        assert!(notifier.check("OOMKilled", start));
+        // A flood of matches stays quiet until the interval has passed
+        assert!(!notifier.check("OOMKilled", start + Duration::from_secs(1)));
+        assert!(notifier.check("OOMKilled", start + utils::NOTIFY_INTERVAL));
+
+        let mut app = App::new(100);
+        app.notifier = Some(utils::Notifier::new(regex::Regex::new("panic").unwrap()));
+        app.paused = true;
+        app.add_log(log_line("web-1", "app", "thread main panicked"));
+        assert!(app.take_bell());
+        assert!(!app.take_bell());
+        assert!(app.is_flashing());
+    }
+
+    #[test]
+    fn test_pipe_marked_range() {
+        let mut app = App::new(100);
+        for i in 0..10 {
+            app.add_log(log_line("web-1", "app", &format!("line {}", i)));
+        }
+        app.show_prefix = false;
+        assert_eq!(app.selected_lines().len(), 10);
+
+        app.scroll_up(); // line 8
+        app.toggle_mark();
+        app.scroll_up();
+        app.scroll_up(); // line 6
+        assert_eq!(app.selected_lines(), vec!["line 6", "line 7", "line 8"]);
+
+        // Without --pipe-cmd nothing is sent and the mark is kept
+        app.pipe_selection();
+        assert!(app.mark.is_some());
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        app.pipe_cmd = Some("wc -l".to_string());
+        app.pipe_tx = Some(tx);
+        app.pipe_selection();
+        assert!(app.mark.is_none());
+        let lines = rx.try_recv().unwrap();
+        assert_eq!(lines.len(), 3);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let msg = runtime.block_on(crate::pipe_to_command("wc -l", lines));
+        assert_eq!(msg, "`wc -l`: 3 (exit 0)");
+        let msg = runtime.block_on(crate::pipe_to_command("exit 3", vec![]));
+        assert_eq!(msg, "`exit 3`: exit 3 (0 lines piped)");
+    }
+
+    #[test]
+    fn test_statefulset_ordinals() {
+        assert_eq!(utils::split_ordinals("db"), Ok(("db", None)));
+        assert_eq!(
+            utils::split_ordinals("db[0-2]"),
+            Ok(("db", Some(vec![0, 1, 2])))
+        );
+        assert_eq!(
+            utils::split_ordinals("db[3,0-1,1]"),
+            Ok(("db", Some(vec![0, 1, 3])))
+        );
+        assert!(utils::split_ordinals("db[2-0]").is_err());
+        assert!(utils::split_ordinals("db[x]").is_err());
+        assert!(utils::split_ordinals("db[0").is_err());
+
+        let spec = utils::parse_resource_spec("prod/statefulset/db[0-1]").unwrap();
+        assert_eq!(spec.kind.as_deref(), Some("statefulset"));
+        assert_eq!(spec.name, "db[0-1]");
+
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "statefulset/db", "--ordinals", "0,1"]).unwrap();
+        assert_eq!(cli.ordinals, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_kubectl_commands_for_view() {
+        use crate::ui::app::PodKey;
+
+        assert_eq!(
+            utils::shell_quote("arn:aws:eks:us-east-1:1:cluster/prod"),
+            "arn:aws:eks:us-east-1:1:cluster/prod"
+        );
+        assert_eq!(utils::shell_quote("it's"), "'it'\\''s'");
+
+        let mut app = App::new(100);
+        app.tail_lines = Some(100);
+        for container in ["app", "envoy"] {
+            app.add_pod(pod_info(PodKey {
+                cluster: "prod".to_string(),
+                namespace: "web".to_string(),
+                pod_name: "web-1".to_string(),
+                container_name: container.to_string(),
+            }));
+        }
+
+        // Nothing selected: every enabled container
+        assert_eq!(
+            app.kubectl_commands(),
+            vec![
+                "kubectl logs --context prod -n web web-1 -c app -f --tail=100",
+                "kubectl logs --context prod -n web web-1 -c envoy -f --tail=100",
+            ]
+        );
+
+        // The current line's container
+        let mut msg = log_line("web-1", "envoy", "hello");
+        msg.cluster = "prod".to_string();
+        msg.namespace = "web".to_string();
+        app.add_log(msg);
+        app.copy_kubectl_commands();
+        assert_eq!(
+            app.take_clipboard().as_deref(),
+            Some("kubectl logs --context prod -n web web-1 -c envoy -f --tail=100")
+        );
+        assert_eq!(app.take_clipboard(), None);
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        use crate::checkpoint::{self, Checkpoint};
+        use crate::ui::app::PodKey;
+
+        let key = PodKey {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "web-1".to_string(),
+            container_name: "app".to_string(),
+        };
+        let mut app = App::new(100);
+        app.add_pod(pod_info(key.clone()));
+        app.pod_states.get_mut(&key).unwrap().enabled = false;
+        app.add_log(log_line("web-1", "app", "first"));
+        app.add_log(log_line("web-1", "app", "second \"quoted\"\nline"));
+
+        let dir = std::env::temp_dir().join(format!("kubectl-tail-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("checkpoint.jsonl");
+        checkpoint::write(&path, &app).unwrap();
+        assert!(!dir.join("checkpoint.jsonl.tmp").exists());
+
+        let mut resumed = App::new(100);
+        let restored = Checkpoint::load(&path)
+            .unwrap()
+            .unwrap()
+            .restore(&mut resumed);
+        assert_eq!(restored, 2);
+        let lines: Vec<_> = resumed.log_buffer.iter().map(|m| m.line.as_str()).collect();
+        assert_eq!(lines, vec!["first", "second \"quoted\"\nline"]);
+        assert_eq!(resumed.log_buffer[0].timestamp, app.log_buffer[0].timestamp);
+        assert!(!resumed.pod_states[&key].enabled);
+
+        // A snapshot holds the buffer as it was when taken, whenever written
+        let snapshot = checkpoint::Snapshot::of(&app);
+        app.add_log(log_line("web-1", "app", "third"));
+        std::thread::spawn(move || snapshot.write(&path))
+            .join()
+            .unwrap()
+            .unwrap();
+        let mut resumed = App::new(100);
+        let restored = Checkpoint::load(&dir.join("checkpoint.jsonl"))
+            .unwrap()
+            .unwrap()
+            .restore(&mut resumed);
+        assert_eq!(restored, 2);
+
+        // Nothing captured yet is not an error
+        assert!(
+            Checkpoint::load(&dir.join("missing.jsonl"))
+                .unwrap()
+                .is_none()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(Cli::try_parse_from(["kubectl-tail", "-l", "app=x", "--resume"]).is_err());
+        assert!(Cli::try_parse_from(["kubectl-tail", "-l", "app=x"]).is_ok());
+    }
+
+    #[test]
+    fn test_suggest_close_resource_names() {
+        assert_eq!(utils::levenshtein("api-sevrer", "api-server"), 2);
+        assert_eq!(utils::levenshtein("", "web"), 3);
+        assert_eq!(utils::levenshtein("web", "web"), 0);
+
+        let names: Vec<String> = ["api-server", "api-gateway", "worker", "web"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            utils::closest_names("api-sevrer", &names),
+            vec!["api-server"]
+        );
+        assert_eq!(utils::closest_names("workr", &names), vec!["worker"]);
+        assert!(utils::closest_names("billing", &names).is_empty());
+    }
+
+    #[test]
+    fn test_mute_cluster_hides_lines_but_keeps_buffering() {
+        let mut app = App::new(100);
+        let mut prod = log_line("web", "app", "from prod");
+        prod.cluster = "prod".to_string();
+        app.add_log(log_line("web", "app", "from test"));
+        app.add_log(prod.clone());
+
+        // Sidebar hidden: mute the current (newest) line's cluster
+        app.toggle_cluster_mute();
+        assert!(app.muted_clusters.contains("prod"));
+        app.add_log(prod);
+        assert_eq!(app.log_buffer.len(), 3);
+        let visible: Vec<_> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(visible, vec!["from test"]);
+        app.update_stats();
+        assert!(app.active_filters.contains(&"muted: prod".to_string()));
+
+        // Pressing again unmutes everything
+        app.toggle_cluster_mute();
+        assert!(app.muted_clusters.is_empty());
+        assert_eq!(app.filtered_logs().len(), 3);
+    }
+
+    #[test]
+    fn test_sidebar_renders_only_visible_window() {
+        use crate::ui::app::{PodKey, SidebarPods, SidebarRow};
+        use crate::ui::widgets::{PodList, PodListOptions, sidebar_window_offset};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+        assert_eq!(sidebar_window_offset(0, Some(5), 100, 10), 0);
+        assert_eq!(sidebar_window_offset(0, Some(25), 100, 10), 16);
+        assert_eq!(sidebar_window_offset(50, Some(20), 100, 10), 20);
+        // A collapse shrinking the list pulls the window back
+        assert_eq!(sidebar_window_offset(90, Some(3), 12, 10), 2);
+
+        let mut app = App::new(10);
+        for i in 0..500 {
+            app.add_pod(pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: format!("web-{:03}", i),
+                container_name: "app".to_string(),
+            }));
+        }
+        app.refresh_sidebar();
+        // Cluster and namespace headers, then each pod with its container
+        assert_eq!(app.sidebar_layout.row_count(), 1002);
+        let pods = SidebarPods::new(&app.pods, None, &[]);
+        assert!(matches!(
+            app.sidebar_layout.rows(pods, 600..601).next(),
+            Some(SidebarRow::Pod {
+                pod_name: "web-299",
+                ..
+            })
+        ));
+
+        let area = Rect::new(0, 0, 40, 10);
+        let mut buf = Buffer::empty(area);
+        app.sidebar_state.select(Some(600));
+        PodList::new(
+            &app.sidebar_layout,
+            pods,
+            &app.pod_states,
+            &app.pod_warnings,
+            &app.muted_clusters,
+            &app.environments,
+            &app.reconnecting_watches,
+            PodListOptions::default(),
+        )
+        .render(area, &mut buf, &mut app.sidebar_state);
+
+        assert_eq!(app.sidebar_state.offset(), 591);
+        let row_text = |y: u16| -> String {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect()
+        };
+        assert!(row_text(0).contains("[x] app"));
+        assert!(row_text(1).contains("web-295"));
+        assert!(row_text(9).contains("→") && row_text(9).contains("web-299"));
+
+        // Laid out again only once something changed, e.g. a collapse
+        app.toggle_sidebar_item();
+        assert_eq!(app.sidebar_layout.row_count(), 1002);
+        app.refresh_sidebar();
+        assert_eq!(app.sidebar_layout.row_count(), 1001);
+        assert_eq!(app.sidebar_item_keys.len(), 1001);
+        assert_eq!(app.sidebar_item_keys[601], None);
+        assert_eq!(
+            app.sidebar_item_keys[602].as_ref().unwrap().pod_name,
+            "web-300"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sidebar_pod_age_is_live() {
+        use crate::ui::app::{PodInfo, PodKey, SidebarPods, SidebarRow};
+        use crate::ui::widgets::{PodList, PodListOptions};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+        let mut app = App::new(10);
+        app.add_pod(PodInfo {
+            created: Some(chrono::Utc::now() - chrono::Duration::milliseconds(59_300)),
+            ..pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: "web-0".to_string(),
+                container_name: "app".to_string(),
+            })
+        });
+        let render = |app: &mut App| {
+            app.refresh_sidebar();
+            let pods = SidebarPods::new(&app.pods, None, &[]);
+            let pod_row = app
+                .sidebar_layout
+                .rows(pods, 0..app.sidebar_layout.row_count())
+                .position(|row| matches!(row, SidebarRow::Pod { .. }))
+                .unwrap() as u16;
+            let area = Rect::new(0, 0, 40, 5);
+            let mut buf = Buffer::empty(area);
+            PodList::new(
+                &app.sidebar_layout,
+                pods,
+                &app.pod_states,
+                &app.pod_warnings,
+                &app.muted_clusters,
+                &app.environments,
+                &app.reconnecting_watches,
+                PodListOptions::default(),
+            )
+            .render(area, &mut buf, &mut app.sidebar_state);
+            (0..area.width)
+                .map(|x| buf[(x, pod_row)].symbol().to_string())
+                .collect::<String>()
+        };
+
+        assert!(render(&mut app).contains("web-0 (Running, 59s)"));
+        // Later renders show the pod older, with no update about it
+        tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+        assert!(render(&mut app).contains("web-0 (Running, 1m)"));
+    }
+
+    #[test]
+    fn test_trace_correlation() {
+        let by_name = utils::parse_trace_field("trace_id").unwrap();
+        for line in [
+            "GET /orders trace_id=4bf92f3577b3 status=200",
+            "level=info trace_id: 4bf92f3577b3",
+            r#"{"msg":"ok","trace_id":"4bf92f3577b3"}"#,
+        ] {
+            assert_eq!(
+                utils::extract_trace_id(&by_name, line).as_deref(),
+                Some("4bf92f3577b3"),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(
+            utils::extract_trace_id(&by_name, "parent_trace_id=x1"),
+            None
+        );
+        assert_eq!(utils::extract_trace_id(&by_name, "no trace here"), None);
+        let by_regex = utils::parse_trace_field(r"traceparent: 00-(\w+)-").unwrap();
+        assert_eq!(
+            utils::extract_trace_id(&by_regex, "traceparent: 00-abc123-def-01").as_deref(),
+            Some("abc123")
+        );
+        assert!(utils::parse_trace_field(r"trace=\w+").is_err());
+
+        let mut app = App::new(100);
+        app.trace_field = Some(by_name);
+        app.add_log(log_line("api", "app", "start trace_id=t1"));
+        app.add_log(log_line("db", "pg", "query trace_id=t2"));
+        app.add_log(log_line("db", "pg", "query trace_id=t1"));
+        app.add_log(log_line("api", "app", "done trace_id=t1"));
+        app.add_log(log_line("api", "app", "untraced"));
+
+        app.scroll_up();
+        app.scroll_up();
+        assert_eq!(app.current_line().unwrap().line, "query trace_id=t1");
+        app.cycle_trace();
+        assert_eq!(app.trace.as_deref(), Some("t1"));
+        assert_eq!(app.filtered_logs().len(), 5);
+
+        app.cycle_trace();
+        let lines: Vec<_> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(
+            lines,
+            vec!["start trace_id=t1", "query trace_id=t1", "done trace_id=t1"]
+        );
+        // The line the trace was picked from stays under the cursor
+        assert_eq!(app.current_line().unwrap().line, "query trace_id=t1");
+
+        app.cycle_trace();
+        assert_eq!(app.trace, None);
+        assert_eq!(app.filtered_logs().len(), 5);
+    }
+
+    #[test]
+    fn test_flush_interval_parsing() {
+        let cli = Cli::try_parse_from(["kubectl-tail", "-l", "app=x"]).unwrap();
+        assert_eq!(cli.flush_interval, std::time::Duration::from_millis(50));
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "-l", "app=x", "--flush-interval", "0"]).unwrap();
+        assert!(cli.flush_interval.is_zero());
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "-l", "app=x", "--flush-interval", "1s"]).unwrap();
+        assert_eq!(cli.flush_interval, std::time::Duration::from_secs(1));
+        assert!(
+            Cli::try_parse_from(["kubectl-tail", "-l", "app=x", "--flush-interval", "soon"])
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flush_interval_batches_stdout() {
+        use std::io::Write;
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        /// What reached the underlying writer, i.e. was flushed
+        #[derive(Clone, Default)]
+        struct Flushed(Arc<Mutex<Vec<u8>>>);
+        impl Write for Flushed {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let write_line =
+            |out: &mut dyn Write, msg: crate::types::LogMessage| writeln!(out, "{}", msg.line);
+        let start = |interval: Duration| {
+            let flushed = Flushed::default();
+            let (log_tx, log_rx) = tokio::sync::mpsc::channel(16);
+            let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+            let out = std::io::BufWriter::new(flushed.clone());
+            let printer = tokio::spawn(crate::print_lines(
+                out,
+                log_rx,
+                write_line,
+                interval,
+                shutdown_rx,
+            ));
+            (flushed, log_tx, shutdown_tx, printer)
+        };
+        let settle = || tokio::time::sleep(Duration::from_millis(20));
+
+        // Zero flushes every line as it is written
+        let (flushed, log_tx, _shutdown_tx, _printer) = start(Duration::ZERO);
+        log_tx.send(log_line("web", "app", "one")).await.unwrap();
+        settle().await;
+        assert_eq!(*flushed.0.lock().unwrap(), b"one\n");
+
+        // Otherwise lines wait for the next tick, or for shutdown
+        let (flushed, log_tx, shutdown_tx, printer) = start(Duration::from_secs(3600));
+        settle().await;
+        log_tx.send(log_line("web", "app", "one")).await.unwrap();
+        settle().await;
+        assert!(flushed.0.lock().unwrap().is_empty());
+        log_tx.send(log_line("web", "app", "two")).await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        printer.await.unwrap().unwrap();
+        assert_eq!(*flushed.0.lock().unwrap(), b"one\ntwo\n");
+    }
+
+    #[test]
+    fn test_qos_class_filter() {
+        use k8s_openapi::api::core::v1::{Pod, PodStatus};
+
+        assert_eq!(utils::parse_qos_class("besteffort").unwrap(), "BestEffort");
+        assert_eq!(utils::parse_qos_class("Guaranteed").unwrap(), "Guaranteed");
+        assert!(utils::parse_qos_class("premium").is_err());
+
+        let pod = |qos: Option<&str>| Pod {
+            status: Some(PodStatus {
+                qos_class: qos.map(str::to_string),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let best_effort = vec!["BestEffort".to_string()];
+        assert!(crate::matches_qos(&pod(Some("BestEffort")), &best_effort));
+        assert!(!crate::matches_qos(&pod(Some("Burstable")), &best_effort));
+        assert!(!crate::matches_qos(&pod(None), &best_effort));
+        assert!(crate::matches_qos(&pod(None), &[]));
+
+        let cli = Cli::try_parse_from([
+            "kubectl-tail",
+            "-l",
+            "app=x",
+            "--qos",
+            "burstable,BESTEFFORT",
+        ])
+        .unwrap();
+        assert_eq!(cli.qos, vec!["Burstable", "BestEffort"]);
+    }
+
+    #[test]
+    fn test_help_overlay_scrolls_on_small_terminal() {
+        use crate::ui::app::OverlayScroll;
+        use crate::ui::widgets::HelpOverlay;
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+        let area = Rect::new(0, 0, 80, 24);
+        let text = |buf: &Buffer| -> String {
+            (0..area.height)
+                .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+                .map(|(x, y)| buf[(x, y)].symbol().to_string())
+                .collect()
+        };
+
+        let mut scroll = OverlayScroll::default();
+        let mut buf = Buffer::empty(area);
+        HelpOverlay.render(area, &mut buf, &mut scroll);
+        assert_eq!(scroll.page, 22);
+        assert!(scroll.max > 0);
+        assert!(!text(&buf).contains("any other key closes"));
+
+        // Scrolling past the end is clamped at render time
+        scroll.down(1000);
+        let mut buf = Buffer::empty(area);
+        HelpOverlay.render(area, &mut buf, &mut scroll);
+        assert_eq!(scroll.offset, scroll.max);
+        assert!(text(&buf).contains("any other key closes"));
+
+        scroll.up(scroll.page);
+        assert_eq!(scroll.offset, scroll.max.saturating_sub(22));
+    }
+
+    #[test]
+    fn test_resource_spec_container_suffix() {
+        let spec = utils::parse_resource_spec("web-1:app").unwrap();
+        assert_eq!(spec.name, "web-1");
+        assert_eq!(spec.kind, None);
+        assert_eq!(spec.container.as_deref(), Some("app"));
+
+        let spec = utils::parse_resource_spec("deployment/api:sidecar").unwrap();
+        assert_eq!(spec.kind.as_deref(), Some("deployment"));
+        assert_eq!(spec.name, "api");
+        assert_eq!(spec.container.as_deref(), Some("sidecar"));
+
+        let spec = utils::parse_resource_spec("prod/deployment/api:envoy").unwrap();
+        assert_eq!(spec.namespace.as_deref(), Some("prod"));
+        assert_eq!(spec.name, "api");
+        assert_eq!(spec.container.as_deref(), Some("envoy"));
+
+        // Only the name is split: context names may contain colons
+        let spec = utils::parse_resource_spec(
+            "arn:aws:eks:us-east-1:1234:cluster/prod/statefulset/db[0-1]:pg",
+        )
+        .unwrap();
+        assert_eq!(
+            spec.context.as_deref(),
+            Some("arn:aws:eks:us-east-1:1234:cluster")
+        );
+        assert_eq!(spec.name, "db[0-1]");
+        assert_eq!(spec.container.as_deref(), Some("pg"));
+
+        let spec = utils::parse_resource_spec("deployment/api").unwrap();
+        assert_eq!(spec.container, None);
+        assert!(utils::parse_resource_spec("deployment/api:").is_err());
+    }
+
+    #[test]
+    fn test_debug_timeline_records_stream_lifecycle() {
+        use crate::types::{ConnectionState, StreamStates};
+        use crate::ui::app::PodKey;
+
+        let states = StreamStates::default();
+        let key = |pod: &str| PodKey {
+            cluster: "prod".to_string(),
+            namespace: "default".to_string(),
+            pod_name: pod.to_string(),
+            container_name: "app".to_string(),
+        };
+        states
+            .timeline
+            .record("prod/default".to_string(), None, "Init: watch (re)started");
+        states
+            .timeline
+            .record("prod/default/web-1".to_string(), None, "Apply (Pending)");
+        states.set(&key("web-1"), ConnectionState::Connecting);
+        states.set(&key("web-1"), ConnectionState::Connected);
+        states.set(&key("web-10"), ConnectionState::Connecting);
+        states.remove(&key("web-1"));
+        // Removing a container that has no task is not a transition
+        states.remove(&key("web-2"));
+
+        let events = |pod: Option<&str>| -> Vec<String> {
+            states
+                .timeline
+                .snapshot(pod)
+                .into_iter()
+                .map(|e| e.event)
+                .collect()
+        };
+        assert_eq!(
+            events(Some("prod/default/web-1")),
+            vec![
+                "Init: watch (re)started",
+                "Apply (Pending)",
+                "tail task spawned, connecting",
+                "stream connected",
+                "tail task stopped",
+            ]
+        );
+        assert_eq!(events(None).len(), 6);
+        let web1 = states.timeline.snapshot(Some("prod/default/web-1"));
+        assert_eq!(web1[2].container.as_deref(), Some("app"));
+    }
+
+    #[test]
+    fn test_line_sequence_per_container() {
+        use crate::types::LineSequence;
+
+        let mut sequence = LineSequence::default();
+        let web_app = log_line("web-1", "app", "a");
+        let web_sidecar = log_line("web-1", "sidecar", "b");
+        assert_eq!(sequence.next(&web_app), 1);
+        assert_eq!(sequence.next(&web_app), 2);
+        assert_eq!(sequence.next(&web_sidecar), 1);
+        assert_eq!(sequence.next(&web_app), 3);
+
+        // The same pod name in another cluster is another container
+        let other_cluster = LogMessage {
+            cluster: "prod".to_string(),
+            ..web_app.clone()
+        };
+        assert_eq!(sequence.next(&other_cluster), 1);
+    }
+
+    #[test]
+    fn test_render_cadence_follows_changes() {
+        let mut app = App::new(100);
+        assert!(!app.take_changed());
+        assert_eq!(crate::render_interval(false), crate::IDLE_RENDER_INTERVAL);
+
+        app.add_log(log_line("pod-a", "app", "hello"));
+        app.mark_changed();
+        assert!(app.take_changed());
+        assert_eq!(crate::render_interval(true), crate::ACTIVE_RENDER_INTERVAL);
+        // The signal is consumed by the frame that shows the change
+        assert!(!app.take_changed());
+        assert!(crate::ACTIVE_RENDER_INTERVAL < crate::IDLE_RENDER_INTERVAL);
+    }
+
+    #[test]
+    fn test_elide_frames_folds_runs_per_container() {
+        let mut app = App::new(100);
+        app.elide_frames = Some(utils::parse_frame_pattern(r"at org\.framework\.").unwrap());
+        app.add_log(log_line("api", "app", "Exception in handler"));
+        app.add_log(log_line("api", "app", "at com.acme.Orders.get"));
+        app.add_log(log_line("api", "app", "at org.framework.A.invoke"));
+        // A single frame is shown as is
+        assert_eq!(
+            app.log_buffer.back().unwrap().line,
+            "at org.framework.A.invoke"
+        );
+        app.add_log(log_line("api", "app", "at org.framework.B.invoke"));
+        app.add_log(log_line("api", "app", "at org.framework.C.invoke"));
+        app.add_log(log_line("api", "app", "at com.acme.Main.run"));
+        // Another container's frame never joins the run
+        app.add_log(log_line("db", "pg", "at org.framework.D.invoke"));
+
+        let lines: Vec<&str> = app.log_buffer.iter().map(|m| m.line.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Exception in handler",
+                "at com.acme.Orders.get",
+                "... 3 frames elided ...",
+                "at com.acme.Main.run",
+                "at org.framework.D.invoke",
+            ]
+        );
+        // The full run is kept for the line detail view
+        assert_eq!(
+            app.log_buffer[2].elided,
+            vec![
+                "at org.framework.A.invoke",
+                "at org.framework.B.invoke",
+                "at org.framework.C.invoke",
+            ]
+        );
+        assert!(utils::parse_frame_pattern("(").is_err());
+    }
+
+    #[test]
+    fn test_cli_parsing_selector_refresh_rejects_zero() {
+        let err = Cli::try_parse_from([
+            "kubectl-tail",
+            "svc/api",
+            "--watch-selector-change",
+            "--selector-refresh",
+            "0s",
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_watch_selector_change_drops_pods_leaving_selector() {
+        let cli = Cli::try_parse_from([
+            "kubectl-tail",
+            "svc/api",
+            "--watch-selector-change",
+            "--selector-refresh",
+            "5s",
+        ])
+        .unwrap();
+        assert!(cli.watch_selector_change);
+        assert_eq!(cli.selector_refresh, std::time::Duration::from_secs(5));
+        // The refresh interval means nothing without following selectors
+        assert!(
+            Cli::try_parse_from(["kubectl-tail", "svc/api", "--selector-refresh", "5s"]).is_err()
+        );
+
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        let old = names(&["api-v1-b", "api-v1-a", "api-v2-a"]);
+        let new = names(&["api-v2-a", "api-v2-b"]);
+        assert_eq!(
+            crate::pods_leaving_selector(&old, &new),
+            vec!["api-v1-a", "api-v1-b"]
+        );
+        assert!(crate::pods_leaving_selector(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_activity_sparkline_decays() {
+        use crate::ui::app::{Activity, SPARKLINE_SECONDS};
+
+        let mut activity = Activity::default();
+        for _ in 0..8 {
+            activity.record(100);
+        }
+        activity.record(101);
+        activity.advance(103);
+        let line: Vec<char> = activity.sparkline().chars().collect();
+        assert_eq!(line.len(), SPARKLINE_SECONDS);
+        // The busiest second is full height, quiet seconds are blank
+        assert_eq!(line[SPARKLINE_SECONDS - 4..], ['█', '▁', ' ', ' ']);
+
+        // A long silence empties the window
+        activity.advance(1000);
+        assert!(activity.sparkline().trim().is_empty());
+        // Time going backwards never corrupts the window
+        activity.record(999);
+        assert_eq!(activity.sparkline().chars().last(), Some('█'));
+    }
+
+    #[test]
+    fn test_watcher_unauthorized_errors_refresh_credentials() {
+        use kube::core::ErrorResponse;
+        use kube::runtime::watcher::Error as WatchError;
+
+        let response = |code: u16| ErrorResponse {
+            status: "Failure".to_string(),
+            message: "denied".to_string(),
+            reason: "Unauthorized".to_string(),
+            code,
+        };
+        let expired: anyhow::Error =
+            WatchError::InitialListFailed(kube::Error::Api(response(401))).into();
+        assert!(crate::watch_unauthorized(&expired));
+        assert!(crate::watch_unauthorized(
+            &WatchError::WatchError(response(401)).into()
+        ));
+        // A polling list rejected outright
+        assert!(crate::watch_unauthorized(
+            &kube::Error::Api(response(401)).into()
+        ));
+        // Forbidden is an RBAC problem that new credentials won't fix
+        assert!(!crate::watch_unauthorized(
+            &WatchError::WatchFailed(kube::Error::Api(response(403))).into()
+        ));
+        assert!(!crate::watch_unauthorized(&anyhow::anyhow!("boom")));
+    }
+
+    #[test]
+    fn test_level_filter() {
+        use crate::types::LogLevel;
+        use crate::utils::{detect_level, parse_level};
+
+        assert_eq!(
+            detect_level(r#"{"level":"warning","msg":"slow"}"#),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            detect_level("ts=1 level=ERROR msg=boom"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            detect_level("E0102 15:04:05.000000       1 reflector.go:1] failed"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            detect_level("2024-01-02 [INFO] started"),
+            Some(LogLevel::Info)
+        );
+        assert_eq!(
+            detect_level("panic: runtime error: index out of range"),
+            Some(LogLevel::Fatal)
+        );
+        assert_eq!(
+            detect_level(r#"10.0.0.1 - - [02/Jan/2024] "GET /orders HTTP/1.1" 502 0"#),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            detect_level(r#"{"msg":"done","status":503}"#),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(detect_level("GET /orders status=200"), None);
+        // Lower-case words in the message are not levels
+        assert_eq!(detect_level("retrying after error"), None);
+        assert_eq!(detect_level("don't panic"), None);
+        assert_eq!(parse_level("WARNING"), Ok(LogLevel::Warn));
+        assert!(parse_level("loud").is_err());
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "all", "--level", "warn"]).unwrap();
+        let mut app = App::new(100);
+        app.level_threshold = cli.level.unwrap();
+        app.add_log(log_line("api", "app", "INFO ready"));
+        app.add_log(log_line("api", "app", "WARN slow request"));
+        app.add_log(log_line("api", "app", "at com.acme.Main"));
+        app.add_log(log_line("api", "app", "level=fatal msg=crashed"));
+
+        app.toggle_level_filter();
+        let visible: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        // The stack frame has no level and is kept
+        assert_eq!(
+            visible,
+            vec![
+                "WARN slow request",
+                "at com.acme.Main",
+                "level=fatal msg=crashed"
+            ]
+        );
+        // The level filter narrows the f filter further
+        app.filter_pattern = "slow".to_string();
+        assert_eq!(app.filtered_logs().len(), 1);
+
+        app.filter_pattern.clear();
+        app.toggle_level_filter();
+        assert_eq!(app.filtered_logs().len(), 4);
+    }
+
+    #[test]
+    fn test_level_filter_cycle_and_unleveled_lines() {
+        use crate::types::LogLevel;
+        use crate::ui::events::handle_key_event;
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let mut app = App::new(100);
+        for line in [
+            "DEBUG cache miss",
+            "INFO ready",
+            "WARN slow request",
+            "ERROR boom",
+            "    at com.acme.Main",
+        ] {
+            app.add_log(log_line("api", "app", line));
+        }
+        let visible = |app: &App| -> Vec<String> {
+            app.filtered_logs().iter().map(|m| m.line.clone()).collect()
+        };
+        let press = |app: &mut App, c| {
+            handle_key_event(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        };
+
+        press(&mut app, 'L');
+        assert_eq!(app.min_level, Some(LogLevel::Info));
+        assert_eq!(visible(&app).len(), 4);
+        press(&mut app, 'L');
+        press(&mut app, 'L');
+        assert_eq!(app.min_level, Some(LogLevel::Error));
+        // The error's stack frame has no level and stays with it
+        assert_eq!(visible(&app), vec!["ERROR boom", "    at com.acme.Main"]);
+        app.update_stats();
+        assert!(app.active_filters.contains(&"level: error+".to_string()));
+
+        // U hides lines without a level, for the same filter e and L set
+        press(&mut app, 'U');
+        assert_eq!(visible(&app), vec!["ERROR boom"]);
+        app.update_stats();
+        assert!(
+            app.active_filters
+                .contains(&"level: error+ (leveled lines only)".to_string())
+        );
+        press(&mut app, 'e');
+        assert_eq!(visible(&app).len(), 5);
+        press(&mut app, 'e');
+        assert_eq!(visible(&app), vec!["ERROR boom"]);
+        press(&mut app, 'U');
+
+        press(&mut app, 'L');
+        assert_eq!(app.min_level, None);
+        assert_eq!(visible(&app).len(), 5);
+    }
+
+    #[test]
+    fn test_error_hotspots_by_node() {
+        use crate::ui::app::{PodInfo, PodKey};
+
+        let mut app = App::new(100);
+        let pod = |name: &str, node: &str, zone: Option<&str>| PodInfo {
+            node: Some(node.to_string()),
+            zone: zone.map(str::to_string),
+            ..pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: name.to_string(),
+                container_name: "app".to_string(),
+            })
+        };
+        app.add_pod(pod("web-1", "node-7", Some("us-east-1a")));
+        app.add_pod(pod("web-2", "node-7", Some("us-east-1a")));
+        app.add_pod(pod("web-3", "node-2", Some("us-east-1b")));
+
+        for _ in 0..3 {
+            app.add_log(log_line("web-1", "app", "ERROR upstream timeout"));
+        }
+        app.add_log(log_line("web-2", "app", "level=error msg=timeout"));
+        app.add_log(log_line("web-3", "app", "ERROR upstream timeout"));
+        app.add_log(log_line("web-3", "app", "INFO ok"));
+        // A pod never listed still counts, under an unknown node
+        app.add_log(log_line("job-1", "app", "FATAL crashed"));
+
+        let hotspots = app.error_hotspots();
+        let summary: Vec<(Option<&str>, Option<&str>, usize, usize)> = hotspots
+            .iter()
+            .map(|h| (h.node.as_deref(), h.zone.as_deref(), h.errors, h.pods))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("node-7"), Some("us-east-1a"), 4, 2),
+                (None, None, 1, 1),
+                (Some("node-2"), Some("us-east-1b"), 1, 1),
+            ]
+        );
+
+        // A zone resolved later reaches every pod on the node, and one update
+        // sent before it doesn't take it away again
+        app.add_pod(pod("web-4", "node-9", None));
+        app.set_node_zone("test", "node-9", "us-east-1c".to_string());
+        app.add_pod(pod("web-4", "node-9", None));
+        let web4 = app.pods.iter().find(|p| p.key.pod_name == "web-4").unwrap();
+        assert_eq!(web4.zone.as_deref(), Some("us-east-1c"));
+
+        // Deleted pods are forgotten, unless tombstoned
+        app.remove_pod(&pod("web-3", "node-2", None).key);
+        let hotspots = app.error_hotspots();
+        assert!(hotspots.iter().all(|h| h.node.as_deref() != Some("node-2")));
+        assert_eq!(hotspots[1].node, None);
+        assert_eq!(hotspots[1].errors, 2);
+
+        app.tombstone_retention = Some(chrono::Duration::minutes(5));
+        app.remove_pod(&pod("web-2", "node-7", None).key);
+        assert_eq!(app.error_hotspots()[0].node.as_deref(), Some("node-7"));
+        assert_eq!(app.error_hotspots()[0].errors, 4);
+    }
+
+    #[test]
+    fn test_command_mode() {
+        use crate::types::LogLevel;
+        use crate::ui::app::{AppMode, Command, parse_command};
+
+        assert_eq!(
+            parse_command("filter  timeout|refused "),
+            Ok(Command::Filter("timeout|refused".to_string()))
+        );
+        assert_eq!(parse_command("ctx"), Ok(Command::Context(None)));
+        assert_eq!(parse_command("tail 50"), Ok(Command::Tail(50)));
+        assert_eq!(
+            parse_command("level warn"),
+            Ok(Command::Level(Some(LogLevel::Warn)))
+        );
+        assert!(parse_command("tail many").is_err());
+        assert!(parse_command("save").is_err());
+        assert!(parse_command("frobnicate").is_err());
+
+        let mut app = App::new(100);
+        for i in 0..10 {
+            app.add_log(log_line("api", "app", &format!("line {}", i)));
+        }
+        app.mode = AppMode::Command;
+        app.command_input = "tail 3".to_string();
+        assert!(app.run_command());
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.current_line().unwrap().line, "line 7");
+
+        app.command_input = "filter line [0-4]".to_string();
+        assert!(app.run_command());
+        let path = std::env::temp_dir().join(format!("kubectl-tail-save-{}", std::process::id()));
+        app.show_prefix = false;
+        app.command_input = format!("save {}", path.display());
+        assert!(app.run_command());
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, "line 0\nline 1\nline 2\nline 3\nline 4\n");
+        assert!(
+            app.status_message
+                .as_ref()
+                .is_some_and(|(msg, _)| msg.starts_with("Saved 5 lines"))
+        );
+
+        // Timestamps are written when shown
+        app.show_timestamps = true;
+        app.save_logs(&path);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let first = saved.lines().next().unwrap();
+        let (timestamp, line) = first.split_once(' ').unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok());
+        assert_eq!(line, "line 0");
+        app.show_timestamps = false;
+        // An empty view writes an empty file and says so
+        app.filter_pattern = "nothing matches".to_string();
+        app.save_logs(&path);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, "");
+        assert!(
+            app.status_message
+                .as_ref()
+                .is_some_and(|(msg, _)| msg.starts_with("Saved 0 lines"))
+        );
+        app.filter_pattern = "line [0-4]".to_string();
+        // A write error is reported instead of ending the session
+        app.save_logs(std::path::Path::new("/nonexistent/dir/out.log"));
+        assert!(
+            app.status_message
+                .as_ref()
+                .is_some_and(|(msg, _)| msg.starts_with("Could not write"))
+        );
+
+        app.command_input = "context nowhere".to_string();
+        assert!(app.run_command());
+        assert_eq!(app.focused_cluster, None);
+
+        app.command_input = "q".to_string();
+        assert!(!app.run_command());
+    }
+
+    #[test]
+    fn test_channel_saturation_hint() {
+        use crate::types::{ChannelStats, SATURATION_HINT};
+        use std::sync::Arc;
+
+        let stats = Arc::new(ChannelStats::default());
+        let (tx, _rx) = tokio::sync::mpsc::channel::<()>(2);
+        assert!(!stats.observe(&tx));
+        tx.try_send(()).unwrap();
+        tx.try_send(()).unwrap();
+        assert_eq!(stats.high_water(), 0);
+
+        // Hinted once, after the channel was found full often enough
+        let hints = (0..150).filter(|_| stats.observe(&tx)).count();
+        assert_eq!(hints, 1);
+        assert_eq!(stats.high_water(), 2);
+        assert!(stats.is_saturating());
+
+        // The TUI shows it once, not on every tick
+        let mut app = App::new(100);
+        app.channel_stats = Some(stats);
+        app.update_stats();
+        assert_eq!(
+            app.status_message.as_ref().map(|(msg, _)| msg.as_str()),
+            Some(SATURATION_HINT)
+        );
+        app.status_message = None;
+        app.update_stats();
+        assert!(app.status_message.is_none());
+    }
+
+    #[test]
+    fn test_f4_saves_lines_in_view() {
+        use crate::ui::events::handle_key_event;
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let mut app = App::new(100);
+        app.add_log(log_line("web-1", "app", "GET /health 200"));
+        app.add_log(log_line("web-1", "app", "POST /orders 500"));
+        app.add_log(log_line("web-2", "app", "POST /orders 201"));
+        app.filter_pattern = "orders".to_string();
+        app.show_prefix = true;
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::F(4), KeyModifiers::NONE));
+        let (status, _) = app.status_message.clone().unwrap();
+        let path = status.strip_prefix("Saved 2 lines to ").unwrap();
+        let saved = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        // Named after the time it was saved, with the filtered lines as shown
+        let epoch = path
+            .strip_prefix("kubectl-tail-")
+            .and_then(|rest| rest.strip_suffix(".log"))
+            .unwrap();
+        assert!((chrono::Utc::now().timestamp() - epoch.parse::<i64>().unwrap()).abs() < 5);
+        assert_eq!(
+            saved,
+            "[test.default/web-1/app] POST /orders 500\n[test.default/web-2/app] POST /orders 201\n"
+        );
+        assert_eq!(app.mode, crate::ui::app::AppMode::Normal);
+    }
+
+    #[test]
+    fn test_cycle_cluster_focus() {
+        use crate::ui::app::PodKey;
+
+        let mut app = App::new(100);
+        for cluster in ["us", "eu"] {
+            app.add_pod(pod_info(PodKey {
+                cluster: cluster.to_string(),
+                namespace: "default".to_string(),
+                pod_name: "web-0".to_string(),
+                container_name: "app".to_string(),
+            }));
+            app.add_log(LogMessage {
+                cluster: cluster.to_string(),
+                ..log_line("web-0", "app", &format!("hello from {}", cluster))
+            });
+        }
+        let shown = |app: &App| -> Vec<String> {
+            app.filtered_logs()
+                .iter()
+                .map(|entry| entry.cluster.clone())
+                .collect()
+        };
+        assert_eq!(shown(&app), vec!["us", "eu"]);
+
+        // Forward through the clusters in name order, then back to all of them
+        app.cycle_cluster_focus(true);
+        assert_eq!(app.focused_cluster.as_deref(), Some("eu"));
+        assert_eq!(shown(&app), vec!["eu"]);
+        app.cycle_cluster_focus(true);
+        assert_eq!(shown(&app), vec!["us"]);
+        app.cycle_cluster_focus(true);
+        assert_eq!(app.focused_cluster, None);
+        assert_eq!(shown(&app), vec!["us", "eu"]);
+
+        // Backward starts from the last
+        app.cycle_cluster_focus(false);
+        assert_eq!(app.focused_cluster.as_deref(), Some("us"));
+    }
+
+    #[test]
+    fn test_profile_merges_with_command_line() {
+        use crate::config::Config;
+
+        let config = Config::parse(
+            "profiles:\n  payments-incident:\n    contexts: [prod-us]\n    namespaces: [payments]\n    resources: [deployment/api]\n    grep: timeout\n    args: [--tail, '50']\n",
+        )
+        .unwrap();
+        assert!(config.profile("missing").is_err());
+
+        let mut argv = vec!["kubectl-tail".to_string()];
+        argv.extend(config.profile("payments-incident").unwrap().to_args());
+        argv.extend(["--grep=refused", "-n", "ledger", "deployment/worker"].map(String::from));
+        let cli = Cli::try_parse_from(argv).unwrap();
+        assert_eq!(cli.context, vec!["prod-us"]);
+        assert_eq!(cli.namespace, vec!["payments", "ledger"]);
+        assert_eq!(cli.resources, vec!["deployment/api", "deployment/worker"]);
+        assert_eq!(cli.grep.as_deref(), Some("refused"));
+        assert_eq!(cli.tail, Some(50));
+    }
+
+    #[test]
+    fn test_environment_filter() {
+        use crate::config::Config;
+        use crate::ui::app::App;
+
+        let config =
+            Config::parse("environments:\n  prod: [prod-us, prod-eu]\n  staging: [stg]\n").unwrap();
+        let environments = config.context_environments().unwrap();
+        assert_eq!(environments["prod-eu"], "prod");
+        assert!(
+            Config::parse("environments: {prod: [a], dev: [a]}")
+                .unwrap()
+                .context_environments()
+                .is_err()
+        );
+
+        let mut app = App::new(100);
+        app.environments = environments;
+        for cluster in ["prod-us", "stg", "kind"] {
+            let mut msg = log_line("web", "app", "hello");
+            msg.cluster = cluster.to_string();
+            app.add_log(msg);
+        }
+        app.command_input = "env prod".to_string();
+        assert!(app.run_command());
+        let clusters: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|msg| msg.cluster.as_str())
+            .collect();
+        assert_eq!(clusters, vec!["prod-us"]);
+
+        app.command_input = "env qa".to_string();
+        app.run_command();
+        assert_eq!(app.env_filter.as_deref(), Some("prod"));
+        app.command_input = "env".to_string();
+        app.run_command();
+        assert_eq!(app.filtered_logs().len(), 3);
+    }
+
+    #[test]
+    fn test_doctor_report() {
+        use crate::cli::Subcommand;
+        use crate::doctor::{Check, ContextReport, format_report};
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--context", "prod", "doctor"]).unwrap();
+        assert_eq!(cli.command, Some(Subcommand::Doctor));
+        assert_eq!(cli.context, vec!["prod"]);
+        // Anything after the first resource is another resource
+        let cli = Cli::try_parse_from(["kubectl-tail", "web", "doctor"]).unwrap();
+        assert_eq!(cli.command, None);
+
+        let report = |context: &str, read_logs: Check| ContextReport {
+            context: context.to_string(),
+            namespace: "payments".to_string(),
+            client: Check::Ok,
+            list_pods: Check::Ok,
+            watch_pods: Check::Ok,
+            read_logs,
+            latency: Some(std::time::Duration::from_millis(42)),
+        };
+        let reports = [
+            report("prod-us", Check::Ok),
+            report(
+                "prod-eu",
+                Check::Failed(
+                    "RBAC denies get pods/log".to_string(),
+                    crate::exit::ExitCode::Forbidden,
+                ),
+            ),
+        ];
+        assert_eq!(reports[0].failure_code(), None);
+        assert_eq!(
+            reports[1].failure_code(),
+            Some(crate::exit::ExitCode::Forbidden)
+        );
+        let lines = format_report(&reports);
+        assert!(lines[0].starts_with("CONTEXT  NAMESPACE  CLIENT"));
+        assert_eq!(
+            lines[2],
+            "prod-eu  payments   ok      ok         ok          FAIL       42ms"
+        );
+        assert_eq!(
+            lines.last().unwrap(),
+            "  prod-eu read logs: RBAC denies get pods/log"
+        );
+    }
+
+    #[test]
+    fn test_frozen_sidebar_order() {
+        use crate::ui::app::{App, PodInfo, PodKey, pod_tree};
+
+        let pod = |name: &str| PodInfo {
+            workload: Some("deployment/web".to_string()),
+            ..pod_info(PodKey {
+                cluster: "test".to_string(),
+                namespace: "default".to_string(),
+                pod_name: name.to_string(),
+                container_name: "app".to_string(),
+            })
+        };
+        let names = |app: &App| -> Vec<String> {
+            pod_tree(&app.pods, app.frozen_order.as_ref(), &app.tombstones)["test"]["default"]
+                .keys()
+                .map(|slot| slot.pod_name.clone())
+                .collect()
+        };
+
+        let mut app = App::new(100);
+        app.add_pod(pod("web-b"));
+        app.add_pod(pod("web-c"));
+        app.toggle_sidebar_freeze();
+        app.add_pod(pod("web-a"));
+        app.remove_pod(&pod("web-b").key);
+        assert_eq!(names(&app), vec!["web-b", "web-c", "web-a"]);
+        assert!(!app.pod_states.contains_key(&pod("web-b").key));
+
+        app.toggle_sidebar_freeze();
+        assert_eq!(names(&app), vec!["web-a", "web-c"]);
+    }
+
+    #[test]
+    fn test_crd_selector_path() {
+        use crate::utils::{parse_crd_selector, selector_at_path, selector_to_labels_string};
+        use serde_json::json;
+
+        let crd = parse_crd_selector("argoproj.io/v1alpha1/Rollout={.spec.selector}").unwrap();
+        assert_eq!(crd.group, "argoproj.io");
+        assert_eq!(crd.path, vec!["spec", "selector"]);
+        assert!(crd.matches_kind("rollout"));
+        assert!(crd.matches_kind("Rollout.argoproj.io"));
+        assert!(!crd.matches_kind("deployment"));
+        assert_eq!(parse_crd_selector("v1/Thing=spec.pods").unwrap().group, "");
+        assert!(parse_crd_selector("argoproj.io/Rollout").is_err());
+        assert!(parse_crd_selector("a/b/c/d=spec").is_err());
+        assert!(parse_crd_selector("v1/Thing=spec..pods").is_err());
+
+        let rollout = json!({"spec": {"selector": {"matchLabels": {"app": "web"}}}});
+        let selector = selector_at_path(&rollout, &crd.path).unwrap().unwrap();
+        assert_eq!(
+            selector_to_labels_string(&selector).as_deref(),
+            Some("app=web")
+        );
+
+        // A plain label map, as in a Service-style spec
+        let plain = json!({"spec": {"selector": {"app": "api", "tier": "backend"}}});
+        let selector = selector_at_path(&plain, &crd.path).unwrap().unwrap();
+        assert_eq!(
+            selector_to_labels_string(&selector).as_deref(),
+            Some("app=api,tier=backend")
+        );
+
+        assert_eq!(selector_at_path(&json!({"spec": {}}), &crd.path), Ok(None));
+        assert!(selector_at_path(&json!({"spec": {"selector": 3}}), &crd.path).is_err());
+    }
+
+    #[test]
+    fn test_day_separator_in_log_view() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use chrono::TimeZone;
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let at = |day: u32, hour: u32, text: &str| {
+            let mut msg = log_line("web", "app", text);
+            msg.timestamp = chrono::Utc
+                .with_ymd_and_hms(2024, 6, day, hour, 0, 0)
+                .unwrap();
+            msg
+        };
+        let logs = [at(1, 22, "late"), at(1, 23, "later"), at(2, 1, "early")].map(LogEntry::new);
+        let render = |show_timestamps: bool, offset: usize| -> Vec<String> {
+            let area = Rect::new(0, 0, 40, 4);
+            let mut buf = Buffer::empty(area);
+            LogView::new(
+                logs.iter().collect(),
+                LogViewOptions {
+                    scroll_offset: offset,
+                    show_timestamps,
+                    ..Default::default()
+                },
+            )
+            .render(area, &mut buf);
+            (0..area.height)
+                .map(|y| {
+                    (0..area.width)
+                        .map(|x| buf[(x, y)].symbol().to_string())
+                        .collect::<String>()
+                        .trim_end()
+                        .to_string()
+                })
+                .collect()
+        };
+
+        let rows = render(true, 0);
+        assert_eq!(rows[2], "──── 2024-06-02 ────");
+        assert!(rows[3].ends_with("early"));
+        // The first visible line starts a new day
+        assert_eq!(render(true, 2)[0], "──── 2024-06-02 ────");
+        // Without timestamps there is nothing to disambiguate
+        assert_eq!(render(false, 0)[2], "early");
+    }
+
+    #[test]
+    fn test_repeated_markers_are_coalesced() {
+        let marker = |pod: &str| log_line(pod, "app", "[stream closed: exit code 1 (Error)]");
+        let mut app = App::new(100);
+        app.add_log(marker("web-1"));
+        app.add_log(log_line("web-2", "app", "unrelated"));
+        app.add_log(marker("web-1"));
+        app.add_log(marker("web-1"));
+        let lines: Vec<&str> = app.log_buffer.iter().map(|m| m.line.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec!["[stream closed: exit code 1 (Error)] ×3", "unrelated"]
+        );
+
+        // A real line from the container in between starts a new marker
+        app.add_log(log_line("web-1", "app", "started again"));
+        app.add_log(marker("web-1"));
+        assert_eq!(app.log_buffer.len(), 4);
+        assert_eq!(app.log_buffer[3].repeats, 1);
+
+        // So does a marker long after the previous one
+        let mut late = marker("web-1");
+        late.timestamp += chrono::Duration::minutes(10);
+        app.add_log(late);
+        assert_eq!(app.log_buffer.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_server_url() {
+        use crate::utils::parse_server_url;
+
+        assert_eq!(
+            parse_server_url("http://127.0.0.1:8001/"),
+            Ok("http://127.0.0.1:8001".to_string())
+        );
+        assert!(parse_server_url("https://bastion.example.com:6443/k8s/prod").is_ok());
+        assert!(parse_server_url("127.0.0.1:8001").is_err());
+        assert!(parse_server_url("https://").is_err());
+        assert!(parse_server_url("https://:6443").is_err());
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--server", "http://localhost:8001", "web"])
+            .unwrap();
+        assert_eq!(cli.server.as_deref(), Some("http://localhost:8001"));
+        let config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+        let config = crate::kubernetes::override_server(config, "http://localhost:8001").unwrap();
+        assert_eq!(config.cluster_url.to_string(), "http://localhost:8001/");
+    }
+
+    #[tokio::test]
+    async fn test_plain_http_server_gets_no_credentials() {
+        use k8s_openapi::api::core::v1::Pod;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A stand-in API server that records the request it gets
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = format!("http://{}", listener.local_addr().unwrap());
+        let request = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 4096];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut chunk).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&chunk[..n]);
+            }
+            let body = r#"{"kind":"Status","apiVersion":"v1","status":"Failure","reason":"NotFound","code":404}"#;
+            let response = format!(
+                "HTTP/1.1 404 Not Found\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_lowercase()
+        });
+
+        let mut config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+        config.auth_info.token = Some("s3cret".to_string().into());
+        assert!(crate::kubernetes::has_credentials(&config.auth_info));
+        let config = crate::kubernetes::override_server(config, &server).unwrap();
+        assert!(crate::kubernetes::is_plain_http(&config));
+        assert!(!crate::kubernetes::has_credentials(&config.auth_info));
+
+        let client = kube::Client::try_from(config).unwrap();
+        let pods: kube::Api<Pod> = kube::Api::namespaced(client, "default");
+        assert!(pods.get_opt("web-1").await.unwrap().is_none());
+        let request = request.await.unwrap();
+        assert!(request.starts_with("get /api/v1/namespaces/default/pods/web-1"));
+        assert!(!request.contains("authorization:"));
+        assert!(!request.contains("s3cret"));
+
+        // https keeps them
+        let mut config = kube::Config::new("https://10.0.0.1:6443".parse().unwrap());
+        config.auth_info.token = Some("s3cret".to_string().into());
+        let config =
+            crate::kubernetes::override_server(config, "https://bastion.example.com:6443").unwrap();
+        assert!(crate::kubernetes::has_credentials(&config.auth_info));
+    }
+
+    #[test]
+    fn test_tee_stdout_target() {
+        // A bare flag never swallows the resource after it
+        let cli = Cli::try_parse_from(["kubectl-tail", "--tee-stdout", "deployment/web"]).unwrap();
+        assert_eq!(cli.tee_stdout, Some(None));
+        assert_eq!(cli.resources, vec!["deployment/web"]);
+
+        let path = std::env::temp_dir().join(format!("kubectl-tail-tee-{}", std::process::id()));
+        let arg = format!("--tee-stdout={}", path.display());
+        let cli = Cli::try_parse_from(["kubectl-tail", arg.as_str(), "web"]).unwrap();
+        assert_eq!(cli.tee_stdout, Some(Some(path.clone())));
+
+        std::fs::write(&path, "stale\n").unwrap();
+        let mut out = crate::open_tee(Some(&path)).unwrap();
+        std::io::Write::write_all(&mut out, b"fresh\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fresh\n");
+        std::fs::remove_file(&path).unwrap();
+
+        // Lines are written by a task of their own, as they are received
+        let (tee_tx, tee_rx) = tokio::sync::mpsc::channel(10);
+        tee_tx.try_send(log_line("web-1", "app", "one")).unwrap();
+        tee_tx.try_send(log_line("web-2", "app", "two")).unwrap();
+        let writer = std::thread::spawn(move || {
+            let mut out = Vec::new();
+            crate::write_tee(&mut out, tee_rx, &std::collections::HashMap::new());
+            String::from_utf8(out).unwrap()
+        });
+        drop(tee_tx);
+        assert_eq!(
+            writer.join().unwrap(),
+            "[test.default/web-1/app] one\n[test.default/web-2/app] two\n"
+        );
+    }
+
+    #[test]
+    fn test_pod_recreated_under_same_name() {
+        use crate::kubernetes::is_other_instance;
+        use crate::types::{ConnectionState, StreamStates};
+        use crate::ui::app::PodKey;
+        use k8s_openapi::api::core::v1::Pod;
+
+        let key = PodKey {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "db-0".to_string(),
+            container_name: "postgres".to_string(),
+        };
+        let base = PodKey {
+            container_name: String::new(),
+            ..key.clone()
+        };
+        let states = StreamStates::default();
+        assert!(!states.take_recreated(&base));
+
+        // Deleted, then back under the same name
+        states.set(&key, ConnectionState::Connected);
+        states.set_draining(&key);
+        states.pod_deleted(&base);
+        assert!(states.take_recreated(&base));
+        states.remove(&key);
+        states.set(&key, ConnectionState::Connecting);
+        assert!(!states.is_draining(&key));
+        // Only the first tail of the new instance is marked
+        assert!(!states.take_recreated(&base));
+
+        // The old instance's task recognizes the new pod and stops
+        let mut pod = Pod::default();
+        pod.metadata.uid = Some("uid-2".to_string());
+        assert!(is_other_instance(&pod, Some("uid-1")));
+        assert!(!is_other_instance(&pod, Some("uid-2")));
+        assert!(!is_other_instance(&pod, None));
+    }
+
+    #[test]
+    fn test_max_line_bytes() {
+        let mut line = "x".repeat(100);
+        assert!(utils::truncate_line_bytes(&mut line, 10, 0));
+        assert_eq!(line, "xxxxxxxxxx… [90 bytes truncated]");
+
+        // Bytes discarded while reading count toward the marker
+        let mut line = "x".repeat(20);
+        assert!(utils::truncate_line_bytes(&mut line, 10, 500));
+        assert_eq!(line, "xxxxxxxxxx… [510 bytes truncated]");
+
+        // Never cut inside a character
+        let mut line = "ab€€".to_string();
+        assert!(utils::truncate_line_bytes(&mut line, 4, 0));
+        assert_eq!(line, "ab… [6 bytes truncated]");
+
+        let mut line = "short".to_string();
+        assert!(!utils::truncate_line_bytes(&mut line, 5, 0));
+        assert_eq!(line, "short");
+
+        assert!(Cli::try_parse_from(["kubectl-tail", "--max-line-bytes", "0", "web"]).is_err());
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "--max-line-bytes", "65536", "web"]).unwrap();
+        assert_eq!(cli.max_line_bytes, Some(65536));
+    }
+
+    #[tokio::test]
+    async fn test_read_line_bounded() {
+        use crate::kubernetes::{ReadLine, read_line_bounded};
+
+        let long = "y".repeat(100_000);
+        let input = format!("short\r\n{long}\nlast");
+        let mut reader = futures::io::BufReader::with_capacity(
+            1024,
+            futures::io::Cursor::new(input.into_bytes()),
+        );
+        let mut buf = Vec::new();
+
+        let read = read_line_bounded(&mut reader, &mut buf, 64).await.unwrap();
+        assert_eq!(
+            read,
+            Some(ReadLine {
+                len: 7,
+                discarded: 0
+            })
+        );
+        assert_eq!(buf, b"short");
+
+        // Only the limit is ever buffered; the rest is skipped as it arrives
+        let read = read_line_bounded(&mut reader, &mut buf, 64).await.unwrap();
+        assert_eq!(
+            read,
+            Some(ReadLine {
+                len: 100_001,
+                discarded: 100_000 - 64
+            })
+        );
+        assert_eq!(buf.len(), 64);
+        assert!(buf.capacity() < 1024);
+
+        // A last line without a newline still counts
+        let read = read_line_bounded(&mut reader, &mut buf, 64).await.unwrap();
+        assert_eq!(
+            read,
+            Some(ReadLine {
+                len: 4,
+                discarded: 0
+            })
+        );
+        assert_eq!(buf, b"last");
+        assert_eq!(
+            read_line_bounded(&mut reader, &mut buf, 64).await.unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_open_cmd() {
+        assert_eq!(
+            utils::fill_open_cmd(
+                "kubectl --context {context} -n {namespace} exec -it {pod} -c {container} -- sh",
+                "prod",
+                "default",
+                "web-1",
+                "app",
+            ),
+            "kubectl --context prod -n default exec -it web-1 -c app -- sh"
+        );
+        assert_eq!(
+            utils::fill_open_cmd("open 'https://dash/{pod}'", "c", "ns", "it's", "app"),
+            "open 'https://dash/'it'\\''s''"
+        );
+
+        let mut app = App::new(100);
+        app.add_log(log_line("web-1", "app", "hello"));
+        app.open_selected();
+        assert_eq!(app.take_open_command(), None);
+
+        app.open_cmd = Some("k9s --context {context} -n {namespace}".to_string());
+        app.open_selected();
+        assert_eq!(
+            app.take_open_command().as_deref(),
+            Some("k9s --context test -n default")
+        );
+        assert_eq!(app.take_open_command(), None);
+    }
+
+    #[test]
+    fn test_tombstones() {
+        use crate::ui::app::{AppMode, PodKey, TreeNodeType, pod_tree};
+
+        let info = pod_info(PodKey {
+            cluster: "test".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "web-1".to_string(),
+            container_name: "app".to_string(),
+        });
+        let mut app = App::new(100);
+        app.tombstone_retention = Some(chrono::Duration::minutes(10));
+        app.tombstone_lines = 2;
+        app.add_pod(info.clone());
+        for line in ["starting", "connecting to db", "panic: no db"] {
+            app.add_log(log_line("web-1", "app", line));
+        }
+        app.add_log(log_line("web-2", "app", "other pod"));
+        app.remove_pod(&info.key);
+
+        // Still in the sidebar, keeping its final lines
+        assert!(app.pods.is_empty());
+        let tree = pod_tree(&app.pods, None, &app.tombstones);
+        assert_eq!(tree["test"]["default"].len(), 1);
+        let lines: Vec<&str> = app.tombstones[0]
+            .lines
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(lines, vec!["connecting to db", "panic: no db"]);
+
+        app.sidebar_visible = true;
+        app.sidebar_item_types = vec![TreeNodeType::Pod(
+            "test".to_string(),
+            "default".to_string(),
+            "web-1".to_string(),
+        )];
+        app.sidebar_state.select(Some(0));
+        app.show_line_detail();
+        assert_eq!(app.mode, AppMode::Tombstone);
+
+        // Dropped after the retention period
+        app.tombstones[0].removed_at -= chrono::Duration::minutes(11);
+        app.update_stats();
+        assert!(app.tombstones.is_empty());
+
+        // A pod coming back under the same name replaces its tombstone
+        app.remove_pod(&info.key);
+        app.add_pod(info.clone());
+        app.remove_pod(&info.key);
+        app.add_pod(info);
+        assert!(app.tombstones.is_empty());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        use crate::exit::{ExitCode, WatcherHealth, WithExitCode, code_of};
+        use kube::core::ErrorResponse;
+        use kube::runtime::watcher::Error as WatchError;
+
+        let response = |code: u16| ErrorResponse {
+            status: "Failure".to_string(),
+            message: "denied".to_string(),
+            reason: "Forbidden".to_string(),
+            code,
+        };
+        let usage = Err::<(), _>(anyhow::anyhow!("bad flag")).exit_code(ExitCode::Usage);
+        assert_eq!(code_of(&usage.unwrap_err()), ExitCode::Usage);
+        // The innermost code wins
+        let no_match = Err::<(), _>(anyhow::anyhow!("no such deployment"))
+            .exit_code(ExitCode::NoMatch)
+            .exit_code(ExitCode::Usage);
+        assert_eq!(code_of(&no_match.unwrap_err()), ExitCode::NoMatch);
+
+        let forbidden: anyhow::Error =
+            WatchError::InitialListFailed(kube::Error::Api(response(403))).into();
+        assert_eq!(code_of(&forbidden), ExitCode::Forbidden);
+        let not_found: anyhow::Error = kube::Error::Api(response(404)).into();
+        assert_eq!(code_of(&not_found), ExitCode::Failure);
+        let refused: anyhow::Error = kube::Error::ProxyProtocolUnsupported {
+            proxy_url: "socks5://proxy".parse().unwrap(),
+        }
+        .into();
+        assert_eq!(code_of(&refused), ExitCode::Unreachable);
+        assert_eq!(code_of(&anyhow::anyhow!("boom")), ExitCode::Failure);
+
+        // Only once every watcher has stopped
+        let health = std::sync::Arc::new(WatcherHealth::default());
+        let (a, b) = (health.started(), health.started());
+        a.stopped(Some(&refused));
+        assert_eq!(health.failure_code(), None);
+        b.stopped(Some(&refused));
+        assert_eq!(health.failure_code(), Some(ExitCode::Unreachable));
+        health.started().stopped(Some(&forbidden));
+        assert_eq!(health.failure_code(), Some(ExitCode::Forbidden));
+
+        // An aborted watcher leaves without an outcome
+        let health = std::sync::Arc::new(WatcherHealth::default());
+        let (a, b) = (health.started(), health.started());
+        a.stopped(Some(&refused));
+        drop(b);
+        assert_eq!(health.failure_code(), Some(ExitCode::Unreachable));
+
+        let help = match Cli::try_parse_from(["kubectl-tail", "--help"]) {
+            Err(e) => e.to_string(),
+            Ok(_) => panic!("--help should print help"),
+        };
+        assert!(help.contains("Exit codes:"));
+    }
+
+    #[test]
+    fn test_nothing_left_to_tail() {
+        use crate::kubernetes::TailOptions;
+        use crate::types::ConnectionState;
+        use crate::ui::app::PodKey;
+
+        let options = TailOptions::default();
+        let key = PodKey {
+            cluster: "prod".to_string(),
+            namespace: "default".to_string(),
+            pod_name: "db-0".to_string(),
+            container_name: "postgres".to_string(),
+        };
+        // A named pod being tailed
+        let named = options.watchers.started();
+        options.stream_states.set(&key, ConnectionState::Connected);
+        assert!(!crate::nothing_left(&options));
+
+        // Deleted: its stream is gone and its watcher waits for nothing else
+        options.watchers.named_pod_gone(named.id(), true);
+        assert!(!crate::nothing_left(&options));
+        options.stream_states.remove(&key);
+        assert!(crate::nothing_left(&options));
+
+        // A selector watcher with no pods is idle, not done
+        let selector = options.watchers.started();
+        assert!(!crate::nothing_left(&options));
+        selector.stopped(None);
+
+        // Recreated (e.g. a StatefulSet replica): tailing again
+        options.watchers.named_pod_gone(named.id(), false);
+        assert!(!crate::nothing_left(&options));
+        options.watchers.named_pod_gone(named.id(), true);
+
+        // A stream that gave up can be restarted with r, but is not tailing
+        options
+            .stream_states
+            .set(&key, ConnectionState::Failed("forbidden".to_string()));
+        assert!(crate::nothing_left(&options));
+
+        let mut app = App::new(10);
+        app.set_nothing_left(true);
+        assert!(
+            app.active_filters
+                .contains(&"all streams ended, nothing left to tail".to_string())
+        );
+        app.set_nothing_left(false);
+        assert!(!app.nothing_left);
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--exit-when-done", "pod/a"]).unwrap();
+        assert!(cli.exit_when_done);
+    }
+
+    #[test]
+    fn test_registered_watchers_are_not_done() {
+        use crate::kubernetes::TailOptions;
+
+        // Watchers are counted when spawned, before their task first runs and
+        // before any pod is found, so a session that just started is not done
+        let options = TailOptions::default();
+        let _a = options.watchers.started();
+        let _b = options.watchers.started();
+        assert_eq!(options.watchers.waiting(), 2);
+        assert!(!crate::nothing_left(&options));
+        assert_eq!(options.watchers.failure_code(), None);
+
+        // Likewise a resource followed with --watch-selector-change alone,
+        // until its task ends
+        let options = TailOptions::default();
+        let following = options.watchers.following();
+        assert!(!crate::nothing_left(&options));
+        drop(following);
+        assert_eq!(options.watchers.waiting(), 0);
+    }
+
+    #[test]
+    fn test_gone_named_pod_watcher_is_counted_once() {
+        use crate::kubernetes::TailOptions;
+
+        // `pod/a -l app=x`: pod a is deleted, then its watcher fails while the
+        // selector watcher still runs
+        let options = TailOptions::default();
+        let named = options.watchers.started();
+        let _selector = options.watchers.started();
+        options.watchers.named_pod_gone(named.id(), true);
+        assert_eq!(options.watchers.waiting(), 1);
+        named.stopped(Some(&anyhow::anyhow!("boom")));
+        assert_eq!(options.watchers.waiting(), 1);
+        assert!(!crate::nothing_left(&options));
+        assert_eq!(options.watchers.failure_code(), None);
+    }
+
+    #[tokio::test]
+    async fn test_session_duration() {
+        let cli = Cli::try_parse_from(["kubectl-tail", "--duration", "5m", "web"]).unwrap();
+        assert_eq!(cli.duration, Some(std::time::Duration::from_secs(300)));
+
+        let short = crate::session_deadline(Some(std::time::Duration::from_millis(10)));
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_secs(5), short)
+                .await
+                .is_ok()
+        );
+        // Without --duration the session never times out
+        let endless = crate::session_deadline(None);
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(20), endless)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_access_log_columns() {
+        use crate::config::{AccessLogEntry, Config, parse_access_log};
+
+        let config = Config::parse(
+            "access_logs:\n  - name: team\n    method: req.verb\n    path: req.url\n    status: code\n    duration: took_ms\n    duration_unit: ms\n",
+        )
+        .unwrap();
+        let schemas = config.access_log_schemas().unwrap();
+
+        // A team format from the config file, with nested fields
+        assert_eq!(
+            parse_access_log(
+                r#"{"req":{"verb":"get","url":"/healthz"},"code":"503","took_ms":1500}"#,
+                &schemas
+            ),
+            Some(AccessLogEntry {
+                method: "GET".to_string(),
+                path: "/healthz".to_string(),
+                status: 503,
+                duration: Some("1.50s".to_string()),
+            })
+        );
+        // Built-in Envoy and Kubernetes audit formats
+        let envoy = parse_access_log(
+            r#"{"method":"POST","path":"/api","response_code":201,"duration":12}"#,
+            &schemas,
+        )
+        .unwrap();
+        assert_eq!(
+            (envoy.status, envoy.duration.as_deref()),
+            (201, Some("12ms"))
+        );
+        let audit = parse_access_log(
+            r#"{"kind":"Event","verb":"list","requestURI":"/api/v1/pods","responseStatus":{"code":403}}"#,
+            &schemas,
+        )
+        .unwrap();
+        assert_eq!((audit.method.as_str(), audit.status), ("LIST", 403));
+        // Other JSON and plain text are left alone
+        assert_eq!(parse_access_log(r#"{"msg":"hello"}"#, &schemas), None);
+        assert_eq!(parse_access_log("GET / 200", &schemas), None);
+
+        let bad = Config::parse(
+            "access_logs:\n  - {name: x, method: m, path: p, status: s, duration_unit: hours}\n",
+        )
+        .unwrap();
+        assert!(bad.access_log_schemas().is_err());
+    }
+
+    #[test]
+    fn test_since_flag() {
+        use crate::ui::app::PodKey;
+        use std::time::Duration;
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--since", "15m", "--tail", "10", "pod/a"])
+            .unwrap();
+        assert_eq!(cli.since, Some(Duration::from_secs(900)));
+        assert!(Cli::try_parse_from(["kubectl-tail", "--since", "soon", "pod/a"]).is_err());
+
+        assert_eq!(crate::since_seconds(Duration::from_secs(7200)), 7200);
+        assert_eq!(crate::since_seconds(Duration::from_millis(1500)), 2);
+        assert_eq!(crate::since_seconds(Duration::ZERO), 1);
+
+        let mut app = App::new(100);
+        app.since_seconds = Some(900);
+        app.add_pod(pod_info(PodKey {
+            cluster: "c".to_string(),
+            namespace: "ns".to_string(),
+            pod_name: "web".to_string(),
+            container_name: "app".to_string(),
+        }));
+        assert_eq!(
+            app.kubectl_commands(),
+            vec!["kubectl logs --context c -n ns web -c app -f --since=900s".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_fixed_wrap_width() {
+        use crate::ui::widgets::wrap_at;
+        use ratatui::style::{Color, Style};
+        use ratatui::text::{Line, Span};
+
+        let line = Line::from(vec![
+            Span::styled("[web] ", Style::default().fg(Color::Cyan)),
+            Span::raw("abcdefgh"),
+        ]);
+        let rows = wrap_at(line, 5);
+        let text: Vec<String> = rows.iter().map(|row| row.to_string()).collect();
+        assert_eq!(text, vec!["[web]", " abcd", "efgh"]);
+        // Styles carry over the break
+        assert_eq!(rows[1].spans[0].style.fg, Some(Color::Cyan));
+        assert_eq!(rows[1].spans[1].content, "abcd");
+        // Wide characters are never split across rows
+        let rows = wrap_at(Line::raw("ab日本"), 3);
+        assert_eq!(
+            rows.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+            ["ab", "日", "本"]
+        );
+
+        let mut app = App::new(10);
+        assert_eq!(app.fixed_wrap_width(), None);
+        app.toggle_fixed_wrap();
+        assert_eq!(app.fixed_wrap_width(), Some(120));
+        app.toggle_fixed_wrap();
+        assert_eq!(app.fixed_wrap_width(), None);
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--wrap-width", "80", "pod/a"]).unwrap();
+        assert_eq!(cli.wrap_width, Some(80));
+        assert!(Cli::try_parse_from(["kubectl-tail", "--wrap-width", "0", "pod/a"]).is_err());
+    }
+
+    #[test]
+    fn test_detail_pane_resize() {
+        use crate::ui::layout::create_layout;
+        use ratatui::layout::Rect;
+
+        let mut app = App::new(10);
+        assert_eq!(app.detail_pane_width(), None);
+        // Resizing a hidden pane opens it at its current width
+        app.resize_detail_pane(true);
+        assert_eq!(app.detail_pane_width(), Some(40));
+        app.resize_detail_pane(true);
+        assert_eq!(app.detail_pane_width(), Some(45));
+        for _ in 0..20 {
+            app.resize_detail_pane(false);
+        }
+        assert_eq!(app.detail_pane_width(), Some(15));
+        // The width is kept while the pane is hidden
+        app.toggle_detail_pane();
+        assert_eq!(app.detail_pane_width(), None);
+        app.toggle_detail_pane();
+        assert_eq!(app.detail_pane_width(), Some(15));
+
+        app.add_log(log_line("web", "app", r#"{"level":"info","msg":"up"}"#));
+        let (line, pod) = app.detail_pane_content();
+        assert_eq!(line.map(|m| m.pod_name.as_str()), Some("web"));
+        assert!(pod.is_none());
+
+        let area = Rect::new(0, 0, 200, 50);
+        let layout = create_layout(area, true, Some(50));
+        assert_eq!(layout.sidebar.width, 60);
+        assert_eq!(layout.main.width + layout.detail.width, 140);
+        assert_eq!(layout.detail.width, 70);
+        let layout = create_layout(area, false, None);
+        assert_eq!(layout.main.width, 200);
+        assert_eq!(layout.detail, Rect::default());
+    }
+
+    #[test]
+    fn test_search_results_only() {
+        let mut app = App::new(100);
+        for line in ["GET /a 200", "GET /b 500", "POST /c 500", "GET /d 200"] {
+            app.add_log(log_line("web", "app", line));
+        }
+
+        // Nothing to narrow to without a search
+        app.toggle_results_only();
+        assert!(!app.results_only);
+
+        app.search_pattern = " 500".to_string();
+        app.update_search_matches();
+        assert_eq!(app.search_matches, vec![1, 2]);
+
+        app.toggle_results_only();
+        let lines: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(lines, vec!["GET /b 500", "POST /c 500"]);
+        assert_eq!(app.search_matches, vec![0, 1]);
+
+        // Back to the full view, with matches indexing into it again
+        app.toggle_results_only();
+        assert_eq!(app.filtered_logs().len(), 4);
+        assert_eq!(app.search_matches, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_previous_prefix() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "-p", "pod/a"]).unwrap();
+        assert!(cli.previous);
+
+        let mut app = App::new(10);
+        app.previous = cli.previous;
+        app.add_log(log_line("web", "app", "panic: boom"));
+        app.add_log(log_line("web", "app", "[pod re-created]"));
+        let area = Rect::new(0, 0, 60, 2);
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            app.log_buffer.iter().collect(),
+            LogViewOptions {
+                show_prefix: true,
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row = |y| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(0).starts_with("[test.default/web/app (previous)] panic: boom"));
+        // Status lines kubectl-tail writes itself are not from the old instance
+        assert!(row(1).starts_with("[test.default/web/app] [pod re-created]"));
+    }
+
+    #[test]
+    fn test_level_colors() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--no-level-colors", "pod/a"]).unwrap();
+        assert!(cli.no_level_colors);
+
+        let logs: Vec<_> = ["ERROR db down", "INFO ready", "panic: boom"]
+            .into_iter()
+            .map(|line| LogEntry::new(log_line("web", "app", line)))
+            .collect();
+        let render = |level_colors: bool| {
+            let area = Rect::new(0, 0, 20, 3);
+            let mut buf = Buffer::empty(area);
+            LogView::new(
+                logs.iter().collect(),
+                LogViewOptions {
+                    level_colors,
+                    ..Default::default()
+                },
+            )
+            .render(area, &mut buf);
+            (0..area.height).map(|y| buf[(0, y)].fg).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            render(true),
+            vec![Color::Red, Color::Reset, Color::LightMagenta]
+        );
+        assert_eq!(render(false), vec![Color::Reset; 3]);
+    }
+
+    #[test]
+    fn test_json_output() {
+        use crate::cli::OutputFormat;
+        use chrono::TimeZone;
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "--output", "json", "pod/a"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        let cli =
+            Cli::try_parse_from(["kubectl-tail", "--template", "{{.message}}", "pod/a"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Text);
+        assert!(
+            Cli::try_parse_from([
+                "kubectl-tail",
+                "-o",
+                "json",
+                "--template",
+                "{{.pod}}",
+                "pod/a"
+            ])
+            .is_err()
+        );
+
+        let mut msg = log_line("web", "app", "said \"hi\"\tthen left");
+        msg.timestamp = chrono::Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let record: serde_json::Value = serde_json::from_str(&msg.to_json(None)).unwrap();
+        assert_eq!(
+            record,
+            serde_json::json!({
+                "cluster": "test",
+                "namespace": "default",
+                "pod_name": "web",
+                "container_name": "app",
+                "timestamp": "2024-06-01T12:00:00+00:00",
+                "line": "said \"hi\"\tthen left",
+            })
+        );
+        assert!(!msg.to_json(None).contains('\n'));
+        assert!(msg.to_json(Some(7)).contains("\"seq\":7"));
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Decompress `chunks` with kubernetes::gunzip, returning the output
+    fn gunzip_chunks(chunks: Vec<Vec<u8>>) -> std::io::Result<Vec<u8>> {
+        use futures::{AsyncReadExt, TryStreamExt};
+
+        futures::executor::block_on(async {
+            let body = futures::stream::iter(chunks.into_iter().map(Ok)).into_async_read();
+            let mut output = Vec::new();
+            crate::kubernetes::gunzip(body)
+                .read_to_end(&mut output)
+                .await?;
+            Ok(output)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_gunzip_hands_over_output_per_chunk() {
+        use futures::{AsyncBufReadExt, StreamExt, TryStreamExt};
+
+        // "first line\n" and "second line\n", each written with a sync flush;
+        // the second chunk is not there yet
+        let first = from_hex("1f8b08000000000002034acb2c2a2e51c8c9cc4be502000000ffff");
+        let body = futures::stream::iter([Ok(first)])
+            .chain(futures::stream::pending())
+            .into_async_read();
+        let mut lines = crate::kubernetes::gunzip(body).lines();
+        // The first line is passed on without waiting for more input
+        let line = tokio::time::timeout(std::time::Duration::from_secs(5), lines.next())
+            .await
+            .expect("decoder waited for the next chunk");
+        assert_eq!(line.unwrap().unwrap(), "first line");
+    }
+
+    #[test]
+    fn test_gunzip_dynamic_huffman() {
+        let text: String = (0..40)
+            .map(|i| {
+                format!(
+                    "level=info msg=\"GET /api/v1/items/{}\" status={} latency={}ms\n",
+                    i,
+                    if i % 7 == 0 { 503 } else { 200 },
+                    i * 13 % 97
+                )
+            })
+            .collect();
+        let gzipped = from_hex(concat!(
+            "1f8b08000000000002038cd5414ac4401085e1bda7087381745575773a8b2cc50b78812051029951481cf0",
+            "f6e266562ff81fe0a717efa37a5beecb36adb7f7cfeeba7f4c9797e7d7ae9fbfd6fe6efd7a2cd7bd4f976e",
+            "3fe6e37b9f4a8a6e9b8fe5f6f633a5ebfeb4fddbdaa3f5941ead058a5dc65e511c328e11c559c6c5515c64",
+            "5c0b8aab8c8786e2412e351a8a9b7e19b5a35e0a1a497a2a86c434b1cc949836561813d3c806e6c4b29cab",
+            "3128a699b1ad4d33b3ccea41cfcdac98869621164dadc08ba2a95586c54d0e36302caea98d0c8b6b6aec2e",
+            "b8be67ceb0b896160c8b6b6a9961714dad322cdef460108ba6d6e0ffa3a9b1b5e3e4df64a7214e3e4e8625",
+            "b4b4ccb0c4c9d7c9b044918355862534b5c6b084a636322ca1af1a3b0d71f27ffe61f9050000ffff030041",
+            "227fcaa0090000"
+        ));
+        assert_eq!(
+            gunzip_chunks(vec![gzipped.clone()]).unwrap(),
+            text.as_bytes()
+        );
+        // Codes and back-references split across chunks
+        let bytewise = gzipped.iter().map(|&b| vec![b]).collect();
+        assert_eq!(gunzip_chunks(bytewise).unwrap(), text.as_bytes());
+    }
+
+    #[test]
+    fn test_gunzip_members_and_errors() {
+        let member = from_hex(concat!(
+            "1f8b08000000000002034acb2c2a2e51c8c9cc4be502000000ffff",
+            "2a4e4dcecf4b81b001000000ffff03002c5a455d17000000"
+        ));
+        // Concatenated members are decoded one after another
+        assert_eq!(
+            gunzip_chunks(vec![member.clone(), member.clone()]).unwrap(),
+            b"first line\nsecond line\nfirst line\nsecond line\n"
+        );
+
+        let mut corrupt = member.clone();
+        let crc = corrupt.len() - 8;
+        corrupt[crc] ^= 1;
+        assert!(gunzip_chunks(vec![corrupt]).is_err());
+        // A stream cut off mid-member, or one that isn't gzip at all
+        assert!(gunzip_chunks(vec![member[..20].to_vec()]).is_err());
+        assert!(gunzip_chunks(vec![b"plain text\n".to_vec()]).is_err());
+    }
+
+    #[test]
+    fn test_wire_bytes_only_with_gzip() {
+        use crate::types::StreamStates;
+        use crate::ui::app::PodKey;
+        use std::sync::atomic::Ordering;
+
+        let key = PodKey {
+            cluster: "c".to_string(),
+            namespace: "ns".to_string(),
+            pod_name: "web".to_string(),
+            container_name: "app".to_string(),
+        };
+        let states = StreamStates::default();
+        assert_eq!(states.wire_total(), None);
+        states.wire_counter(&key).fetch_add(300, Ordering::Relaxed);
+        states.wire_counter(&key).fetch_add(20, Ordering::Relaxed);
+        assert_eq!(states.wire_total(), Some(320));
+
+        let cli = Cli::try_parse_from(["kubectl-tail", "deploy/web", "--gzip"]).unwrap();
+        assert!(cli.gzip);
+    }
+
+    #[test]
+    fn test_received_bytes() {
+        use crate::types::StreamStates;
+        use crate::ui::app::PodKey;
+        use std::sync::atomic::Ordering;
+
+        assert_eq!(utils::format_bytes(512), "512 B");
+        assert_eq!(utils::format_bytes(1536), "1.5 KiB");
+        assert_eq!(utils::format_bytes(13 * 1024 * 1024), "13.0 MiB");
+        assert_eq!(utils::format_bytes(3 << 30), "3.0 GiB");
+
+        let key = |container: &str| PodKey {
+            cluster: "c".to_string(),
+            namespace: "ns".to_string(),
+            pod_name: "web".to_string(),
+            container_name: container.to_string(),
+        };
+        let states = StreamStates::default();
+        states
+            .received_counter(&key("app"))
+            .fetch_add(100, Ordering::Relaxed);
+        // A reconnected stream keeps adding to the same count
+        states
+            .received_counter(&key("app"))
+            .fetch_add(50, Ordering::Relaxed);
+        states
+            .received_counter(&key("envoy"))
+            .fetch_add(10, Ordering::Relaxed);
+        assert_eq!(states.received(&key("app")), 150);
+        assert_eq!(states.received_total(), (160, 2));
+    }
+
+    #[test]
+    fn test_notes() {
+        use crate::checkpoint::{self, Checkpoint};
+        use crate::ui::app::AppMode;
+        use crate::ui::events::handle_key_event;
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let mut app = App::new(100);
+        app.add_log(log_line("web", "app", "GET /health 200"));
+        app.paused = true;
+
+        // Typed after A; inserted even while paused
+        let press = |app: &mut App, code| {
+            handle_key_event(app, KeyEvent::new(code, KeyModifiers::NONE));
+        };
+        press(&mut app, KeyCode::Char('A'));
+        assert_eq!(app.mode, AppMode::Note);
+        for c in "deployed fix".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.log_buffer[1].is_note());
+        assert_eq!(app.log_buffer[1].line, "deployed fix");
+
+        // An empty note adds nothing
+        press(&mut app, KeyCode::Char('A'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.log_buffer.len(), 2);
+
+        // Notes stay in view under filters and are marked in exports
+        app.filter_pattern = "nomatch".to_string();
+        assert_eq!(app.selected_lines(), vec!["[note] deployed fix"]);
+        app.filter_pattern.clear();
+        assert_eq!(
+            app.selected_lines(),
+            vec![
+                "[test.default/web/app] GET /health 200",
+                "[note] deployed fix"
+            ]
+        );
+
+        // And survive a checkpoint
+        let dir = std::env::temp_dir().join(format!("kubectl-tail-notes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("checkpoint.jsonl");
+        checkpoint::write(&path, &app).unwrap();
+        let mut resumed = App::new(100);
+        assert_eq!(
+            Checkpoint::load(&path)
+                .unwrap()
+                .unwrap()
+                .restore(&mut resumed),
+            2
+        );
+        assert!(resumed.log_buffer[1].is_note());
+        assert_eq!(resumed.log_buffer[1].timestamp, app.log_buffer[1].timestamp);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_exclude_pattern() {
+        let cli = Cli::try_parse_from(["kubectl-tail", "--exclude", "healthz", "pod/a"]).unwrap();
+        assert!(crate::exclude_regex(&cli).unwrap().is_some());
+        let cli = Cli::try_parse_from(["kubectl-tail", "--exclude", "(", "pod/a"]).unwrap();
+        match crate::exclude_regex(&cli) {
+            Ok(_) => panic!("an invalid --exclude must be rejected"),
+            Err(e) => {
+                assert_eq!(crate::exit::code_of(&e), crate::exit::ExitCode::Usage);
+                assert!(e.to_string().contains("Invalid --exclude pattern '('"));
+            }
+        }
+
+        let mut app = App::new(100);
+        app.exclude_pattern = Some(regex::Regex::new("GET /healthz").unwrap());
+        for line in ["GET /healthz 200", "POST /orders 201", "GET /healthz 200"] {
+            app.add_log(log_line("web", "app", line));
+        }
+        let lines: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|m| m.line.as_str())
+            .collect();
+        assert_eq!(lines, vec!["POST /orders 201"]);
+    }
+
+    #[tokio::test]
+    async fn test_scale_to_zero_waiting() {
+        use crate::ui::app::PodKey;
+        use crate::ui::events::AppEvent;
+        use crate::{IDLE_HEARTBEAT, WatchPresence, observe_pod, report_idle};
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut presence = WatchPresence::default();
+
+        // Scaled to zero before we started: idle once the listing is complete
+        presence.relist();
+        assert_eq!(presence.listed(at(0)), Some(true));
+        assert_eq!(presence.heartbeat_due(at(10)), None);
+        assert_eq!(
+            presence.heartbeat_due(at(0) + IDLE_HEARTBEAT),
+            Some(IDLE_HEARTBEAT)
+        );
+
+        // Scaled back up through the watch, with --annotation tail=true
+        let ctx = crate::TailContext {
+            annotations: Arc::new(vec![utils::parse_annotation_filter("tail=true").unwrap()]),
+            ..test_context()
+        };
+        let handles = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(64);
+        let event_tx = Some(event_tx);
+        let target = "pods matching app=api";
+        let mut app = App::new(10);
+        report_idle(&ctx, target, true, &event_tx).await;
+        let annotated = |name: &str, tail: &str| {
+            let mut pod = running_pod(name, &[("app", true)]);
+            pod.metadata.annotations = Some([("tail".to_string(), tail.to_string())].into());
+            pod
+        };
+        let observe = async |pod, presence: &mut WatchPresence, secs| {
+            let change = observe_pod(
+                pod,
+                presence,
+                &mut None,
+                &handles,
+                &ctx,
+                &event_tx,
+                at(secs),
+            )
+            .await;
+            if let Some(idle) = change {
+                report_idle(&ctx, target, idle, &event_tx).await;
+            }
+            change
+        };
+
+        // A pod the filters leave out doesn't end the wait
+        assert_eq!(
+            observe(annotated("api-0", "false"), &mut presence, 80).await,
+            None
+        );
+        // The first matching pod does, and more change nothing
+        assert_eq!(
+            observe(annotated("api-1", "true"), &mut presence, 90).await,
+            Some(false)
+        );
+        assert_eq!(
+            observe(annotated("api-2", "true"), &mut presence, 90).await,
+            None
+        );
+        assert_eq!(presence.heartbeat_due(at(500)), None);
+        {
+            let guard = handles.lock().await;
+            let mut tailed: Vec<&str> =
+                guard.keys().map(|k: &PodKey| k.pod_name.as_str()).collect();
+            tailed.sort();
+            assert_eq!(tailed, ["api-1", "api-2"]);
+            for handle in guard.values().flatten() {
+                handle.abort();
+            }
+        }
+        while let Ok(event) = event_rx.try_recv() {
+            if let AppEvent::WatchIdle { watch, idle } = event {
+                app.set_watch_idle(watch, idle);
+            }
+        }
+        assert!(app.idle_watches.is_empty());
+        assert!(!app.active_filters.iter().any(|f| f.starts_with("waiting")));
+
+        // Scaled to zero again
+        assert_eq!(presence.delete("api-1", at(100)), None);
+        assert_eq!(presence.delete("api-2", at(100)), Some(true));
+
+        // A relist after a watch restart drops pods deleted in between
+        assert_eq!(presence.apply("api-3", at(110)), Some(false));
+        presence.relist();
+        assert_eq!(presence.listed(at(120)), Some(true));
+
+        // Before the first listing completes, no pods doesn't mean waiting
+        let mut fresh = WatchPresence::default();
+        assert_eq!(fresh.delete("gone", at(0)), None);
+        assert_eq!(fresh.replace(Default::default(), at(1)), Some(true));
+
+        let mut app = App::new(10);
+        let watch = "pods matching app=api in prod/default".to_string();
+        app.set_watch_idle(watch.clone(), true);
+        assert!(
+            app.active_filters
+                .contains(&"waiting for pods matching app=api in prod/default".to_string())
+        );
+        app.set_watch_idle(watch, false);
+        assert!(app.idle_watches.is_empty());
+        assert!(!app.active_filters.iter().any(|f| f.starts_with("waiting")));
+    }
+
+    #[test]
+    fn test_watch_reconnect() {
+        use crate::{
+            WATCH_RETRY_MAX, WATCH_RETRY_MIN, next_watch_retry, watch_error_retryable,
+            watch_gives_up,
+        };
+        use kube::core::ErrorResponse;
+        use kube::runtime::watcher::Error as WatchError;
+        use std::time::Duration;
+
+        assert_eq!(next_watch_retry(None), WATCH_RETRY_MIN);
+        assert_eq!(next_watch_retry(Some(WATCH_RETRY_MIN)), WATCH_RETRY_MIN * 2);
+        assert_eq!(
+            next_watch_retry(Some(Duration::from_secs(20))),
+            WATCH_RETRY_MAX
+        );
+
+        let response = |code: u16| ErrorResponse {
+            status: "Failure".to_string(),
+            message: "failed".to_string(),
+            reason: String::new(),
+            code,
+        };
+        // An expired resourceVersion or a server hiccup: resume the watch
+        assert!(watch_error_retryable(&WatchError::WatchError(response(
+            410
+        ))));
+        assert!(watch_error_retryable(&WatchError::WatchFailed(
+            kube::Error::Api(response(500))
+        )));
+        // Rejected credentials or permissions won't come right by retrying
+        assert!(!watch_error_retryable(&WatchError::WatchError(response(
+            401
+        ))));
+        assert!(!watch_error_retryable(&WatchError::WatchStartFailed(
+            kube::Error::Api(response(403))
+        )));
+
+        // A failed initial list is retried too, until the backoff peaks
+        let list_failed = WatchError::InitialListFailed(kube::Error::Api(response(503)));
+        assert!(!watch_gives_up(&list_failed, false, None));
+        assert!(!watch_gives_up(&list_failed, false, Some(WATCH_RETRY_MIN)));
+        assert!(watch_gives_up(&list_failed, false, Some(WATCH_RETRY_MAX)));
+        // Once pods were listed, the watch is resumed for as long as it takes
+        assert!(!watch_gives_up(&list_failed, true, Some(WATCH_RETRY_MAX)));
+        let forbidden = WatchError::InitialListFailed(kube::Error::Api(response(403)));
+        assert!(watch_gives_up(&forbidden, false, None));
+
+        let mut app = App::new(10);
+        let watch = "pods matching app=api in default".to_string();
+        app.set_watch_reconnecting("prod".to_string(), watch.clone(), true);
+        app.set_watch_reconnecting("prod".to_string(), "pod db in default".to_string(), true);
+        assert_eq!(app.reconnecting_watches["prod"].len(), 2);
+        app.set_watch_reconnecting("prod".to_string(), watch, false);
+        assert!(app.reconnecting_watches.contains_key("prod"));
+        app.set_watch_reconnecting("prod".to_string(), "pod db in default".to_string(), false);
+        assert!(app.reconnecting_watches.is_empty());
+    }
+
+    #[test]
+    fn test_expand_context_globs() {
+        assert!(utils::glob_match("prod-*", "prod-eu"));
+        assert!(utils::glob_match("*-eu-?", "prod-eu-1"));
+        assert!(utils::glob_match("*", ""));
+        assert!(!utils::glob_match("prod-*", "staging-eu"));
+        assert!(!utils::glob_match("prod-?", "prod-eu"));
+
+        let names: Vec<String> = ["prod-eu", "staging", "prod-us"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let expand = |patterns: &[&str]| {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            utils::expand_context_globs(&patterns, &names)
+        };
+        assert_eq!(expand(&["prod-*"]).unwrap(), vec!["prod-eu", "prod-us"]);
+        // Plain names pass through and duplicates collapse
+        assert_eq!(
+            expand(&["prod-us", "prod-*", "other"]).unwrap(),
+            vec!["prod-us", "prod-eu", "other"]
+        );
+        let err = expand(&["dev-*"]).unwrap_err();
+        assert!(err.contains("'dev-*'"));
+        assert!(err.contains("prod-eu, staging, prod-us"));
+    }
+
+    #[test]
+    fn test_reconnect_skips_only_replayed_lines() {
+        use crate::kubernetes::SeenLines;
+
+        let at = |s: &str| s.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        let mut seen = SeenLines::default();
+        for (ts, line) in [
+            ("2024-06-01T12:00:00.100Z", "GET /health 200"),
+            ("2024-06-01T12:00:00.200Z", "GET /health 200"),
+            ("2024-06-01T12:00:00.300Z", "GET /orders 200"),
+        ] {
+            assert!(!seen.is_replay(at(ts), line));
+            seen.record(at(ts), line);
+        }
+        assert_eq!(seen.last_time(), Some(at("2024-06-01T12:00:00.300Z")));
+
+        // sinceTime resumes at 12:00:00, so the whole second comes again
+        assert!(seen.is_replay(at("2024-06-01T12:00:00.100Z"), "GET /health 200"));
+        assert!(seen.is_replay(at("2024-06-01T12:00:00.200Z"), "GET /health 200"));
+        assert!(seen.is_replay(at("2024-06-01T12:00:00.300Z"), "GET /orders 200"));
+        // The same line written again later is new
+        assert!(!seen.is_replay(at("2024-06-01T12:00:01.000Z"), "GET /health 200"));
+        // As is a different line written at the same instant as the last one
+        assert!(!seen.is_replay(at("2024-06-01T12:00:00.300Z"), "GET /cart 200"));
+    }
+
+    #[test]
+    fn test_split_timestamp() {
+        let (ts, line) = utils::split_timestamp("2024-06-01T12:34:56.123456789Z GET /health 200");
+        assert_eq!(line, "GET /health 200");
+        assert_eq!(
+            ts.unwrap()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "2024-06-01T12:34:56.123Z"
+        );
+
+        // A line without a prefix comes back whole
+        assert_eq!(utils::split_timestamp("plain line"), (None, "plain line"));
+        assert_eq!(utils::split_timestamp(""), (None, ""));
+    }
+
+    #[test]
+    fn test_container_resources() {
+        use crate::kubernetes::{container_resources, finished_container};
+        use k8s_openapi::api::core::v1::{
+            Container, ContainerState, ContainerStateTerminated, ContainerStatus, Pod, PodSpec,
+            PodStatus, ResourceRequirements,
+        };
+        use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+        let quantities = |pairs: &[(&str, &str)]| {
+            Some(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), Quantity(v.to_string())))
+                    .collect(),
+            )
+        };
+        let pod = Pod {
+            spec: Some(PodSpec {
+                restart_policy: Some("Never".to_string()),
+                containers: vec![
+                    Container {
+                        name: "app".to_string(),
+                        resources: Some(ResourceRequirements {
+                            requests: quantities(&[("cpu", "100m"), ("memory", "128Mi")]),
+                            limits: quantities(&[("memory", "256Mi")]),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    Container {
+                        name: "sidecar".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Failed".to_string()),
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "app".to_string(),
+                    state: Some(ContainerState {
+                        terminated: Some(ContainerStateTerminated {
+                            exit_code: 137,
+                            reason: Some("OOMKilled".to_string()),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            container_resources(&pod, "app").as_deref(),
+            Some("requests cpu=100m memory=128Mi, limits memory=256Mi")
+        );
+        assert_eq!(container_resources(&pod, "sidecar"), None);
+        assert_eq!(container_resources(&pod, "missing"), None);
+
+        // An OOM kill comes with the limit that was hit
+        assert_eq!(
+            finished_container(&pod, "app").as_deref(),
+            Some("exit code 137 (OOMKilled, memory limit 256Mi)")
+        );
+    }
+
+    #[test]
+    fn test_compact_timestamps() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use chrono::TimeZone;
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let at = |second: u32, millis: i64, text: &str| {
+            let mut msg = log_line("web", "app", text);
+            msg.timestamp = chrono::Utc
+                .with_ymd_and_hms(2024, 6, 1, 12, 0, second)
+                .unwrap()
+                + chrono::Duration::milliseconds(millis);
+            msg
+        };
+        let logs = [at(1, 100, "a"), at(1, 250, "b"), at(2, 5, "c")].map(LogEntry::new);
+        let area = Rect::new(0, 0, 30, 3);
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            logs.iter().collect(),
+            LogViewOptions {
+                show_timestamps: true,
+                timestamp_style: TimestampStyle::Compact,
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row = |y| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        assert_eq!(row(0), "12:00:01.100 a");
+        assert_eq!(row(1), "        .250 b");
+        assert_eq!(row(2), "12:00:02.005 c");
+
+        // `t` cycles off, full, compact, relative, off
+        let mut app = App::new(10);
+        app.cycle_timestamps();
+        assert!(app.show_timestamps && app.timestamp_style == TimestampStyle::Full);
+        app.cycle_timestamps();
+        assert!(app.show_timestamps && app.timestamp_style == TimestampStyle::Compact);
+        app.cycle_timestamps();
+        assert!(app.show_timestamps && app.timestamp_style == TimestampStyle::Relative);
+        app.cycle_timestamps();
+        assert!(!app.show_timestamps);
+    }
+
+    #[test]
+    fn test_reverse_order() {
+        use crate::ui::events::handle_key_event;
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let logs: Vec<_> = ["one", "two", "three"]
+            .iter()
+            .map(|text| log_line("web", "app", text))
+            .map(LogEntry::new)
+            .collect();
+        let render = |follow: bool, offset: usize| -> Vec<String> {
+            let area = Rect::new(0, 0, 20, 2);
+            let mut buf = Buffer::empty(area);
+            LogView::new(
+                logs.iter().collect(),
+                LogViewOptions {
+                    scroll_offset: offset,
+                    follow,
+                    reversed: true,
+                    ..Default::default()
+                },
+            )
+            .render(area, &mut buf);
+            (0..area.height)
+                .map(|y| {
+                    (0..area.width)
+                        .map(|x| buf[(x, y)].symbol().to_string())
+                        .collect::<String>()
+                        .trim_end()
+                        .to_string()
+                })
+                .collect()
+        };
+        // Following keeps the newest line on top
+        assert_eq!(render(true, 0), vec!["three", "two"]);
+        // Scrolled, the offset is the top (newest) line in view
+        assert_eq!(render(false, 1), vec!["two", "one"]);
+        assert_eq!(render(false, 0), vec!["one", ""]);
+
+        // Down moves to older lines, up back towards the newest
+        let mut app = App::new(100);
+        for entry in &logs {
+            app.add_log(entry.msg.clone());
+        }
+        let press = |app: &mut App, code| {
+            handle_key_event(app, KeyEvent::new(code, KeyModifiers::NONE));
+        };
+        press(&mut app, KeyCode::Char('R'));
+        assert!(app.reverse_order);
+        press(&mut app, KeyCode::Down);
+        assert!(!app.auto_scroll);
+        assert_eq!(app.current_line().unwrap().line, "two");
+        press(&mut app, KeyCode::Char('G'));
+        assert_eq!(app.current_line().unwrap().line, "one");
+        press(&mut app, KeyCode::Char('g'));
+        assert!(app.auto_scroll);
+        assert_eq!(app.current_line().unwrap().line, "three");
+    }
+
+    #[test]
+    fn test_relative_timestamps() {
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let ms = chrono::Duration::milliseconds;
+        assert_eq!(utils::format_relative_time(ms(83_456)), "+00:01:23.456");
+        assert_eq!(utils::format_relative_time(ms(-5_000)), "-00:00:05.000");
+        assert_eq!(utils::format_relative_time(ms(90_000_001)), "+25:00:00.001");
+
+        let start = chrono::Utc::now();
+        let mut before = log_line("web", "app", "history");
+        before.timestamp = start - ms(1_500);
+        let mut after = log_line("web", "app", "repro");
+        after.timestamp = start + ms(62_250);
+        let logs = [before, after].map(LogEntry::new);
+        let area = Rect::new(0, 0, 30, 2);
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            logs.iter().collect(),
+            LogViewOptions {
+                show_timestamps: true,
+                timestamp_style: TimestampStyle::Relative,
+                start_time: start,
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row = |y| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        // Lines from before the session (e.g. --tail history) count down
+        assert_eq!(row(0), "-00:00:01.500 history");
+        assert_eq!(row(1), "+00:01:02.250 repro");
+    }
+
+    #[test]
+    fn test_parse_json_line() {
+        use crate::types::LogLevel;
+        use crate::ui::widgets::{LogView, LogViewOptions};
+        use crate::utils::{JsonLogFormat, parse_json_line};
+        use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+        let line = r#"{"ts":"2024-06-01T12:00:00Z","level":"warn","msg":"slow query","ms":812,"db":"orders"}"#;
+        let entry = parse_json_line(line, None).unwrap();
+        assert_eq!(entry.time.as_deref(), Some("2024-06-01T12:00:00Z"));
+        assert_eq!(entry.severity(), Some(LogLevel::Warn));
+        assert_eq!(entry.message, "slow query");
+        assert_eq!(entry.fields, vec!["db=orders", "ms=812"]);
+
+        // A custom message key; the default keys become ordinary fields
+        let entry = parse_json_line(r#"{"event":"started","msg":"x"}"#, Some("event")).unwrap();
+        assert_eq!(entry.message, "started");
+        assert_eq!(entry.fields, vec!["msg=x"]);
+
+        // Anything but a JSON object is shown as is
+        assert_eq!(parse_json_line("plain text", None), None);
+        assert_eq!(parse_json_line("[1, 2]", None), None);
+        assert_eq!(parse_json_line("{not json", None), None);
+
+        let logs = [log_line("web", "app", line)].map(LogEntry::new);
+        let json = JsonLogFormat::default();
+        let area = Rect::new(0, 0, 60, 1);
+        let mut buf = Buffer::empty(area);
+        LogView::new(
+            logs.iter().collect(),
+            LogViewOptions {
+                json_logs: Some(&json),
+                ..Default::default()
+            },
+        )
+        .render(area, &mut buf);
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert!(row.starts_with("2024-06-01T12:00:00Z WARN slow query db=orders"));
+        assert_eq!(buf[(21, 0)].fg, Color::Yellow);
+    }
 }
@@ -1,3 +1,93 @@
+use ratatui::style::Color;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// One container of a pod in a cluster: what a log stream is tailed for
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct PodKey {
+    pub cluster: String,
+    pub namespace: String,
+    pub pod_name: String,
+    pub container_name: String,
+}
+
+/// Severity of a log line, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" | "notice" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "err" => Some(Self::Error),
+            "fatal" | "critical" | "crit" | "panic" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+            Self::Fatal => "fatal",
+        }
+    }
+
+    /// Color of the level in the TUI
+    pub fn color(self) -> Color {
+        match self {
+            Self::Trace | Self::Debug => Color::DarkGray,
+            Self::Info => Color::Green,
+            Self::Warn => Color::Yellow,
+            Self::Error => Color::Red,
+            Self::Fatal => Color::LightMagenta,
+        }
+    }
+
+    /// Color of a whole line of this level in the log view; info lines keep
+    /// the default so that only what stands out is colored
+    pub fn line_color(self) -> Option<Color> {
+        match self {
+            Self::Trace | Self::Debug => Some(Color::DarkGray),
+            Self::Info => None,
+            Self::Warn => Some(Color::Yellow),
+            Self::Error => Some(Color::Red),
+            Self::Fatal => Some(Color::LightMagenta),
+        }
+    }
+
+    /// Color of the level in stdout mode
+    pub fn crossterm_color(self) -> crossterm::style::Color {
+        match self {
+            Self::Trace | Self::Debug => crossterm::style::Color::DarkGrey,
+            Self::Info => crossterm::style::Color::Green,
+            Self::Warn => crossterm::style::Color::Yellow,
+            Self::Error => crossterm::style::Color::Red,
+            Self::Fatal => crossterm::style::Color::AnsiValue(95), // Bright Magenta
+        }
+    }
+}
+
+/// A single log line from one container.
+///
+/// Lines of a given container are delivered (to stdout and to the TUI buffer) in
+/// the order they were emitted; no ordering is implied between containers.
 #[derive(Debug, Clone)]
 pub struct LogMessage {
     pub cluster: String,
@@ -5,13 +95,439 @@ pub struct LogMessage {
     pub pod_name: String,
     pub container_name: String,
     pub line: String,
+    /// When the container wrote the line, per the kubelet's timestamp; the
+    /// local receive time for lines without one
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Kind of a status line inserted into a container's log. Repeats of the same
+/// kind in quick succession are coalesced into one line with a count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerKind {
+    StreamClosed,
+    /// The pod was deleted and a new one came up under the same name
+    PodRecreated,
+    /// A note typed into the TUI; belongs to no pod
+    Note,
+}
+
+impl MarkerKind {
+    /// The line put in a container's log when a pod comes back under its name
+    pub const POD_RECREATED: &str = "[pod re-created]";
+
+    /// The line that ends the log of a container that finished for good
+    pub fn stream_closed(reason: impl std::fmt::Display) -> String {
+        format!("[stream closed: {}]", reason)
+    }
+
+    /// Kind of a line kubectl-tail wrote into a container's log, told by its
+    /// text. A container printing the same text is taken for one, which only
+    /// means its repeats are counted too.
+    pub fn of(line: &str) -> Option<Self> {
+        if line == Self::POD_RECREATED {
+            Some(Self::PodRecreated)
+        } else if line.starts_with("[stream closed: ") && line.ends_with(']') {
+            Some(Self::StreamClosed)
+        } else {
+            None
+        }
+    }
+}
+
+impl LogMessage {
+    /// The line as one NDJSON record for --output json, with its --seq number
+    pub fn to_json(&self, seq: Option<u64>) -> String {
+        let mut record = serde_json::json!({
+            "cluster": self.cluster,
+            "namespace": self.namespace,
+            "pod_name": self.pod_name,
+            "container_name": self.container_name,
+            "timestamp": self.timestamp.to_rfc3339(),
+            "line": self.line,
+        });
+        if let Some(seq) = seq {
+            record["seq"] = seq.into();
+        }
+        record.to_string()
+    }
+
+    /// Order for the sort-by-time view: by timestamp, then pod name, then container
+    /// name. Sorting with a stable sort keeps arrival order as the final tiebreak,
+    /// so lines with equal timestamps always come out in the same order.
+    pub fn time_order(&self, other: &Self) -> std::cmp::Ordering {
+        self.timestamp
+            .cmp(&other.timestamp)
+            .then_with(|| self.pod_name.cmp(&other.pod_name))
+            .then_with(|| self.container_name.cmp(&other.container_name))
+    }
+
+    /// The container the line came from
+    pub fn pod_key(&self) -> PodKey {
+        PodKey {
+            cluster: self.cluster.clone(),
+            namespace: self.namespace.clone(),
+            pod_name: self.pod_name.clone(),
+            container_name: self.container_name.clone(),
+        }
+    }
+}
+
+/// Per-container numbering of emitted lines for --seq: 1, 2, 3, … for each
+/// container, kept across reconnects, so a gap downstream means a lost line
+#[derive(Debug, Default)]
+pub struct LineSequence {
+    last: HashMap<PodKey, u64>,
+}
+
+impl LineSequence {
+    pub fn next(&mut self, msg: &LogMessage) -> u64 {
+        let seq = self.last.entry(msg.pod_key()).or_default();
+        *seq += 1;
+        *seq
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ResourceSpec {
     pub context: Option<String>,
     pub namespace: Option<String>,
     pub kind: Option<String>,
     pub name: String,
+    /// Container to tail in this resource's pods (`name:container`), instead of -c
+    pub container: Option<String>,
+}
+
+/// How many sends must find the log channel full before suggesting a bigger buffer
+const SATURATION_HINT_THRESHOLD: usize = 100;
+
+/// Occupancy statistics for the log channel, shared by every tail task
+#[derive(Debug, Default)]
+pub struct ChannelStats {
+    high_water: AtomicUsize,
+    full_sends: AtomicUsize,
+    hinted: AtomicBool,
+    /// Lines cut down to --max-line-bytes before entering the channel
+    truncated: AtomicUsize,
+}
+
+impl ChannelStats {
+    /// Record the channel depth just before a send. Returns true exactly once: the
+    /// first time the channel has been found full often enough to warrant a hint.
+    pub fn observe<T>(&self, tx: &mpsc::Sender<T>) -> bool {
+        let depth = tx.max_capacity() - tx.capacity();
+        self.high_water.fetch_max(depth, Ordering::Relaxed);
+        if tx.capacity() > 0 {
+            return false;
+        }
+        let full = self.full_sends.fetch_add(1, Ordering::Relaxed) + 1;
+        full >= SATURATION_HINT_THRESHOLD && !self.hinted.swap(true, Ordering::Relaxed)
+    }
+
+    /// Deepest the channel has been since startup
+    pub fn high_water(&self) -> usize {
+        self.high_water.load(Ordering::Relaxed)
+    }
+
+    /// Whether the channel has been saturating often enough to warrant a hint
+    pub fn is_saturating(&self) -> bool {
+        self.full_sends.load(Ordering::Relaxed) >= SATURATION_HINT_THRESHOLD
+    }
+
+    pub fn record_truncated(&self) {
+        self.truncated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// How many lines were truncated to --max-line-bytes since startup
+    pub fn truncated(&self) -> usize {
+        self.truncated.load(Ordering::Relaxed)
+    }
+}
+
+/// Hint shown when the log channel keeps filling up
+pub const SATURATION_HINT: &str =
+    "log channel saturating — consider increasing --buffer-size (consumer is falling behind)";
+
+/// Connection state of one container's log stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    /// Waiting to retry after the stream ended, or failed with the given
+    /// status (e.g. `500 InternalError`)
+    Reconnecting(Option<String>),
+    /// The tail task gave up; carries the status of the last error
+    Failed(String),
+}
+
+/// How many entries the debug timeline keeps
+const MAX_TIMELINE_ENTRIES: usize = 1000;
+
+/// One step of pod discovery or of a tail task's lifecycle
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// `cluster/namespace/pod`, or `cluster/namespace` for watcher-wide events
+    pub target: String,
+    pub container: Option<String>,
+    pub event: String,
+}
+
+/// Recent watcher events and tail task transitions, oldest first, for the
+/// debug timeline overlay
+#[derive(Debug, Default)]
+pub struct Timeline {
+    entries: Mutex<VecDeque<TimelineEntry>>,
+}
+
+impl Timeline {
+    pub fn record(&self, target: String, container: Option<&str>, event: impl Into<String>) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_TIMELINE_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(TimelineEntry {
+            at: chrono::Utc::now(),
+            target,
+            container: container.map(str::to_string),
+            event: event.into(),
+        });
+    }
+
+    /// Entries of one pod (`cluster/namespace/pod`) along with the events of
+    /// the watchers of its namespace, or every entry
+    pub fn snapshot(&self, pod: Option<&str>) -> Vec<TimelineEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| {
+                pod.is_none_or(|pod| {
+                    e.target == pod
+                        || pod
+                            .strip_prefix(e.target.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// How soon a pod must come back under the same name to count as re-created
+const RECREATE_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+fn pod_path(key: &PodKey) -> String {
+    format!("{}/{}/{}", key.cluster, key.namespace, key.pod_name)
+}
+
+/// Connection state of every tail task, keyed by container
+#[derive(Debug, Default)]
+pub struct StreamStates {
+    states: Mutex<HashMap<PodKey, ConnectionState>>,
+    reconnects: Mutex<HashMap<PodKey, usize>>,
+    /// The last error each stream hit, kept after it reconnects
+    last_errors: Mutex<HashMap<PodKey, String>>,
+    /// Containers of deleted pods still being read during --delete-grace
+    draining: Mutex<HashSet<PodKey>>,
+    /// Pods (`cluster/namespace/pod`) deleted within the last RECREATE_WINDOW
+    deleted: Mutex<HashMap<String, Instant>>,
+    /// Bytes read from each container's stream, across reconnects
+    received: Mutex<HashMap<PodKey, Arc<AtomicU64>>>,
+    /// Bytes of each stream as they came over the wire, with --gzip
+    wire: Mutex<HashMap<PodKey, Arc<AtomicU64>>>,
+    /// Every state change, plus the watcher events recorded alongside them
+    pub timeline: Timeline,
+}
+
+impl StreamStates {
+    pub fn set(&self, key: &PodKey, state: ConnectionState) {
+        let event = match &state {
+            ConnectionState::Connecting => "tail task spawned, connecting".to_string(),
+            ConnectionState::Connected => "stream connected".to_string(),
+            ConnectionState::Reconnecting(None) => "stream ended, reconnecting".to_string(),
+            ConnectionState::Reconnecting(Some(status)) => {
+                format!("stream failed ({}), reconnecting", status)
+            }
+            ConnectionState::Failed(status) => format!("tail task gave up: {}", status),
+        };
+        self.timeline
+            .record(pod_path(key), Some(&key.container_name), event);
+        if let ConnectionState::Reconnecting(Some(status)) | ConnectionState::Failed(status) =
+            &state
+        {
+            self.last_errors
+                .lock()
+                .unwrap()
+                .insert(key.clone(), status.clone());
+        }
+        if matches!(state, ConnectionState::Reconnecting(_)) {
+            *self
+                .reconnects
+                .lock()
+                .unwrap()
+                .entry(key.clone())
+                .or_default() += 1;
+        }
+        self.states.lock().unwrap().insert(key.clone(), state);
+    }
+
+    /// Every container's state and how often its stream had to reconnect,
+    /// ordered by container
+    pub fn snapshot(&self) -> Vec<(PodKey, ConnectionState, usize)> {
+        let states = self.states.lock().unwrap();
+        let reconnects = self.reconnects.lock().unwrap();
+        let mut snapshot: Vec<_> = states
+            .iter()
+            .map(|(key, state)| {
+                let count = reconnects.get(key).copied().unwrap_or_default();
+                (key.clone(), state.clone(), count)
+            })
+            .collect();
+        snapshot.sort_by(|a, b| {
+            (
+                &a.0.cluster,
+                &a.0.namespace,
+                &a.0.pod_name,
+                &a.0.container_name,
+            )
+                .cmp(&(
+                    &b.0.cluster,
+                    &b.0.namespace,
+                    &b.0.pod_name,
+                    &b.0.container_name,
+                ))
+        });
+        snapshot
+    }
+
+    /// The counter a tail task adds the bytes it reads to
+    pub fn received_counter(&self, key: &PodKey) -> Arc<AtomicU64> {
+        self.received
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// The counter a --gzip tail task adds the (compressed) bytes it receives to
+    pub fn wire_counter(&self, key: &PodKey) -> Arc<AtomicU64> {
+        self.wire
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Bytes received over the wire by every stream since startup; None unless
+    /// streams are fetched with --gzip
+    pub fn wire_total(&self) -> Option<u64> {
+        let wire = self.wire.lock().unwrap();
+        (!wire.is_empty()).then(|| {
+            wire.values()
+                .map(|counter| counter.load(Ordering::Relaxed))
+                .sum()
+        })
+    }
+
+    /// Bytes read from a container's stream since startup
+    pub fn received(&self, key: &PodKey) -> u64 {
+        self.received
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0, |counter| counter.load(Ordering::Relaxed))
+    }
+
+    /// Bytes read from every stream since startup, and how many streams
+    pub fn received_total(&self) -> (u64, usize) {
+        let received = self.received.lock().unwrap();
+        let total = received
+            .values()
+            .map(|counter| counter.load(Ordering::Relaxed))
+            .sum();
+        (total, received.len())
+    }
+
+    /// The last error a container's stream failed with, even if it has since
+    /// reconnected
+    pub fn last_error(&self, key: &PodKey) -> Option<String> {
+        self.last_errors.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn get(&self, key: &PodKey) -> Option<ConnectionState> {
+        self.states.lock().unwrap().get(key).cloned()
+    }
+
+    /// Tail tasks still running: connecting, connected or reconnecting
+    pub fn active(&self) -> usize {
+        self.states
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|state| !matches!(state, ConnectionState::Failed(_)))
+            .count()
+    }
+
+    /// Forget every container of a pod
+    pub fn remove_pod(&self, key: &PodKey) {
+        self.timeline.record(pod_path(key), None, "stopped tailing");
+        self.states.lock().unwrap().retain(|k, _| {
+            !(k.cluster == key.cluster
+                && k.namespace == key.namespace
+                && k.pod_name == key.pod_name)
+        });
+    }
+
+    pub fn remove(&self, key: &PodKey) {
+        if self.states.lock().unwrap().remove(key).is_some() {
+            self.timeline.record(
+                pod_path(key),
+                Some(&key.container_name),
+                "tail task stopped",
+            );
+        }
+        self.draining.lock().unwrap().remove(key);
+    }
+
+    /// Mark a container of a deleted pod: its stream is read until it ends or
+    /// the grace period runs out, but never reconnected
+    pub fn set_draining(&self, key: &PodKey) {
+        self.timeline.record(
+            pod_path(key),
+            Some(&key.container_name),
+            "pod deleted, draining stream",
+        );
+        self.draining.lock().unwrap().insert(key.clone());
+    }
+
+    pub fn is_draining(&self, key: &PodKey) -> bool {
+        self.draining.lock().unwrap().contains(key)
+    }
+
+    /// Remember that a pod was deleted, so a new pod under its name is
+    /// recognized as a re-creation
+    pub fn pod_deleted(&self, key: &PodKey) {
+        let mut deleted = self.deleted.lock().unwrap();
+        deleted.retain(|_, at| at.elapsed() < RECREATE_WINDOW);
+        deleted.insert(pod_path(key), Instant::now());
+    }
+
+    /// Whether a pod about to be tailed replaces one of the same name deleted
+    /// shortly before. Answers true only once per deletion.
+    pub fn take_recreated(&self, key: &PodKey) -> bool {
+        let path = pod_path(key);
+        let recreated = self
+            .deleted
+            .lock()
+            .unwrap()
+            .remove(&path)
+            .is_some_and(|at| at.elapsed() < RECREATE_WINDOW);
+        if recreated {
+            self.timeline
+                .record(path, None, "pod re-created under the same name");
+        }
+        recreated
+    }
 }
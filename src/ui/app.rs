@@ -1,30 +1,199 @@
-use crate::types::LogMessage;
+use crate::config::{AccessLogSchema, HighlightRule};
+use crate::types::{
+    ChannelStats, ConnectionState, LogLevel, LogMessage, MarkerKind, SATURATION_HINT, StreamStates,
+    TimelineEntry,
+};
+use crate::ui::events::PodWarningEvent;
+use crate::utils::{
+    AutoScrollResume, JsonLogFormat, Notifier, PrefixFormat, detect_level, elided_frames_line,
+    env_tag, extract_trace_id, fill_open_cmd, fuzzy_match, parse_level, shell_quote,
+};
 use ratatui::widgets::ListState;
 use regex::Regex;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
-pub struct PodKey {
-    pub cluster: String,
-    pub namespace: String,
-    pub pod_name: String,
-    pub container_name: String,
-}
+pub use crate::types::PodKey;
+
+/// How long transient status-bar messages stay visible
+const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(5);
+
+/// How long the status bar flashes on a --notify match
+const FLASH_DURATION: Duration = Duration::from_millis(600);
+
+/// Column `w` wraps at when --wrap-width isn't given
+const DEFAULT_WRAP_WIDTH: u16 = 120;
+
+/// Share of the log area the detail pane (v) takes when first opened, and the
+/// bounds and step `<`/`>` resize it by, in percent
+const DEFAULT_DETAIL_WIDTH: u16 = 40;
+const MIN_DETAIL_WIDTH: u16 = 15;
+const MAX_DETAIL_WIDTH: u16 = 85;
+const DETAIL_WIDTH_STEP: u16 = 5;
 
 #[derive(Debug, Clone)]
 pub struct PodInfo {
     pub key: PodKey,
     pub phase: String,
-    #[allow(dead_code)]
-    pub age: String,
-    #[allow(dead_code)]
+    /// Creation time; the sidebar shows the age computed from it at render time
+    pub created: Option<chrono::DateTime<chrono::Utc>>,
     pub restarts: i32,
+    pub image: Option<String>,
+    /// Known but not tailed because it is not among the --newest pods
+    pub skipped: bool,
+    /// Owning workload (e.g. `deployment/web`) when grouping by workload
+    pub workload: Option<String>,
+    /// QoS class from the pod status (Guaranteed, Burstable, BestEffort)
+    pub qos_class: Option<String>,
+    /// Scheduling priority from the pod spec
+    pub priority: Option<i32>,
+    /// Node the pod is scheduled on
+    pub node: Option<String>,
+    /// Topology zone of the node, when its labels could be read
+    pub zone: Option<String>,
+    /// CPU/memory requests and limits from the container spec
+    pub resources: Option<String>,
+    /// Whether this is a regular, init (--include-init) or ephemeral
+    /// (--include-ephemeral) container
+    pub kind: ContainerKind,
+}
+
+/// Which list of the pod spec a container comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContainerKind {
+    #[default]
+    Regular,
+    Init,
+    Ephemeral,
+}
+
+impl ContainerKind {
+    /// Label shown after the container's name in the sidebar
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            ContainerKind::Regular => None,
+            ContainerKind::Init => Some("(init)"),
+            ContainerKind::Ephemeral => Some("(ephemeral)"),
+        }
+    }
+}
+
+/// How shown timestamps are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampStyle {
+    /// Time of day, e.g. `12:00:01.100`
+    #[default]
+    Full,
+    /// Only `.mmm` for a line in the same second as the line above it
+    Compact,
+    /// Time since the session started, e.g. `+00:01:23.456`
+    Relative,
+}
+
+/// Error-level lines of one node, for the hotspot view
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hotspot {
+    pub cluster: String,
+    pub node: Option<String>,
+    pub zone: Option<String>,
+    pub errors: usize,
+    /// Distinct pods the errors came from
+    pub pods: usize,
 }
 
+/// Number of phase transitions remembered per container
+const MAX_PHASE_HISTORY: usize = 16;
+
+/// A marker repeating the container's previous line within this long is
+/// counted on that line instead of being added
+const MARKER_WINDOW: chrono::Duration = chrono::Duration::minutes(5);
+
+/// How far back from the newest line to look for a container's previous line
+const MARKER_LOOKBACK: usize = 500;
+
 pub struct PodState {
     pub enabled: bool,
     #[allow(dead_code)]
     pub last_seen: chrono::DateTime<chrono::Utc>,
+    /// Phases this pod has been observed in, oldest first, with the time each started
+    pub phase_history: VecDeque<(chrono::DateTime<chrono::Utc>, String)>,
+    /// Recent lines per second, kept with --sparklines
+    pub activity: Activity,
+}
+
+/// Seconds of history in a --sparklines sparkline
+pub const SPARKLINE_SECONDS: usize = 20;
+
+/// Lines per second of one container over the last `SPARKLINE_SECONDS`
+#[derive(Debug, Clone, Default)]
+pub struct Activity {
+    /// Oldest first; the last count is the current second
+    counts: VecDeque<u32>,
+    /// Unix time of the current second
+    second: i64,
+}
+
+impl Activity {
+    /// Move the window up to `now`, zeroing the seconds without lines
+    pub fn advance(&mut self, now: i64) {
+        if self.counts.is_empty() {
+            self.counts = VecDeque::from(vec![0; SPARKLINE_SECONDS]);
+            self.second = now;
+            return;
+        }
+        let elapsed = (now - self.second).clamp(0, SPARKLINE_SECONDS as i64);
+        for _ in 0..elapsed {
+            self.counts.pop_front();
+            self.counts.push_back(0);
+        }
+        self.second = self.second.max(now);
+    }
+
+    /// Count a line received at `now`
+    pub fn record(&mut self, now: i64) {
+        self.advance(now);
+        if let Some(current) = self.counts.back_mut() {
+            *current += 1;
+        }
+    }
+
+    /// One block character per second, scaled to the busiest second; quiet
+    /// seconds are blank
+    pub fn sparkline(&self) -> String {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        self.counts
+            .iter()
+            .map(|&count| match count {
+                0 => ' ',
+                _ => BARS[((count as usize * BARS.len() - 1) / max as usize).min(BARS.len() - 1)],
+            })
+            .collect()
+    }
+}
+
+/// How long a Warning event keeps counting towards a pod's sidebar badge
+pub const WARNING_WINDOW: chrono::Duration = chrono::Duration::minutes(10);
+
+/// Recent Warning events of one pod, keyed by Event name
+#[derive(Debug, Default)]
+pub struct PodWarnings {
+    events: HashMap<String, (chrono::DateTime<chrono::Utc>, String)>,
+}
+
+impl PodWarnings {
+    /// Drop events last seen before `cutoff`
+    fn prune(&mut self, cutoff: chrono::DateTime<chrono::Utc>) {
+        self.events.retain(|_, (seen, _)| *seen >= cutoff);
+    }
+
+    /// Sidebar badge: count of recent warnings and the latest reason, e.g. `⚠2 BackOff`
+    pub fn badge(&self) -> Option<String> {
+        let (_, reason) = self.events.values().max_by_key(|(seen, _)| *seen)?;
+        Some(format!("⚠{} {}", self.events.len(), reason))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -33,19 +202,627 @@ pub enum AppMode {
     Search,
     Filter,
     Help,
+    LineDetail,
+    /// Final lines of the deleted pod selected in the sidebar (--tombstones)
+    Tombstone,
+    PhaseHistory,
+    DebugTimeline,
+    Hotspots,
+    ContainerPicker,
+    Command,
+    /// Typing a note to insert into the stream
+    Note,
+}
+
+/// A command typed after `:`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Show only lines matching the regex; none clears the filter
+    Filter(String),
+    /// Highlight matches, navigated with n/N
+    Search(String),
+    Clear,
+    /// Write the lines in view to a file
+    Save(std::path::PathBuf),
+    /// Show one cluster, or all of them
+    Context(Option<String>),
+    /// Scroll to the n-th newest line in view
+    Tail(usize),
+    /// Hide lines below a level, or turn the level filter off
+    Level(Option<LogLevel>),
+    /// Show one environment, or all of them
+    Env(Option<String>),
+    Quit,
+}
+
+/// Parse a command line typed after `:`, e.g. `filter timeout|refused`
+pub fn parse_command(input: &str) -> Result<Command, String> {
+    let input = input.trim();
+    let (name, arg) = match input.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (input, ""),
+    };
+    let required = |what: &str| {
+        if arg.is_empty() {
+            Err(format!(":{} needs {}", name, what))
+        } else {
+            Ok(arg.to_string())
+        }
+    };
+    match name {
+        "filter" | "f" => Ok(Command::Filter(arg.to_string())),
+        "grep" | "search" => Ok(Command::Search(arg.to_string())),
+        "clear" => Ok(Command::Clear),
+        "save" | "w" => required("a file path").map(|path| Command::Save(path.into())),
+        "context" | "ctx" => Ok(Command::Context((!arg.is_empty()).then(|| arg.to_string()))),
+        "tail" => required("a line count")?
+            .parse()
+            .map(Command::Tail)
+            .map_err(|_| format!("Not a line count: {}", arg)),
+        "level" if arg.is_empty() || arg == "off" => Ok(Command::Level(None)),
+        "level" => parse_level(arg).map(|level| Command::Level(Some(level))),
+        "env" => Ok(Command::Env((!arg.is_empty()).then(|| arg.to_string()))),
+        "q" | "quit" => Ok(Command::Quit),
+        "" => Err("Type a command, e.g. :filter <regex>".to_string()),
+        other => Err(format!("Unknown command: {}", other)),
+    }
+}
+
+/// Scroll position of the help and line detail overlays. `page` and `max` are
+/// refreshed on every render, so keys can page by what is actually visible.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OverlayScroll {
+    pub offset: usize,
+    /// Content rows visible at the last render
+    pub page: usize,
+    /// Largest offset that still fills the overlay
+    pub max: usize,
+}
+
+impl OverlayScroll {
+    pub fn up(&mut self, rows: usize) {
+        self.offset = self.offset.saturating_sub(rows);
+    }
+
+    pub fn down(&mut self, rows: usize) {
+        self.offset = (self.offset + rows).min(self.max);
+    }
+
+    pub fn top(&mut self) {
+        self.offset = 0;
+    }
+
+    pub fn bottom(&mut self) {
+        self.offset = self.max;
+    }
+}
+
+/// Popup listing the containers of one pod for bulk enabling/disabling
+#[derive(Debug, Clone)]
+pub struct ContainerPicker {
+    /// The pod (its `container_name` is unused)
+    pub pod: PodKey,
+    /// Fuzzy filter typed into the popup
+    pub filter: String,
+    /// Highlighted row among the matching containers
+    pub selected: usize,
+}
+
+/// Sidebar tree: cluster -> namespace -> (workload, pod) -> containers. The
+/// workload is empty unless grouping by workload; bare pods sort first.
+pub type PodTree<'a> = SlotTree<&'a PodInfo>;
+
+/// Sidebar tree holding containers as any `T`, e.g. `SidebarPods` positions
+type SlotTree<T> = BTreeMap<String, BTreeMap<String, BTreeMap<PodSlot, Vec<T>>>>;
+
+/// Position of a pod within its namespace: by workload, then pod name, unless
+/// the sidebar is frozen and the ranks pin the rows in place
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PodSlot {
+    pub workload_rank: usize,
+    /// Owning workload, empty for bare pods
+    pub workload: String,
+    pub pod_rank: usize,
+    pub pod_name: String,
+}
+
+/// Sidebar order pinned with `F`: workloads and pods keep the rows they had
+/// when the sidebar was frozen, new ones are appended below them, and deleted
+/// pods stay (greyed out) until the sidebar is unfrozen.
+#[derive(Debug, Default, Clone)]
+pub struct FrozenOrder {
+    workload_ranks: HashMap<String, usize>,
+    pod_ranks: HashMap<String, usize>,
+    /// Containers of pods deleted while frozen
+    pub stale: Vec<PodInfo>,
+}
+
+impl FrozenOrder {
+    /// Pin the current (sorted) order of `pods`
+    pub fn new(pods: &[PodInfo]) -> Self {
+        let mut order = Self::default();
+        for (cluster, namespaces) in pod_tree(pods, None, &[]) {
+            for (namespace, slots) in namespaces {
+                for slot in slots.keys() {
+                    order.place(&cluster, &namespace, &slot.workload, &slot.pod_name);
+                }
+            }
+        }
+        order
+    }
+
+    /// Give a pod (and its workload) a rank below everything placed so far,
+    /// unless it already has one
+    fn place(&mut self, cluster: &str, namespace: &str, workload: &str, pod_name: &str) {
+        let next = self.workload_ranks.len();
+        self.workload_ranks
+            .entry(format!("{}/{}/{}", cluster, namespace, workload))
+            .or_insert(next);
+        let next = self.pod_ranks.len();
+        self.pod_ranks
+            .entry(format!("{}/{}/{}", cluster, namespace, pod_name))
+            .or_insert(next);
+    }
+
+    pub fn add(&mut self, info: &PodInfo) {
+        // A pod that comes back (e.g. a StatefulSet replica) is live again
+        self.stale.retain(|p| p.key != info.key);
+        self.place(
+            &info.key.cluster,
+            &info.key.namespace,
+            info.workload.as_deref().unwrap_or_default(),
+            &info.key.pod_name,
+        );
+    }
+
+    fn slot(&self, info: &PodInfo) -> PodSlot {
+        let workload = info.workload.clone().unwrap_or_default();
+        let rank = |ranks: &HashMap<String, usize>, name: &str| {
+            ranks
+                .get(&format!(
+                    "{}/{}/{}",
+                    info.key.cluster, info.key.namespace, name
+                ))
+                .copied()
+                .unwrap_or(usize::MAX)
+        };
+        PodSlot {
+            workload_rank: rank(&self.workload_ranks, &workload),
+            pod_rank: rank(&self.pod_ranks, &info.key.pod_name),
+            workload,
+            pod_name: info.key.pod_name.clone(),
+        }
+    }
+}
+
+/// A line in the TUI buffer: the message as it was received, and what the
+/// TUI works out about it
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub msg: LogMessage,
+    /// Level per `detect_level`; none for notes
+    pub level: Option<LogLevel>,
+    /// Set on lines kubectl-tail writes itself, e.g. `[stream closed: ...]`
+    pub marker: Option<MarkerKind>,
+    /// How many markers this line stands for, once repeats are coalesced
+    pub repeats: usize,
+    /// Read from the container's previous, terminated instance (--previous)
+    pub previous: bool,
+    /// Frames folded into this line per --elide-frames. Empty for ordinary lines;
+    /// otherwise `line` is the `... N frames elided ...` placeholder.
+    pub elided: Vec<String>,
+    /// Trace ID per --trace-field, extracted the first time it is asked for
+    trace_id: OnceLock<Option<String>>,
+}
+
+impl LogEntry {
+    pub fn new(msg: LogMessage) -> Self {
+        Self {
+            level: detect_level(&msg.line),
+            marker: MarkerKind::of(&msg.line),
+            repeats: 1,
+            previous: false,
+            msg,
+            elided: Vec::new(),
+            trace_id: OnceLock::new(),
+        }
+    }
+
+    /// A note typed by the user, placed in the stream at `timestamp`
+    pub fn note(text: String, timestamp: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            level: None,
+            marker: Some(MarkerKind::Note),
+            ..Self::new(LogMessage {
+                cluster: String::new(),
+                namespace: String::new(),
+                pod_name: String::new(),
+                container_name: String::new(),
+                line: text,
+                timestamp,
+            })
+        }
+    }
+
+    pub fn is_note(&self) -> bool {
+        self.marker == Some(MarkerKind::Note)
+    }
+
+    /// The line's trace ID per `field` (--trace-field)
+    pub fn trace_id(&self, field: Option<&Regex>) -> Option<&str> {
+        self.trace_id
+            .get_or_init(|| field.and_then(|regex| extract_trace_id(regex, &self.msg.line)))
+            .as_deref()
+    }
+}
+
+impl std::ops::Deref for LogEntry {
+    type Target = LogMessage;
+
+    fn deref(&self) -> &LogMessage {
+        &self.msg
+    }
+}
+
+/// A container of a deleted pod kept in the sidebar with --tombstones, along
+/// with its final lines, until the retention period runs out
+#[derive(Debug, Clone)]
+pub struct Tombstone {
+    pub info: PodInfo,
+    pub removed_at: chrono::DateTime<chrono::Utc>,
+    pub lines: Vec<LogEntry>,
+}
+
+/// The containers listed in the sidebar, addressed by position: live pods,
+/// then the stale pods of a frozen sidebar, then tombstones
+#[derive(Debug, Clone, Copy)]
+pub struct SidebarPods<'a> {
+    pods: &'a [PodInfo],
+    stale: &'a [PodInfo],
+    tombstones: &'a [Tombstone],
+}
+
+impl<'a> SidebarPods<'a> {
+    pub fn new(
+        pods: &'a [PodInfo],
+        frozen: Option<&'a FrozenOrder>,
+        tombstones: &'a [Tombstone],
+    ) -> Self {
+        Self {
+            pods,
+            stale: frozen.map(|f| f.stale.as_slice()).unwrap_or_default(),
+            tombstones,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a PodInfo> + use<'a> {
+        self.pods
+            .iter()
+            .chain(self.stale)
+            .chain(self.tombstones.iter().map(|t| &t.info))
+    }
+
+    fn get(&self, position: usize) -> &'a PodInfo {
+        if let Some(pod) = self.pods.get(position) {
+            return pod;
+        }
+        let position = position - self.pods.len();
+        match self.stale.get(position) {
+            Some(pod) => pod,
+            None => &self.tombstones[position - self.stale.len()].info,
+        }
+    }
+}
+
+/// Group pods into the sidebar tree, in `frozen` order (including its stale
+/// pods) if given, followed by the tombstones of deleted pods
+pub fn pod_tree<'a>(
+    pods: &'a [PodInfo],
+    frozen: Option<&'a FrozenOrder>,
+    tombstones: &'a [Tombstone],
+) -> PodTree<'a> {
+    let pods = SidebarPods::new(pods, frozen, tombstones);
+    slot_tree(pods.iter().map(|pod| (pod, pod)), frozen)
+}
+
+/// Group `(pod, container)` pairs by cluster, namespace and `PodSlot`
+fn slot_tree<'a, T>(
+    pods: impl Iterator<Item = (&'a PodInfo, T)>,
+    frozen: Option<&FrozenOrder>,
+) -> SlotTree<T> {
+    let mut tree = SlotTree::new();
+    for (pod, container) in pods {
+        let slot = match frozen {
+            Some(frozen) => frozen.slot(pod),
+            None => PodSlot {
+                workload_rank: 0,
+                workload: pod.workload.clone().unwrap_or_default(),
+                pod_rank: 0,
+                pod_name: pod.key.pod_name.clone(),
+            },
+        };
+        tree.entry(pod.key.cluster.clone())
+            .or_default()
+            .entry(pod.key.namespace.clone())
+            .or_default()
+            .entry(slot)
+            .or_default()
+            .push(container);
+    }
+    tree
+}
+
+/// The containers under a sidebar row, in sidebar order. Never empty.
+#[derive(Debug, Clone, Copy)]
+pub struct RowContainers<'a> {
+    pods: SidebarPods<'a>,
+    positions: &'a [usize],
+}
+
+impl<'a> RowContainers<'a> {
+    pub fn iter(&self) -> impl Iterator<Item = &'a PodInfo> + use<'a> {
+        let pods = self.pods;
+        self.positions.iter().map(move |&p| pods.get(p))
+    }
+
+    pub fn first(&self) -> &'a PodInfo {
+        self.pods.get(self.positions[0])
+    }
+}
+
+/// One visible row of the sidebar, borrowing from the `SidebarPods` it was
+/// laid out from. Only the rows on screen are built each frame.
+#[derive(Debug, Clone, Copy)]
+pub enum SidebarRow<'a> {
+    Cluster {
+        cluster: &'a str,
+        containers: RowContainers<'a>,
+        expanded: bool,
+    },
+    Namespace {
+        cluster: &'a str,
+        namespace: &'a str,
+        containers: RowContainers<'a>,
+        expanded: bool,
+    },
+    Workload {
+        cluster: &'a str,
+        namespace: &'a str,
+        workload: &'a str,
+        pod_count: usize,
+        expanded: bool,
+    },
+    Pod {
+        cluster: &'a str,
+        namespace: &'a str,
+        pod_name: &'a str,
+        containers: RowContainers<'a>,
+        /// Listed under a workload header
+        grouped: bool,
+        expanded: bool,
+    },
+    Container {
+        info: &'a PodInfo,
+        grouped: bool,
+    },
+}
+
+impl SidebarRow<'_> {
+    pub fn node_type(&self) -> TreeNodeType {
+        match *self {
+            SidebarRow::Cluster { cluster, .. } => TreeNodeType::Cluster(cluster.to_string()),
+            SidebarRow::Namespace {
+                cluster, namespace, ..
+            } => TreeNodeType::Namespace(cluster.to_string(), namespace.to_string()),
+            SidebarRow::Workload {
+                cluster,
+                namespace,
+                workload,
+                ..
+            } => TreeNodeType::Workload(
+                cluster.to_string(),
+                namespace.to_string(),
+                workload.to_string(),
+            ),
+            SidebarRow::Pod {
+                cluster,
+                namespace,
+                pod_name,
+                ..
+            } => TreeNodeType::Pod(
+                cluster.to_string(),
+                namespace.to_string(),
+                pod_name.to_string(),
+            ),
+            SidebarRow::Container { .. } => TreeNodeType::Container,
+        }
+    }
+
+    /// Container key of a leaf row; None for headers
+    pub fn key(&self) -> Option<PodKey> {
+        match self {
+            SidebarRow::Container { info, .. } => Some(info.key.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The sidebar flattened into rows given the expanded nodes. It is kept
+/// between frames and rebuilt only when the pods, tombstones, frozen order or
+/// expanded nodes change; rows refer to containers by `SidebarPods` position.
+#[derive(Debug, Clone, Default)]
+pub struct SidebarLayout {
+    /// Every container in sidebar order, collapsed or not
+    order: Vec<usize>,
+    rows: Vec<LayoutRow>,
+}
+
+#[derive(Debug, Clone)]
+struct LayoutRow {
+    kind: RowKind,
+    /// The row's containers, as a range of `order`
+    containers: std::ops::Range<usize>,
+    grouped: bool,
+    expanded: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RowKind {
+    Cluster,
+    Namespace,
+    Workload { pod_count: usize },
+    Pod,
+    Container,
+}
+
+impl SidebarLayout {
+    pub fn new(
+        pods: SidebarPods,
+        frozen: Option<&FrozenOrder>,
+        expanded: &HashSet<String>,
+    ) -> Self {
+        let tree = slot_tree(pods.iter().enumerate().map(|(i, pod)| (pod, i)), frozen);
+        let mut layout = Self::default();
+        for (cluster, namespaces) in &tree {
+            let cluster_row = layout.open(RowKind::Cluster, false, expanded.contains(cluster));
+            let cluster_expanded = layout.rows[cluster_row].expanded;
+            for (namespace, slots) in namespaces {
+                let ns_path = format!("{}/{}", cluster, namespace);
+                let ns_row = cluster_expanded
+                    .then(|| layout.open(RowKind::Namespace, false, expanded.contains(&ns_path)));
+                let ns_expanded = ns_row.is_some_and(|row| layout.rows[row].expanded);
+                // Header row of the workload being listed, if shown
+                let mut current_workload: Option<(&str, Option<usize>)> = None;
+                for (slot, containers) in slots {
+                    let (workload, pod_name) = (slot.workload.as_str(), &slot.pod_name);
+                    let grouped = !workload.is_empty();
+                    if current_workload.map(|(w, _)| w) != Some(workload) {
+                        if let Some((_, row)) = current_workload.take() {
+                            layout.close(row);
+                        }
+                        if grouped {
+                            let kind = RowKind::Workload {
+                                pod_count: slots.keys().filter(|s| s.workload == workload).count(),
+                            };
+                            let path = format!("{}/{}/{}", cluster, namespace, workload);
+                            let row = ns_expanded
+                                .then(|| layout.open(kind, false, expanded.contains(&path)));
+                            current_workload = Some((workload, row));
+                        }
+                    }
+                    let shown = ns_expanded
+                        && current_workload
+                            .is_none_or(|(_, row)| row.is_some_and(|r| layout.rows[r].expanded));
+
+                    let pod_path = format!("{}/{}/{}", cluster, namespace, pod_name);
+                    let pod_row = shown
+                        .then(|| layout.open(RowKind::Pod, grouped, expanded.contains(&pod_path)));
+                    let pod_expanded = pod_row.is_some_and(|row| layout.rows[row].expanded);
+                    for &position in containers {
+                        if pod_expanded {
+                            let row = layout.open(RowKind::Container, grouped, false);
+                            layout.order.push(position);
+                            layout.close(Some(row));
+                        } else {
+                            layout.order.push(position);
+                        }
+                    }
+                    layout.close(pod_row);
+                }
+                if let Some((_, row)) = current_workload {
+                    layout.close(row);
+                }
+                layout.close(ns_row);
+            }
+            layout.close(Some(cluster_row));
+        }
+        layout
+    }
+
+    /// Start a row whose containers are the ones pushed until it is closed
+    fn open(&mut self, kind: RowKind, grouped: bool, expanded: bool) -> usize {
+        let start = self.order.len();
+        self.rows.push(LayoutRow {
+            kind,
+            containers: start..start,
+            grouped,
+            expanded,
+        });
+        self.rows.len() - 1
+    }
+
+    fn close(&mut self, row: Option<usize>) {
+        if let Some(row) = row {
+            self.rows[row].containers.end = self.order.len();
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The rows in `range`, borrowing from the `pods` the layout was built from
+    pub fn rows<'a>(
+        &'a self,
+        pods: SidebarPods<'a>,
+        range: std::ops::Range<usize>,
+    ) -> impl Iterator<Item = SidebarRow<'a>> + use<'a> {
+        self.rows[range].iter().map(move |row| {
+            let containers = RowContainers {
+                pods,
+                positions: &self.order[row.containers.clone()],
+            };
+            let first = containers.first();
+            let (cluster, namespace) = (&first.key.cluster, &first.key.namespace);
+            let expanded = row.expanded;
+            match row.kind {
+                RowKind::Cluster => SidebarRow::Cluster {
+                    cluster,
+                    containers,
+                    expanded,
+                },
+                RowKind::Namespace => SidebarRow::Namespace {
+                    cluster,
+                    namespace,
+                    containers,
+                    expanded,
+                },
+                RowKind::Workload { pod_count } => SidebarRow::Workload {
+                    cluster,
+                    namespace,
+                    workload: first.workload.as_deref().unwrap_or_default(),
+                    pod_count,
+                    expanded,
+                },
+                RowKind::Pod => SidebarRow::Pod {
+                    cluster,
+                    namespace,
+                    pod_name: &first.key.pod_name,
+                    containers,
+                    grouped: row.grouped,
+                    expanded,
+                },
+                RowKind::Container => SidebarRow::Container {
+                    info: first,
+                    grouped: row.grouped,
+                },
+            }
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TreeNodeType {
     Cluster(String),
-    Namespace(String, String), // cluster, namespace
-    Pod(String, String, String), // cluster, namespace, pod
-    Container, // Leaf node
+    Namespace(String, String),        // cluster, namespace
+    Workload(String, String, String), // cluster, namespace, workload
+    Pod(String, String, String),      // cluster, namespace, pod
+    Container,                        // Leaf node
 }
 
 pub struct App {
     // Log buffer - ring buffer with configurable size
-    pub log_buffer: VecDeque<LogMessage>,
+    pub log_buffer: VecDeque<LogEntry>,
     pub max_buffer_size: usize,
 
     // Pod tracking
@@ -56,18 +833,29 @@ pub struct App {
     pub sidebar_visible: bool,
     pub sidebar_state: ListState,
     pub sidebar_item_keys: Vec<Option<PodKey>>, // Maps list index to container key (None for headers)
-    pub sidebar_item_types: Vec<TreeNodeType>, // Type of each item for collapse/expand
+    pub sidebar_item_types: Vec<TreeNodeType>,  // Type of each item for collapse/expand
+    pub sidebar_layout: SidebarLayout,
     pub expanded_nodes: std::collections::HashSet<String>, // Set of expanded node paths
     pub scroll_offset: usize,
     pub auto_scroll: bool,
+    pub auto_scroll_resume: AutoScrollResume,
+    /// Last scroll input that took the view off the bottom
+    pub last_scroll_input: Option<Instant>,
 
     // Search state (/ key - highlights and allows n/N navigation)
     pub search_pattern: String,
     pub search_matches: Vec<usize>,
+    /// Narrow the view to the search matches (& key)
+    pub results_only: bool,
     pub current_match_index: usize,
 
     // Filter state (f key - shows only matching lines)
     pub filter_pattern: String,
+    /// Command being typed in command mode, without the leading `:`
+    pub command_input: String,
+    pub note_input: String,
+    /// --exclude: lines matching it are never shown
+    pub exclude_pattern: Option<Regex>,
     pub active_filters: Vec<String>,
 
     // Status tracking
@@ -78,9 +866,124 @@ pub struct App {
     // UI mode
     pub mode: AppMode,
     pub help_visible: bool,
+    /// Scroll position of the open help or line detail overlay
+    pub overlay_scroll: OverlayScroll,
     pub paused: bool,
     pub show_timestamps: bool,
+    pub timestamp_style: TimestampStyle,
+    /// When the session started, for relative timestamps
+    pub start_time: chrono::DateTime<chrono::Utc>,
+    /// Show lines ordered by timestamp instead of arrival order
+    pub sort_by_time: bool,
+    /// Show the newest line at the top. The buffer and offsets stay
+    /// chronological; only rendering and the navigation keys are flipped
+    pub reverse_order: bool,
     pub show_prefix: bool,
+    /// --prefix-format: replaces the `[cluster.namespace/pod/container]` prefix
+    pub prefix_format: Option<PrefixFormat>,
+    /// Set with --parse json: JSON lines are shown by their fields
+    pub json_logs: Option<JsonLogFormat>,
+    pub colorize_prefix: bool,
+    /// Color lines by level: errors red, warnings yellow, debug dimmed
+    pub level_colors: bool,
+    pub show_image: bool,
+    /// Show pod QoS class and priority in the sidebar
+    pub show_qos: bool,
+    /// Show each container's recent lines per second in the sidebar
+    pub show_sparklines: bool,
+    pub max_line_width: Option<usize>,
+    /// Column lines wrap at when fixed wrapping is on
+    pub wrap_width: u16,
+    /// Wrap at `wrap_width` rather than the pane edge
+    pub fixed_wrap: bool,
+    pub detail_line: Option<LogEntry>,
+    /// Show the detail pane right of the logs (v)
+    pub detail_pane: bool,
+    /// Detail pane's share of the log area in percent, kept while it is hidden
+    pub detail_width: u16,
+    pub container_picker: Option<ContainerPicker>,
+    pub focused_cluster: Option<String>,
+    /// Clusters hidden from the view; their streams keep running and buffering
+    pub muted_clusters: std::collections::HashSet<String>,
+    /// Sidebar order pinned with F, while frozen
+    pub frozen_order: Option<FrozenOrder>,
+    /// How long deleted pods stay in the sidebar with their final lines
+    /// (--tombstones), and how many lines of each container are kept
+    pub tombstone_retention: Option<chrono::Duration>,
+    pub tombstone_lines: usize,
+    pub tombstones: Vec<Tombstone>,
+    /// Environment tag of each cluster, from the config file
+    pub environments: HashMap<String, String>,
+    /// Show only clusters of this environment (--env)
+    pub env_filter: Option<String>,
+
+    // Transient status-bar message and when it was set
+    pub status_message: Option<(String, Instant)>,
+
+    // Diagnostics
+    pub verbose: bool,
+    pub channel_stats: Option<Arc<ChannelStats>>,
+    /// Keyword highlight rules from the config file
+    pub highlights: Vec<HighlightRule>,
+    /// Access-log formats shown as columns; `None` unless --access-logs
+    pub access_logs: Option<Vec<AccessLogSchema>>,
+    /// Alerts for lines matching --notify
+    pub notifier: Option<Notifier>,
+    /// Extracts the trace ID of each line (--trace-field)
+    pub trace_field: Option<Regex>,
+    /// Trace whose lines are highlighted, chosen with T
+    pub trace: Option<String>,
+    /// Show only the lines of `trace`
+    pub trace_only: bool,
+    /// Hide lines below this level (--level, toggled with e, cycled with L)
+    pub min_level: Option<LogLevel>,
+    /// Level that e filters on
+    pub level_threshold: LogLevel,
+    /// Whether the level filter keeps lines without a detected level, such as
+    /// the stack frames of the errors it shows (toggled with U)
+    pub keep_unleveled: bool,
+    /// Stack frames to fold together per --elide-frames
+    pub elide_frames: Option<Regex>,
+    /// Lines are read from the containers' previous instances (--previous)
+    pub previous: bool,
+    /// A bell is due on the next frame
+    bell_pending: bool,
+    /// Something visible changed since the last frame
+    changed: bool,
+    /// Text to put on the clipboard on the next frame
+    clipboard_pending: Option<String>,
+    /// The session's --tail, for reproducing it with kubectl
+    pub tail_lines: Option<i64>,
+    /// --since in seconds, for the same
+    pub since_seconds: Option<i64>,
+    /// The status bar flashes until then
+    flash_until: Option<Instant>,
+    /// Recent Warning events per pod path (`cluster/namespace/pod`)
+    pub pod_warnings: HashMap<String, PodWarnings>,
+    /// Connection state of every tail task
+    pub stream_states: Option<Arc<StreamStates>>,
+    /// Asks the watcher layer to restart a container's tail task
+    pub respawn_tx: Option<mpsc::UnboundedSender<PodKey>>,
+    /// Start of the range to pipe, as an offset into the filtered view
+    pub mark: Option<usize>,
+    /// The --pipe-cmd, and where to send the lines to pipe to it
+    pub pipe_cmd: Option<String>,
+    pub pipe_tx: Option<mpsc::UnboundedSender<Vec<String>>>,
+    /// The --open-cmd template
+    pub open_cmd: Option<String>,
+    /// Filled-in --open-cmd to run with the TUI suspended
+    open_pending: Option<String>,
+    /// What is being watched, one line per context/namespace (for state dumps)
+    pub watch_summary: Vec<String>,
+    /// Watchers with no matching pods right now, e.g. scaled to zero
+    pub idle_watches: BTreeSet<String>,
+    /// Watchers being resumed after an error, by cluster
+    pub reconnecting_watches: HashMap<String, BTreeSet<String>>,
+    /// Set once every stream ended and no watcher can find more pods
+    pub nothing_left: bool,
+    saturation_hinted: bool,
+    /// Set when the sidebar layout is out of date; see `refresh_sidebar`
+    sidebar_dirty: bool,
 }
 
 impl App {
@@ -94,73 +997,285 @@ impl App {
             sidebar_state: ListState::default(),
             sidebar_item_keys: Vec::new(),
             sidebar_item_types: Vec::new(),
+            sidebar_layout: SidebarLayout::default(),
             expanded_nodes: std::collections::HashSet::new(),
             scroll_offset: 0,
             auto_scroll: true,
+            auto_scroll_resume: AutoScrollResume::Sticky,
+            last_scroll_input: None,
             search_pattern: String::new(),
             search_matches: Vec::new(),
+            results_only: false,
             current_match_index: 0,
             filter_pattern: String::new(),
+            command_input: String::new(),
+            note_input: String::new(),
+            exclude_pattern: None,
             active_filters: Vec::new(),
             running_pods: 0,
             total_pods: 0,
             memory_usage: 0,
             mode: AppMode::Normal,
             help_visible: false,
+            overlay_scroll: OverlayScroll::default(),
             paused: false,
             show_timestamps: false,
+            timestamp_style: TimestampStyle::Full,
+            start_time: chrono::Utc::now(),
+            sort_by_time: false,
+            reverse_order: false,
             show_prefix: true,
+            prefix_format: None,
+            json_logs: None,
+            colorize_prefix: true,
+            level_colors: true,
+            show_image: false,
+            show_qos: false,
+            show_sparklines: false,
+            max_line_width: None,
+            wrap_width: DEFAULT_WRAP_WIDTH,
+            fixed_wrap: false,
+            detail_line: None,
+            detail_pane: false,
+            detail_width: DEFAULT_DETAIL_WIDTH,
+            container_picker: None,
+            focused_cluster: None,
+            muted_clusters: std::collections::HashSet::new(),
+            frozen_order: None,
+            tombstone_retention: None,
+            tombstone_lines: 0,
+            tombstones: Vec::new(),
+            environments: HashMap::new(),
+            env_filter: None,
+            status_message: None,
+            verbose: false,
+            channel_stats: None,
+            highlights: Vec::new(),
+            access_logs: None,
+            notifier: None,
+            trace_field: None,
+            trace: None,
+            trace_only: false,
+            min_level: None,
+            level_threshold: LogLevel::Error,
+            keep_unleveled: true,
+            elide_frames: None,
+            previous: false,
+            bell_pending: false,
+            changed: false,
+            clipboard_pending: None,
+            tail_lines: None,
+            since_seconds: None,
+            flash_until: None,
+            pod_warnings: HashMap::new(),
+            stream_states: None,
+            respawn_tx: None,
+            mark: None,
+            pipe_cmd: None,
+            pipe_tx: None,
+            open_cmd: None,
+            open_pending: None,
+            watch_summary: Vec::new(),
+            idle_watches: BTreeSet::new(),
+            reconnecting_watches: HashMap::new(),
+            nothing_left: false,
+            saturation_hinted: false,
+            sidebar_dirty: true,
         }
     }
 
     pub fn add_log(&mut self, msg: LogMessage) {
-        if !self.paused {
-            // Enforce ring buffer size
-            while self.log_buffer.len() >= self.max_buffer_size {
-                self.log_buffer.pop_front();
-                // Adjust scroll offset if we removed from the front
-                if self.scroll_offset > 0 {
-                    self.scroll_offset = self.scroll_offset.saturating_sub(1);
-                }
+        // Activity is counted even while paused
+        if self.show_sparklines
+            && let Some(state) = self.pod_states.get_mut(&msg.pod_key())
+        {
+            state.activity.record(chrono::Utc::now().timestamp());
+        }
+
+        // Alert even while paused: the point is not having to watch
+        if let Some(notifier) = &mut self.notifier {
+            let now = Instant::now();
+            if notifier.check(&msg.line, now) {
+                self.bell_pending = true;
+                self.flash_until = Some(now + FLASH_DURATION);
+                self.set_status_message(format!("--notify matched in {}", msg.pod_name));
             }
+        }
 
-            self.log_buffer.push_back(msg);
+        if !self.paused {
+            if self.coalesce_marker(&msg) {
+                return;
+            }
+            self.make_room();
+            if let Some(msg) = self.fold_frame(msg) {
+                let mut entry = LogEntry::new(msg);
+                entry.previous = self.previous && entry.marker.is_none();
+                self.log_buffer.push_back(entry);
+            }
+        }
+    }
 
-            // Auto-scroll to bottom if enabled
-            if self.auto_scroll {
-                self.scroll_offset = self.log_buffer.len().saturating_sub(1);
+    /// Enforce ring buffer size. The offset indexes the filtered view, so it
+    /// only moves when a line that is part of the view drops out; lines of
+    /// hidden pods (or filtered out) never shift what is being read.
+    fn make_room(&mut self) {
+        let filter_regex = ((!self.auto_scroll || self.mark.is_some())
+            && self.log_buffer.len() >= self.max_buffer_size)
+            .then(|| self.filter_regex())
+            .flatten();
+        while self.log_buffer.len() >= self.max_buffer_size {
+            let Some(evicted) = self.log_buffer.pop_front() else {
+                break;
+            };
+            if (!self.auto_scroll || self.mark.is_some())
+                && self.is_visible(&evicted, filter_regex.as_ref())
+            {
+                self.scroll_offset = self.scroll_offset.saturating_sub(1);
+                self.mark = self.mark.map(|m| m.saturating_sub(1));
             }
         }
     }
 
+    /// Add a note to the end of the buffer, even while paused
+    pub fn add_note(&mut self, note: LogEntry) {
+        self.make_room();
+        self.log_buffer.push_back(note);
+    }
+
+    /// Insert the note typed in note mode at the live position
+    pub fn submit_note(&mut self) {
+        self.mode = AppMode::Normal;
+        let text = std::mem::take(&mut self.note_input);
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.add_note(LogEntry::note(text.to_string(), chrono::Utc::now()));
+        self.set_status_message("Note added");
+    }
+
+    /// Count a marker that repeats its container's previous line (same kind,
+    /// within `MARKER_WINDOW`) on that line, as `... ×N`, instead of adding
+    /// another one. Returns whether the marker was absorbed.
+    fn coalesce_marker(&mut self, msg: &LogMessage) -> bool {
+        let Some(kind) = MarkerKind::of(&msg.line) else {
+            return false;
+        };
+        let previous = self
+            .log_buffer
+            .iter_mut()
+            .rev()
+            .take(MARKER_LOOKBACK)
+            .find(|m| {
+                m.container_name == msg.container_name
+                    && m.pod_name == msg.pod_name
+                    && m.namespace == msg.namespace
+                    && m.cluster == msg.cluster
+            });
+        let Some(previous) = previous else {
+            return false;
+        };
+        if previous.marker != Some(kind) || msg.timestamp - previous.timestamp > MARKER_WINDOW {
+            return false;
+        }
+        previous.repeats += 1;
+        previous.msg.line = format!("{} ×{}", msg.line, previous.repeats);
+        previous.msg.timestamp = msg.timestamp;
+        true
+    }
+
+    /// Fold an --elide-frames stack frame into the newest line when that is a
+    /// frame of the same container, keeping the full text for the line detail
+    /// view. Returns the line when it starts a new entry instead.
+    fn fold_frame(&mut self, msg: LogMessage) -> Option<LogMessage> {
+        let Some(regex) = &self.elide_frames else {
+            return Some(msg);
+        };
+        if !regex.is_match(&msg.line) {
+            return Some(msg);
+        }
+        let Some(last) = self.log_buffer.back_mut() else {
+            return Some(msg);
+        };
+        let same_container = last.container_name == msg.container_name
+            && last.pod_name == msg.pod_name
+            && last.namespace == msg.namespace
+            && last.cluster == msg.cluster;
+        if !same_container || (last.elided.is_empty() && !regex.is_match(&last.line)) {
+            return Some(msg);
+        }
+        if last.elided.is_empty() {
+            last.elided.push(std::mem::take(&mut last.msg.line));
+        }
+        last.elided.push(msg.line);
+        last.msg.line = elided_frames_line(last.elided.len());
+        None
+    }
+
     pub fn add_pod(&mut self, info: PodInfo) {
+        self.sidebar_dirty = true;
+        if let Some(order) = &mut self.frozen_order {
+            order.add(&info);
+        }
+        // A pod that comes back under the same name is live again
+        self.tombstones.retain(|t| t.info.key != info.key);
+
         // Add pod state if not exists
-        if !self.pod_states.contains_key(&info.key) {
-            self.pod_states.insert(
-                info.key.clone(),
-                PodState {
-                    enabled: true,
-                    last_seen: chrono::Utc::now(),
-                },
-            );
+        let state = self
+            .pod_states
+            .entry(info.key.clone())
+            .or_insert_with(|| PodState {
+                enabled: true,
+                last_seen: chrono::Utc::now(),
+                phase_history: VecDeque::new(),
+                activity: Activity::default(),
+            });
+
+        // Record phase transitions
+        if state.phase_history.back().map(|(_, p)| p) != Some(&info.phase) {
+            // A pod that becomes Running again has recovered; drop its warning badge
+            if info.phase == "Running" && !state.phase_history.is_empty() {
+                self.pod_warnings.remove(&format!(
+                    "{}/{}/{}",
+                    info.key.cluster, info.key.namespace, info.key.pod_name
+                ));
+            }
+            state
+                .phase_history
+                .push_back((chrono::Utc::now(), info.phase.clone()));
+            if state.phase_history.len() > MAX_PHASE_HISTORY {
+                state.phase_history.pop_front();
+            }
         }
 
         // Update pod info
         if let Some(existing) = self.pods.iter_mut().find(|p| p.key == info.key) {
-            *existing = info;
+            // The zone may have been filled in after this update was sent
+            let zone = match &info.zone {
+                None if existing.node == info.node => existing.zone.take(),
+                _ => None,
+            };
+            *existing = PodInfo {
+                zone: info.zone.or(zone),
+                ..info
+            };
         } else {
             self.pods.push(info.clone());
 
             // Auto-expand parent nodes for new pods
             self.expanded_nodes.insert(info.key.cluster.clone());
-            self.expanded_nodes.insert(format!(
-                "{}/{}",
-                info.key.cluster, info.key.namespace
-            ));
+            self.expanded_nodes
+                .insert(format!("{}/{}", info.key.cluster, info.key.namespace));
             self.expanded_nodes.insert(format!(
                 "{}/{}/{}",
                 info.key.cluster, info.key.namespace, info.key.pod_name
             ));
+            if let Some(workload) = &info.workload {
+                self.expanded_nodes.insert(format!(
+                    "{}/{}/{}",
+                    info.key.cluster, info.key.namespace, workload
+                ));
+            }
 
             // Auto-select first pod if sidebar visible and nothing selected
             if self.sidebar_visible
@@ -172,9 +1287,69 @@ impl App {
         }
     }
 
+    /// Fill in the zone of a node once it is resolved, on the pods (and
+    /// tombstones) scheduled there
+    pub fn set_node_zone(&mut self, cluster: &str, node: &str, zone: String) {
+        let infos = self
+            .pods
+            .iter_mut()
+            .chain(self.tombstones.iter_mut().map(|t| &mut t.info));
+        for info in infos {
+            if info.key.cluster == cluster && info.node.as_deref() == Some(node) {
+                info.zone = Some(zone.clone());
+            }
+        }
+    }
+
     pub fn remove_pod(&mut self, key: &PodKey) {
+        self.sidebar_dirty = true;
+        if let Some(info) = self.pods.iter().find(|p| &p.key == key) {
+            if self.tombstone_retention.is_some() {
+                let mut lines: Vec<LogEntry> = self
+                    .log_buffer
+                    .iter()
+                    .rev()
+                    .filter(|m| {
+                        m.cluster == key.cluster
+                            && m.namespace == key.namespace
+                            && m.pod_name == key.pod_name
+                            && m.container_name == key.container_name
+                    })
+                    .take(self.tombstone_lines)
+                    .cloned()
+                    .collect();
+                lines.reverse();
+                self.tombstones.push(Tombstone {
+                    info: info.clone(),
+                    removed_at: chrono::Utc::now(),
+                    lines,
+                });
+            } else if let Some(order) = &mut self.frozen_order {
+                order.stale.push(info.clone());
+            }
+        }
         self.pods.retain(|p| &p.key != key);
         self.pod_states.remove(key);
+        self.pod_warnings.remove(&format!(
+            "{}/{}/{}",
+            key.cluster, key.namespace, key.pod_name
+        ));
+    }
+
+    /// Record a Warning event for a pod's sidebar badge
+    pub fn add_pod_warning(&mut self, warning: PodWarningEvent) {
+        if warning.last_seen < chrono::Utc::now() - WARNING_WINDOW {
+            return;
+        }
+        let path = format!(
+            "{}/{}/{}",
+            warning.cluster, warning.namespace, warning.pod_name
+        );
+        self.pod_warnings
+            .entry(path)
+            .or_default()
+            .events
+            .insert(warning.event_name, (warning.last_seen, warning.reason));
     }
 
     pub fn toggle_sidebar_item(&mut self) {
@@ -199,6 +1374,14 @@ impl App {
                         self.expanded_nodes.insert(path);
                     }
                 }
+                TreeNodeType::Workload(cluster, namespace, workload) => {
+                    let path = format!("{}/{}/{}", cluster, namespace, workload);
+                    if self.expanded_nodes.contains(&path) {
+                        self.expanded_nodes.remove(&path);
+                    } else {
+                        self.expanded_nodes.insert(path);
+                    }
+                }
                 TreeNodeType::Pod(cluster, namespace, pod) => {
                     let path = format!("{}/{}/{}", cluster, namespace, pod);
                     if self.expanded_nodes.contains(&path) {
@@ -214,56 +1397,489 @@ impl App {
                     {
                         state.enabled = !state.enabled;
                     }
+                    return;
                 }
             }
+            self.sidebar_dirty = true;
         }
     }
 
-    pub fn filtered_logs(&self) -> Vec<&LogMessage> {
-        let filter_regex = if !self.filter_pattern.is_empty() {
-            // Make filter case-insensitive by default (prepend (?i))
-            let pattern = format!("(?i){}", self.filter_pattern);
-            Regex::new(&pattern).ok()
-        } else {
-            None
+    /// The `f` filter, case-insensitive by default
+    fn filter_regex(&self) -> Option<Regex> {
+        if self.filter_pattern.is_empty() {
+            return None;
+        }
+        Regex::new(&format!("(?i){}", self.filter_pattern)).ok()
+    }
+
+    /// Whether a line is part of the view: its container is enabled, it belongs
+    /// to the focused cluster (if any) and it matches the filter
+    fn is_visible(&self, msg: &LogEntry, filter_regex: Option<&Regex>) -> bool {
+        // The user's own notes stay in view whatever is filtered
+        if msg.is_note() {
+            return true;
+        }
+        let key = PodKey {
+            cluster: msg.cluster.clone(),
+            namespace: msg.namespace.clone(),
+            pod_name: msg.pod_name.clone(),
+            container_name: msg.container_name.clone(),
         };
+        let enabled = self.pod_states.get(&key).map(|s| s.enabled).unwrap_or(true);
 
-        self.log_buffer
-            .iter()
-            .filter(|msg| {
-                // Check if pod is enabled
-                let key = PodKey {
-                    cluster: msg.cluster.clone(),
-                    namespace: msg.namespace.clone(),
-                    pod_name: msg.pod_name.clone(),
-                    container_name: msg.container_name.clone(),
-                };
-                let enabled = self.pod_states.get(&key).map(|s| s.enabled).unwrap_or(true);
-
-                if !enabled {
-                    return false;
-                }
+        if !enabled {
+            return false;
+        }
 
-                // Apply filter pattern (f key - shows only matching lines)
-                if let Some(ref re) = filter_regex {
-                    return re.is_match(&msg.line);
-                }
+        // Restrict to the focused cluster, if any
+        if let Some(ref cluster) = self.focused_cluster
+            && &msg.cluster != cluster
+        {
+            return false;
+        }
 
-                true
-            })
-            .collect()
+        if self.muted_clusters.contains(&msg.cluster) {
+            return false;
+        }
+
+        if self
+            .exclude_pattern
+            .as_ref()
+            .is_some_and(|re| re.is_match(&msg.line))
+        {
+            return false;
+        }
+
+        if self.env_filter.is_some() && self.environment(&msg.cluster) != self.env_filter.as_deref()
+        {
+            return false;
+        }
+
+        if self.trace_only && msg.trace_id(self.trace_field.as_ref()) != self.trace.as_deref() {
+            return false;
+        }
+
+        if let Some(min) = self.min_level
+            && !msg.level.map_or(self.keep_unleveled, |level| level >= min)
+        {
+            return false;
+        }
+
+        // Apply filter pattern (f key - shows only matching lines)
+        if let Some(re) = filter_regex {
+            return re.is_match(&msg.line);
+        }
+
+        true
     }
 
-    pub fn update_search_matches(&mut self) {
-        self.search_matches.clear();
+    pub fn filtered_logs(&self) -> Vec<&LogEntry> {
+        let filter_regex = self.filter_regex();
+        let results_regex = self.results_only.then(|| self.search_regex()).flatten();
+        let mut logs: Vec<&LogEntry> = self
+            .log_buffer
+            .iter()
+            .filter(|msg| self.is_visible(msg, filter_regex.as_ref()))
+            .filter(|msg| {
+                results_regex
+                    .as_ref()
+                    .is_none_or(|re| re.is_match(&msg.line))
+            })
+            .collect();
 
-        if self.search_pattern.is_empty() {
+        // Stable, so arrival order breaks ties left by time_order
+        if self.sort_by_time {
+            logs.sort_by(|a, b| a.time_order(b));
+        }
+        logs
+    }
+
+    /// Switch between wrapping at the pane edge and at a fixed column
+    pub fn toggle_fixed_wrap(&mut self) {
+        self.fixed_wrap = !self.fixed_wrap;
+        let msg = if self.fixed_wrap {
+            format!("Wrapping at column {}", self.wrap_width)
+        } else {
+            "Wrapping at the pane edge".to_string()
+        };
+        self.set_status_message(msg);
+    }
+
+    /// The column log lines wrap at, if not the pane edge
+    pub fn fixed_wrap_width(&self) -> Option<u16> {
+        self.fixed_wrap.then_some(self.wrap_width)
+    }
+
+    pub fn toggle_detail_pane(&mut self) {
+        self.detail_pane = !self.detail_pane;
+    }
+
+    /// Widen (`grow`) or narrow the detail pane, opening it if it is hidden
+    pub fn resize_detail_pane(&mut self, grow: bool) {
+        if !self.detail_pane {
+            self.detail_pane = true;
+            return;
+        }
+        self.detail_width = if grow {
+            (self.detail_width + DETAIL_WIDTH_STEP).min(MAX_DETAIL_WIDTH)
+        } else {
+            self.detail_width
+                .saturating_sub(DETAIL_WIDTH_STEP)
+                .max(MIN_DETAIL_WIDTH)
+        };
+        self.set_status_message(format!("Detail pane: {}% wide", self.detail_width));
+    }
+
+    /// The detail pane's width in percent of the log area, while it is shown
+    pub fn detail_pane_width(&self) -> Option<u16> {
+        self.detail_pane.then_some(self.detail_width)
+    }
+
+    /// What the detail pane shows: the current line and the pod selected in the
+    /// sidebar, or the current line's pod when the sidebar is hidden
+    pub fn detail_pane_content(&self) -> (Option<&LogEntry>, Option<&PodInfo>) {
+        let pod = self
+            .selected_pod_key()
+            .and_then(|key| self.pods.iter().find(|p| p.key == key));
+        (self.current_line(), pod)
+    }
+
+    pub fn toggle_sort_by_time(&mut self) {
+        self.sort_by_time = !self.sort_by_time;
+        let order = if self.sort_by_time {
+            "sorted by time"
+        } else {
+            "arrival order"
+        };
+        self.set_status_message(format!("Showing lines in {}", order));
+        self.update_search_matches();
+    }
+
+    pub fn toggle_reverse_order(&mut self) {
+        self.reverse_order = !self.reverse_order;
+        self.set_status_message(if self.reverse_order {
+            "Newest lines at the top"
+        } else {
+            "Newest lines at the bottom"
+        });
+    }
+
+    /// The line the view is anchored on: the newest line while auto-scrolling,
+    /// otherwise the top visible line.
+    pub fn current_line(&self) -> Option<&LogEntry> {
+        let filtered = self.filtered_logs();
+        let idx = if self.auto_scroll {
+            filtered.len().checked_sub(1)?
+        } else {
+            self.scroll_offset.min(filtered.len().checked_sub(1)?)
+        };
+        filtered.get(idx).copied()
+    }
+
+    pub fn show_line_detail(&mut self) {
+        // On a deleted pod in the sidebar, show what it said last instead
+        if !self.selected_tombstones().is_empty() {
+            self.overlay_scroll = OverlayScroll::default();
+            self.mode = AppMode::Tombstone;
+            return;
+        }
+        if let Some(msg) = self.current_line().cloned() {
+            self.detail_line = Some(msg);
+            self.overlay_scroll = OverlayScroll::default();
+            self.mode = AppMode::LineDetail;
+        }
+    }
+
+    /// Tombstones of the deleted pod or container selected in the sidebar
+    pub fn selected_tombstones(&self) -> Vec<&Tombstone> {
+        if !self.sidebar_visible {
+            return Vec::new();
+        }
+        let Some(idx) = self.sidebar_state.selected() else {
+            return Vec::new();
+        };
+        match self.sidebar_item_types.get(idx) {
+            Some(TreeNodeType::Pod(cluster, namespace, pod)) => self
+                .tombstones
+                .iter()
+                .filter(|t| {
+                    &t.info.key.cluster == cluster
+                        && &t.info.key.namespace == namespace
+                        && &t.info.key.pod_name == pod
+                })
+                .collect(),
+            Some(TreeNodeType::Container) => match self.sidebar_item_keys.get(idx) {
+                Some(Some(key)) => self
+                    .tombstones
+                    .iter()
+                    .filter(|t| &t.info.key == key)
+                    .collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// The pod whose details should be shown: the pod selected in the sidebar,
+    /// or the pod of the current line when the sidebar is hidden.
+    pub fn selected_pod_key(&self) -> Option<PodKey> {
+        if self.sidebar_visible {
+            let idx = self.sidebar_state.selected()?;
+            match self.sidebar_item_types.get(idx)? {
+                TreeNodeType::Pod(cluster, namespace, pod) => self
+                    .pods
+                    .iter()
+                    .find(|p| {
+                        &p.key.cluster == cluster
+                            && &p.key.namespace == namespace
+                            && &p.key.pod_name == pod
+                    })
+                    .map(|p| p.key.clone()),
+                TreeNodeType::Container => self.sidebar_item_keys.get(idx)?.clone(),
+                _ => None,
+            }
+        } else {
+            self.current_line().map(|msg| PodKey {
+                cluster: msg.cluster.clone(),
+                namespace: msg.namespace.clone(),
+                pod_name: msg.pod_name.clone(),
+                container_name: msg.container_name.clone(),
+            })
+        }
+    }
+
+    /// Restart the failed log streams of the selected pod (all its containers)
+    /// or container
+    pub fn restart_failed_streams(&mut self) {
+        let (Some(selected), Some(states), Some(tx)) = (
+            self.selected_pod_key(),
+            &self.stream_states,
+            &self.respawn_tx,
+        ) else {
+            return;
+        };
+        let whole_pod = self.sidebar_visible
+            && matches!(
+                self.sidebar_state
+                    .selected()
+                    .and_then(|idx| self.sidebar_item_types.get(idx)),
+                Some(TreeNodeType::Pod(..))
+            );
+
+        let failed: Vec<PodKey> = self
+            .pods
+            .iter()
+            .map(|p| &p.key)
+            .filter(|k| {
+                k.cluster == selected.cluster
+                    && k.namespace == selected.namespace
+                    && k.pod_name == selected.pod_name
+                    && (whole_pod || k.container_name == selected.container_name)
+            })
+            .filter(|k| matches!(states.get(k), Some(ConnectionState::Failed(_))))
+            .cloned()
+            .collect();
+
+        if failed.is_empty() {
+            self.set_status_message("No failed streams to restart".to_string());
+            return;
+        }
+        let count = failed.len();
+        for key in failed {
+            let _ = tx.send(key);
+        }
+        self.set_status_message(format!("Restarting {} failed stream(s)", count));
+    }
+
+    /// Open the container popup for the selected pod
+    pub fn open_container_picker(&mut self) {
+        if let Some(pod) = self.selected_pod_key() {
+            self.container_picker = Some(ContainerPicker {
+                pod,
+                filter: String::new(),
+                selected: 0,
+            });
+            self.mode = AppMode::ContainerPicker;
+        }
+    }
+
+    pub fn close_container_picker(&mut self) {
+        self.container_picker = None;
+        self.mode = AppMode::Normal;
+    }
+
+    /// Containers of the picker's pod matching its filter, with whether each is enabled
+    pub fn picker_entries(&self) -> Vec<(PodKey, bool)> {
+        let Some(picker) = &self.container_picker else {
+            return Vec::new();
+        };
+        let mut entries: Vec<(PodKey, bool)> = self
+            .pods
+            .iter()
+            .filter(|p| {
+                p.key.cluster == picker.pod.cluster
+                    && p.key.namespace == picker.pod.namespace
+                    && p.key.pod_name == picker.pod.pod_name
+                    && fuzzy_match(&picker.filter, &p.key.container_name)
+            })
+            .map(|p| {
+                let enabled = self.pod_states.get(&p.key).is_none_or(|s| s.enabled);
+                (p.key.clone(), enabled)
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.container_name.cmp(&b.0.container_name));
+        entries
+    }
+
+    pub fn picker_edit_filter(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(picker) = &mut self.container_picker {
+            edit(&mut picker.filter);
+            picker.selected = 0;
+        }
+    }
+
+    pub fn picker_move(&mut self, down: bool) {
+        let count = self.picker_entries().len();
+        if let Some(picker) = &mut self.container_picker
+            && count > 0
+        {
+            picker.selected = if down {
+                (picker.selected + 1).min(count - 1)
+            } else {
+                picker.selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Toggle the highlighted container
+    pub fn picker_toggle(&mut self) {
+        let Some(picker) = &self.container_picker else {
             return;
+        };
+        if let Some((key, _)) = self.picker_entries().get(picker.selected)
+            && let Some(state) = self.pod_states.get_mut(key)
+        {
+            state.enabled = !state.enabled;
+        }
+    }
+
+    /// Enable every matching container, or disable them all if they already are
+    pub fn picker_toggle_all(&mut self) {
+        let entries = self.picker_entries();
+        let enable = entries.iter().any(|(_, enabled)| !enabled);
+        for (key, _) in entries {
+            if let Some(state) = self.pod_states.get_mut(&key) {
+                state.enabled = enable;
+            }
+        }
+    }
+
+    pub fn show_phase_history(&mut self) {
+        if self.selected_pod_key().is_some() {
+            self.mode = AppMode::PhaseHistory;
+        }
+    }
+
+    /// Open the watcher/stream debug timeline, scrolled to the newest entries
+    pub fn show_debug_timeline(&mut self) {
+        if self.stream_states.is_some() {
+            self.overlay_scroll = OverlayScroll {
+                offset: usize::MAX,
+                ..OverlayScroll::default()
+            };
+            self.mode = AppMode::DebugTimeline;
+        }
+    }
+
+    pub fn show_hotspots(&mut self) {
+        self.overlay_scroll = OverlayScroll::default();
+        self.mode = AppMode::Hotspots;
+    }
+
+    /// Error-level lines in the buffer tallied by node, most errors first.
+    /// Lines of pods whose node is unknown are counted under `node: None`;
+    /// deleted pods count towards their node while they are tombstoned.
+    pub fn error_hotspots(&self) -> Vec<Hotspot> {
+        let known = self
+            .pods
+            .iter()
+            .chain(self.tombstones.iter().map(|t| &t.info));
+        // Node of every known pod, by pod path, and the zone of every node
+        let mut nodes: HashMap<String, &str> = HashMap::new();
+        let mut zones: HashMap<(&str, &str), &str> = HashMap::new();
+        for info in known {
+            let Some(node) = &info.node else {
+                continue;
+            };
+            let key = &info.key;
+            nodes.insert(
+                format!("{}/{}/{}", key.cluster, key.namespace, key.pod_name),
+                node,
+            );
+            if let Some(zone) = &info.zone {
+                zones.insert((&key.cluster, node), zone);
+            }
+        }
+
+        // (cluster, node) -> (error lines, pods)
+        type Tally<'a> = HashMap<(&'a str, Option<&'a str>), (usize, HashSet<&'a str>)>;
+        let mut tally = Tally::new();
+        for msg in &self.log_buffer {
+            if msg.level.is_none_or(|level| level < LogLevel::Error) {
+                continue;
+            }
+            let path = format!("{}/{}/{}", msg.cluster, msg.namespace, msg.pod_name);
+            let node = nodes.get(&path).copied();
+            let (errors, pods) = tally.entry((msg.cluster.as_str(), node)).or_default();
+            *errors += 1;
+            pods.insert(msg.pod_name.as_str());
+        }
+
+        let mut hotspots: Vec<Hotspot> = tally
+            .into_iter()
+            .map(|((cluster, node), (errors, pods))| Hotspot {
+                cluster: cluster.to_string(),
+                node: node.map(str::to_string),
+                zone: node
+                    .and_then(|node| zones.get(&(cluster, node)))
+                    .map(|zone| zone.to_string()),
+                errors,
+                pods: pods.len(),
+            })
+            .collect();
+        hotspots.sort_by(|a, b| {
+            b.errors
+                .cmp(&a.errors)
+                .then_with(|| a.cluster.cmp(&b.cluster))
+                .then_with(|| a.node.cmp(&b.node))
+        });
+        hotspots
+    }
+
+    /// Entries of the debug timeline: of the selected pod if any, else all
+    pub fn debug_timeline(&self) -> (Option<String>, Vec<TimelineEntry>) {
+        let Some(stream_states) = &self.stream_states else {
+            return (None, Vec::new());
+        };
+        let pod = self
+            .selected_pod_key()
+            .map(|k| format!("{}/{}/{}", k.cluster, k.namespace, k.pod_name));
+        let entries = stream_states.timeline.snapshot(pod.as_deref());
+        (pod, entries)
+    }
+
+    /// The search pattern, case-insensitive by default (prepend (?i))
+    fn search_regex(&self) -> Option<Regex> {
+        if self.search_pattern.is_empty() {
+            return None;
         }
+        Regex::new(&format!("(?i){}", self.search_pattern)).ok()
+    }
 
-        // Make search case-insensitive by default (prepend (?i))
-        let pattern = format!("(?i){}", self.search_pattern);
-        let Ok(regex) = Regex::new(&pattern) else {
+    pub fn update_search_matches(&mut self) {
+        self.search_matches.clear();
+
+        let Some(regex) = self.search_regex() else {
             return;
         };
 
@@ -284,8 +1900,11 @@ impl App {
             return;
         }
         self.current_match_index = (self.current_match_index + 1) % self.search_matches.len();
+        if self.current_match_index == 0 {
+            self.set_status_message("search wrapped to top".to_string());
+        }
         self.scroll_offset = self.search_matches[self.current_match_index];
-        self.auto_scroll = false;
+        self.leave_bottom();
     }
 
     pub fn jump_to_prev_match(&mut self) {
@@ -294,47 +1913,431 @@ impl App {
         }
         if self.current_match_index == 0 {
             self.current_match_index = self.search_matches.len() - 1;
+            self.set_status_message("search wrapped to bottom".to_string());
         } else {
             self.current_match_index -= 1;
         }
         self.scroll_offset = self.search_matches[self.current_match_index];
-        self.auto_scroll = false;
+        self.leave_bottom();
+    }
+
+    /// Last valid offset into the filtered view
+    fn max_offset(&self) -> usize {
+        self.filtered_logs().len().saturating_sub(1)
+    }
+
+    /// Offset of the line the view is anchored on: the newest line while
+    /// auto-scrolling, otherwise `scroll_offset`
+    fn view_offset(&self) -> usize {
+        let max_offset = self.max_offset();
+        if self.auto_scroll {
+            max_offset
+        } else {
+            self.scroll_offset.min(max_offset)
+        }
     }
 
     pub fn scroll_up(&mut self) {
-        self.scroll_offset = self.scroll_offset.saturating_sub(1);
-        self.auto_scroll = false;
+        self.scroll_offset = self.view_offset().saturating_sub(1);
+        self.leave_bottom();
     }
 
     pub fn scroll_down(&mut self) {
-        if self.scroll_offset < self.log_buffer.len().saturating_sub(1) {
+        if self.scroll_offset < self.max_offset() {
             self.scroll_offset += 1;
-        } else {
+        } else if self.auto_scroll_resume != AutoScrollResume::Never {
             self.auto_scroll = true;
         }
     }
 
     pub fn page_up(&mut self, page_size: usize) {
-        self.scroll_offset = self.scroll_offset.saturating_sub(page_size);
-        self.auto_scroll = false;
+        self.scroll_offset = self.view_offset().saturating_sub(page_size);
+        self.leave_bottom();
     }
 
     pub fn page_down(&mut self, page_size: usize) {
-        let max_offset = self.log_buffer.len().saturating_sub(1);
+        let max_offset = self.max_offset();
         self.scroll_offset = (self.scroll_offset + page_size).min(max_offset);
-        if self.scroll_offset >= max_offset {
+        if self.scroll_offset >= max_offset && self.auto_scroll_resume != AutoScrollResume::Never {
             self.auto_scroll = true;
         }
     }
 
     pub fn scroll_to_top(&mut self) {
         self.scroll_offset = 0;
-        self.auto_scroll = false;
+        self.leave_bottom();
     }
 
     pub fn scroll_to_bottom(&mut self) {
-        self.scroll_offset = self.log_buffer.len().saturating_sub(1);
+        self.scroll_offset = self.max_offset();
         self.auto_scroll = true;
+        self.last_scroll_input = None;
+    }
+
+    /// Cycle timestamps: off, full, compact (`.mmm` within the same second),
+    /// relative to the start of the session
+    pub fn cycle_timestamps(&mut self) {
+        use TimestampStyle::*;
+        (self.show_timestamps, self.timestamp_style) =
+            match (self.show_timestamps, self.timestamp_style) {
+                (false, _) => (true, Full),
+                (true, Full) => (true, Compact),
+                (true, Compact) => (true, Relative),
+                (true, Relative) => (false, Full),
+            };
+        match (self.show_timestamps, self.timestamp_style) {
+            (true, Compact) => self.set_status_message("Compact timestamps"),
+            (true, Relative) => self.set_status_message("Timestamps relative to session start"),
+            _ => {}
+        }
+    }
+
+    pub fn toggle_auto_scroll(&mut self) {
+        // Stay on the lines being shown when auto-scroll is turned off
+        self.scroll_offset = self.view_offset();
+        self.auto_scroll = !self.auto_scroll;
+        // Turned off on purpose: don't resume on a timeout
+        self.last_scroll_input = None;
+    }
+
+    /// Mark the current line as one end of the range to pipe, or clear the mark
+    pub fn toggle_mark(&mut self) {
+        if self.mark.take().is_some() {
+            self.set_status_message("Mark cleared");
+        } else if !self.filtered_logs().is_empty() {
+            self.mark = Some(self.view_offset());
+            self.set_status_message("Line marked; scroll to the other end and press | to pipe");
+        }
+    }
+
+    /// The lines to pipe: from the mark to the current line, or the whole view
+    /// without a mark. Lines carry their prefix unless it is hidden.
+    pub fn selected_lines(&self) -> Vec<String> {
+        let logs = self.filtered_logs();
+        let Some(last) = logs.len().checked_sub(1) else {
+            return Vec::new();
+        };
+        let (start, end) = match self.mark {
+            Some(mark) => {
+                let current = self.view_offset();
+                (mark.min(current).min(last), mark.max(current).min(last))
+            }
+            None => (0, last),
+        };
+        logs[start..=end]
+            .iter()
+            .map(|msg| self.export_line(msg))
+            .collect()
+    }
+
+    /// A line as copied or saved: with its prefix when prefixes are shown
+    fn export_line(&self, msg: &LogEntry) -> String {
+        if msg.is_note() {
+            format!("[note] {}", msg.line)
+        } else if let Some(format) = self.prefix_format.as_ref().filter(|_| self.show_prefix) {
+            format!("{} {}", format.render(msg), msg.line)
+        } else if self.show_prefix {
+            format!(
+                "[{}{}.{}/{}/{}] {}",
+                env_tag(self.environment(&msg.cluster)),
+                msg.cluster,
+                msg.namespace,
+                msg.pod_name,
+                msg.container_name,
+                msg.line
+            )
+        } else {
+            msg.line.clone()
+        }
+    }
+
+    /// Write the lines in view to `path`, formatted as exported and with their
+    /// timestamps when those are shown
+    pub fn save_logs(&mut self, path: &std::path::Path) {
+        let lines: Vec<String> = self
+            .filtered_logs()
+            .iter()
+            .map(|msg| {
+                let line = self.export_line(msg);
+                if self.show_timestamps {
+                    format!(
+                        "{} {}",
+                        msg.timestamp
+                            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                        line
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+        let count = lines.len();
+        let mut contents = lines.join("\n");
+        if count > 0 {
+            contents.push('\n');
+        }
+        match std::fs::write(path, contents) {
+            Ok(()) => {
+                self.set_status_message(format!("Saved {} lines to {}", count, path.display()))
+            }
+            Err(e) => self.set_status_message(format!("Could not write {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Save the lines in view to `kubectl-tail-<epoch>.log` in the working
+    /// directory
+    pub fn save_logs_timestamped(&mut self) {
+        let path = format!("kubectl-tail-{}.log", chrono::Utc::now().timestamp());
+        self.save_logs(std::path::Path::new(&path));
+    }
+
+    /// Run the command typed in command mode. Returns false when it quits.
+    pub fn run_command(&mut self) -> bool {
+        let input = std::mem::take(&mut self.command_input);
+        self.mode = AppMode::Normal;
+        let command = match parse_command(&input) {
+            Ok(command) => command,
+            Err(e) => {
+                self.set_status_message(e);
+                return true;
+            }
+        };
+        match command {
+            Command::Filter(pattern) => {
+                if let Err(e) = Regex::new(&pattern) {
+                    self.set_status_message(format!("Invalid regex: {}", e));
+                    return true;
+                }
+                self.filter_pattern = pattern;
+                self.scroll_to_top();
+            }
+            Command::Search(pattern) => {
+                self.search_pattern = pattern;
+                self.update_search_matches();
+                self.set_status_message(format!("{} matches", self.search_matches.len()));
+            }
+            Command::Clear => self.clear_logs(),
+            Command::Save(path) => self.save_logs(&path),
+            Command::Context(cluster) => {
+                if let Some(name) = &cluster
+                    && !self.get_clusters().contains(name)
+                {
+                    self.set_status_message(format!("No cluster named {}", name));
+                    return true;
+                }
+                self.focused_cluster = cluster;
+                if self.auto_scroll {
+                    self.scroll_to_bottom();
+                } else {
+                    self.scroll_to_top();
+                }
+            }
+            Command::Tail(n) => {
+                self.scroll_offset = self.filtered_logs().len().saturating_sub(n.max(1));
+                self.leave_bottom();
+            }
+            Command::Level(level) => {
+                let anchor = self.current_line().cloned();
+                if let Some(level) = level {
+                    self.level_threshold = level;
+                }
+                self.min_level = level;
+                self.keep_anchor(anchor);
+            }
+            Command::Env(env) => {
+                if let Some(name) = &env
+                    && !self.environments.values().any(|e| e == name)
+                {
+                    self.set_status_message(format!("No environment named {}", name));
+                    return true;
+                }
+                self.env_filter = env;
+                if self.auto_scroll {
+                    self.scroll_to_bottom();
+                } else {
+                    self.scroll_to_top();
+                }
+            }
+            Command::Quit => return false,
+        }
+        true
+    }
+
+    /// The environment tag of a cluster, if the config file gives it one
+    pub fn environment(&self, cluster: &str) -> Option<&str> {
+        self.environments.get(cluster).map(String::as_str)
+    }
+
+    /// Send the selected lines to the --pipe-cmd
+    pub fn pipe_selection(&mut self) {
+        let (Some(cmd), Some(tx)) = (&self.pipe_cmd, &self.pipe_tx) else {
+            self.set_status_message("No --pipe-cmd given");
+            return;
+        };
+        let lines = self.selected_lines();
+        if lines.is_empty() {
+            return;
+        }
+        let msg = format!("Piping {} lines to `{}`", lines.len(), cmd);
+        if tx.send(lines).is_ok() {
+            self.mark = None;
+            self.set_status_message(msg);
+        }
+    }
+
+    /// `kubectl logs` commands reproducing the selected pod or container, or
+    /// every enabled container when nothing is selected
+    pub fn kubectl_commands(&self) -> Vec<String> {
+        let selected = self.selected_pod_key();
+        let whole_pod = self.sidebar_visible
+            && matches!(
+                self.sidebar_state
+                    .selected()
+                    .and_then(|idx| self.sidebar_item_types.get(idx)),
+                Some(TreeNodeType::Pod(..))
+            );
+        let mut keys: Vec<&PodKey> = self
+            .pods
+            .iter()
+            .map(|p| &p.key)
+            .filter(|k| match &selected {
+                Some(sel) => {
+                    k.cluster == sel.cluster
+                        && k.namespace == sel.namespace
+                        && k.pod_name == sel.pod_name
+                        && (whole_pod || k.container_name == sel.container_name)
+                }
+                None => self.pod_states.get(*k).is_none_or(|s| s.enabled),
+            })
+            .collect();
+        keys.sort_by(|a, b| {
+            (&a.cluster, &a.namespace, &a.pod_name, &a.container_name).cmp(&(
+                &b.cluster,
+                &b.namespace,
+                &b.pod_name,
+                &b.container_name,
+            ))
+        });
+
+        keys.into_iter()
+            .map(|k| {
+                let mut cmd = format!(
+                    "kubectl logs --context {} -n {} {} -c {} -f",
+                    shell_quote(&k.cluster),
+                    shell_quote(&k.namespace),
+                    shell_quote(&k.pod_name),
+                    shell_quote(&k.container_name)
+                );
+                if let Some(tail) = self.tail_lines {
+                    cmd.push_str(&format!(" --tail={}", tail));
+                }
+                if let Some(since) = self.since_seconds {
+                    cmd.push_str(&format!(" --since={}s", since));
+                }
+                cmd
+            })
+            .collect()
+    }
+
+    /// Copy the equivalent kubectl commands to the clipboard
+    pub fn copy_kubectl_commands(&mut self) {
+        let commands = self.kubectl_commands();
+        let msg = match commands.as_slice() {
+            [] => return,
+            [cmd] => format!("Copied: {}", cmd),
+            _ => format!("Copied {} kubectl commands", commands.len()),
+        };
+        self.clipboard_pending = Some(commands.join("\n"));
+        self.set_status_message(msg);
+    }
+
+    /// Text due for the clipboard; clears it
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard_pending.take()
+    }
+
+    /// Queue the --open-cmd for the selected pod or container
+    pub fn open_selected(&mut self) {
+        let Some(template) = &self.open_cmd else {
+            self.set_status_message("No --open-cmd given");
+            return;
+        };
+        let Some(key) = self.selected_pod_key() else {
+            self.set_status_message("Select a pod to open");
+            return;
+        };
+        self.open_pending = Some(fill_open_cmd(
+            template,
+            &key.cluster,
+            &key.namespace,
+            &key.pod_name,
+            &key.container_name,
+        ));
+    }
+
+    /// Command due to run with the TUI suspended; clears it
+    pub fn take_open_command(&mut self) -> Option<String> {
+        self.open_pending.take()
+    }
+
+    /// Note that the next frame differs from the last one drawn
+    pub fn mark_changed(&mut self) {
+        self.changed = true;
+    }
+
+    /// Whether anything changed since the last frame; clears it
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Whether a --notify bell is due; clears it
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell_pending)
+    }
+
+    /// Whether the status bar is flashing for a --notify match
+    pub fn is_flashing(&self) -> bool {
+        self.flash_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    fn leave_bottom(&mut self) {
+        self.auto_scroll = false;
+        self.last_scroll_input = Some(Instant::now());
+    }
+
+    /// Status bar indicator for auto-scroll, if any
+    pub fn auto_scroll_indicator(&self) -> Option<String> {
+        if self.auto_scroll {
+            return Some("AUTO".to_string());
+        }
+        match (self.auto_scroll_resume, self.last_scroll_input) {
+            (AutoScrollResume::Timeout(timeout), Some(at)) => {
+                let remaining = timeout.saturating_sub(at.elapsed());
+                Some(format!(
+                    "AUTO in {}s",
+                    remaining.as_secs_f64().ceil() as u64
+                ))
+            }
+            (AutoScrollResume::Never, _) => Some("HOLD".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Lay the sidebar out again if it changed since the last call, and the
+    /// index vectors mapping list positions back to nodes with it
+    pub fn refresh_sidebar(&mut self) {
+        if !std::mem::take(&mut self.sidebar_dirty) {
+            return;
+        }
+        let pods = SidebarPods::new(&self.pods, self.frozen_order.as_ref(), &self.tombstones);
+        self.sidebar_layout =
+            SidebarLayout::new(pods, self.frozen_order.as_ref(), &self.expanded_nodes);
+        let rows = || {
+            self.sidebar_layout
+                .rows(pods, 0..self.sidebar_layout.row_count())
+        };
+        self.sidebar_item_keys = rows().map(|row| row.key()).collect();
+        self.sidebar_item_types = rows().map(|row| row.node_type()).collect();
     }
 
     pub fn sidebar_select_next(&mut self) {
@@ -372,10 +2375,31 @@ impl App {
     }
 
     pub fn update_stats(&mut self) {
+        // Drop tombstones past their retention; a frozen sidebar keeps the row
+        if let Some(retention) = self.tombstone_retention {
+            let cutoff = chrono::Utc::now() - retention;
+            let (expired, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.tombstones)
+                .into_iter()
+                .partition(|t| t.removed_at < cutoff);
+            self.tombstones = kept;
+            self.sidebar_dirty |= !expired.is_empty();
+            if let Some(order) = &mut self.frozen_order {
+                order.stale.extend(expired.into_iter().map(|t| t.info));
+            }
+        }
+
         // Count running/enabled pods
         self.running_pods = self.pod_states.values().filter(|s| s.enabled).count();
         self.total_pods = self.pod_states.len();
 
+        // Quiet seconds scroll the sparklines too
+        if self.show_sparklines {
+            let now = chrono::Utc::now().timestamp();
+            for state in self.pod_states.values_mut() {
+                state.activity.advance(now);
+            }
+        }
+
         // Estimate memory usage (approximate: 200 bytes per log line)
         let avg_line_size = 200;
         self.memory_usage = (self.log_buffer.len() * avg_line_size) / (1024 * 1024);
@@ -387,20 +2411,483 @@ impl App {
                 .push(format!("filter: {}", self.filter_pattern));
         }
         if !self.search_pattern.is_empty() {
+            // Position among the matches, as navigated with n/N
+            let position = if self.search_matches.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    " [{}/{}]",
+                    self.current_match_index + 1,
+                    self.search_matches.len()
+                )
+            };
             self.active_filters
-                .push(format!("search: {}", self.search_pattern));
+                .push(format!("search: {}{}", self.search_pattern, position));
         }
         if self.paused {
             self.active_filters.push("PAUSED".to_string());
         }
+        if self.sort_by_time {
+            self.active_filters.push("SORTED".to_string());
+        }
+        if self.results_only && !self.search_pattern.is_empty() {
+            self.active_filters.push("MATCHES ONLY".to_string());
+        }
+        for watch in &self.idle_watches {
+            self.active_filters.push(format!("waiting for {}", watch));
+        }
+        if self.nothing_left {
+            self.active_filters
+                .push("all streams ended, nothing left to tail".to_string());
+        }
+        if self.frozen_order.is_some() {
+            self.active_filters.push("FROZEN".to_string());
+        }
+        if let Some(trace) = &self.trace {
+            let mode = if self.trace_only { "" } else { " (highlight)" };
+            self.active_filters
+                .push(format!("trace: {}{}", trace, mode));
+        }
+        if let Some(level) = self.min_level {
+            let mode = if self.keep_unleveled {
+                ""
+            } else {
+                " (leveled lines only)"
+            };
+            self.active_filters
+                .push(format!("level: {}+{}", level.name(), mode));
+        }
+        if let Some(env) = &self.env_filter {
+            self.active_filters.push(format!("env: {}", env));
+        }
+        if !self.muted_clusters.is_empty() {
+            let mut muted: Vec<&str> = self.muted_clusters.iter().map(String::as_str).collect();
+            muted.sort();
+            self.active_filters
+                .push(format!("muted: {}", muted.join(",")));
+        }
+        if let Some(mark) = self.mark {
+            let lines = mark.abs_diff(self.view_offset()) + 1;
+            self.active_filters.push(format!("MARK {} lines", lines));
+        }
+
+        // Expire warning badges
+        let cutoff = chrono::Utc::now() - WARNING_WINDOW;
+        for warnings in self.pod_warnings.values_mut() {
+            warnings.prune(cutoff);
+        }
+        self.pod_warnings.retain(|_, w| !w.events.is_empty());
+
+        // Suggest a bigger buffer once if the log channel keeps filling up
+        if !self.saturation_hinted
+            && let Some(stats) = &self.channel_stats
+            && stats.is_saturating()
+        {
+            self.saturation_hinted = true;
+            self.set_status_message(SATURATION_HINT);
+        }
+
+        // Return to live output once scrolling has been idle long enough
+        if let AutoScrollResume::Timeout(timeout) = self.auto_scroll_resume
+            && !self.auto_scroll
+            && self
+                .last_scroll_input
+                .is_some_and(|at| at.elapsed() >= timeout)
+        {
+            self.scroll_to_bottom();
+        }
+
+        // Expire transient status messages
+        if let Some((_, set_at)) = &self.status_message
+            && set_at.elapsed() >= STATUS_MESSAGE_TTL
+        {
+            self.status_message = None;
+        }
+    }
+
+    /// Diagnostic snapshot of the session for bug reports. Only metadata is
+    /// included, never log line contents.
+    pub fn state_dump(&self) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "kubectl-tail {} state dump at {}",
+            env!("CARGO_PKG_VERSION"),
+            chrono::Utc::now().to_rfc3339()
+        );
+
+        let _ = writeln!(out, "\n[watches]");
+        for watch in &self.watch_summary {
+            let _ = writeln!(out, "{}", watch);
+        }
+
+        let _ = writeln!(out, "\n[buffer]");
+        let _ = writeln!(
+            out,
+            "lines: {}/{}",
+            self.log_buffer.len(),
+            self.max_buffer_size
+        );
+        if let Some(stats) = &self.channel_stats {
+            let _ = writeln!(
+                out,
+                "channel high-water: {} (saturating: {})",
+                stats.high_water(),
+                stats.is_saturating()
+            );
+            let _ = writeln!(out, "truncated lines: {}", stats.truncated());
+        }
+        let _ = writeln!(
+            out,
+            "paused: {}, auto-scroll: {}, filter: {:?}, search: {:?}",
+            self.paused, self.auto_scroll, self.filter_pattern, self.search_pattern
+        );
+
+        let _ = writeln!(out, "\n[pods]");
+        let mut pods: Vec<&PodInfo> = self.pods.iter().collect();
+        pods.sort_by_key(|p| {
+            (
+                &p.key.cluster,
+                &p.key.namespace,
+                &p.key.pod_name,
+                &p.key.container_name,
+            )
+        });
+        for pod in pods {
+            let key = &pod.key;
+            let state = self.pod_states.get(key);
+            let phases: Vec<String> = state
+                .map(|s| {
+                    s.phase_history
+                        .iter()
+                        .map(|(at, phase)| format!("{}@{}", phase, at.format("%H:%M:%S")))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{}/{}/{}/{} phase={} enabled={} skipped={} restarts={} history=[{}]",
+                key.cluster,
+                key.namespace,
+                key.pod_name,
+                key.container_name,
+                pod.phase,
+                state.map(|s| s.enabled).unwrap_or(true),
+                pod.skipped,
+                pod.restarts,
+                phases.join(", ")
+            );
+        }
+
+        let _ = writeln!(out, "\n[streams]");
+        if let Some(states) = &self.stream_states {
+            for (key, state, reconnects) in states.snapshot() {
+                let last_error = states
+                    .last_error(&key)
+                    .map(|error| format!(" last_error={:?}", error))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "{}/{}/{}/{} state={:?} reconnects={} received={}{}",
+                    key.cluster,
+                    key.namespace,
+                    key.pod_name,
+                    key.container_name,
+                    state,
+                    reconnects,
+                    states.received(&key),
+                    last_error
+                );
+            }
+        }
+        out
+    }
+
+    /// Write `state_dump` to a file in the temp directory and report where
+    pub fn dump_state(&mut self) {
+        let path = std::env::temp_dir().join(format!(
+            "kubectl-tail-state-{}.txt",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        match std::fs::write(&path, self.state_dump()) {
+            Ok(()) => self.set_status_message(format!("State written to {}", path.display())),
+            Err(e) => self.set_status_message(format!("Could not write state dump: {}", e)),
+        }
+    }
+
+    /// Record whether a watcher is waiting for pods to match
+    pub fn set_watch_idle(&mut self, watch: String, idle: bool) {
+        if idle {
+            self.set_status_message(format!("No {}, waiting", watch));
+            self.idle_watches.insert(watch);
+        } else if self.idle_watches.remove(&watch) {
+            self.set_status_message(format!("Found {} again", watch));
+        }
+        self.update_stats();
+    }
+
+    /// Record whether tailing has ended for good, as opposed to waiting for
+    /// pods to appear
+    pub fn set_nothing_left(&mut self, ended: bool) {
+        if ended && !self.nothing_left {
+            self.set_status_message("All streams ended, nothing left to tail");
+        }
+        self.nothing_left = ended;
+        self.update_stats();
+    }
+
+    /// Record whether a watcher failed and is being resumed, shown on its
+    /// cluster's sidebar header
+    pub fn set_watch_reconnecting(&mut self, cluster: String, watch: String, reconnecting: bool) {
+        if reconnecting {
+            self.set_status_message(format!(
+                "Watch of {} on {} failed, reconnecting",
+                watch, cluster
+            ));
+            self.reconnecting_watches
+                .entry(cluster)
+                .or_default()
+                .insert(watch);
+        } else if let Some(watches) = self.reconnecting_watches.get_mut(&cluster) {
+            let (removed, empty) = (watches.remove(&watch), watches.is_empty());
+            if removed {
+                self.set_status_message(format!("Watch of {} on {} reconnected", watch, cluster));
+            }
+            if empty {
+                self.reconnecting_watches.remove(&cluster);
+            }
+        }
+    }
+
+    /// Show a message in the status bar for a few seconds
+    pub fn set_status_message(&mut self, msg: impl Into<String>) {
+        self.status_message = Some((msg.into(), Instant::now()));
     }
 
     pub fn clear_logs(&mut self) {
         self.log_buffer.clear();
         self.scroll_offset = 0;
+        self.mark = None;
         self.search_matches.clear();
     }
 
+    /// Step through trace correlation for the current line's trace: highlight
+    /// its lines, then show only them, then back off
+    pub fn cycle_trace(&mut self) {
+        let anchor = self.current_line().cloned();
+        if self.trace_only {
+            self.trace = None;
+            self.trace_only = false;
+            self.set_status_message("Trace correlation off");
+        } else if self.trace.is_some()
+            && anchor
+                .as_ref()
+                .and_then(|m| m.trace_id(self.trace_field.as_ref()))
+                == self.trace.as_deref()
+        {
+            self.trace_only = true;
+            self.set_status_message("Showing only this trace (T again to clear)");
+        } else {
+            let Some(id) = anchor
+                .as_ref()
+                .and_then(|m| m.trace_id(self.trace_field.as_ref()))
+                .map(str::to_string)
+            else {
+                self.trace = None;
+                self.set_status_message(if self.trace_field.is_some() {
+                    "No trace ID on this line"
+                } else {
+                    "Trace correlation is disabled (--trace-field)"
+                });
+                return;
+            };
+            self.set_status_message(format!("Highlighting trace {} (T again to filter)", id));
+            self.trace = Some(id);
+        }
+
+        // Keep the line the trace was picked from under the cursor
+        self.keep_anchor(anchor);
+    }
+
+    /// Show only the search matches, or every line again with `n`/`N` moving
+    /// between the matches in their context
+    pub fn toggle_results_only(&mut self) {
+        if self.search_pattern.is_empty() && !self.results_only {
+            self.set_status_message("Search with / first to show only its matches");
+            return;
+        }
+        let anchor = self.current_line().cloned();
+        self.results_only = !self.results_only;
+        if self.results_only {
+            self.set_status_message("Showing only search matches (& for all lines)");
+        } else {
+            self.set_status_message("Showing all lines");
+        }
+        self.keep_anchor(anchor);
+        self.update_search_matches();
+    }
+
+    /// Show only lines at `level_threshold` or above, or everything again
+    pub fn toggle_level_filter(&mut self) {
+        let anchor = self.current_line().cloned();
+        if self.min_level.take().is_some() {
+            self.set_status_message("Level filter off (e to restore)");
+        } else {
+            self.min_level = Some(self.level_threshold);
+            self.set_status_message(format!(
+                "Showing only {} and above (e to clear)",
+                self.level_threshold.name()
+            ));
+        }
+        self.keep_anchor(anchor);
+    }
+
+    /// Cycle the level filter: all, info, warn, error
+    pub fn cycle_min_level(&mut self) {
+        let anchor = self.current_line().cloned();
+        self.min_level = match self.min_level {
+            None => Some(LogLevel::Info),
+            Some(LogLevel::Info) => Some(LogLevel::Warn),
+            Some(LogLevel::Warn) => Some(LogLevel::Error),
+            Some(_) => None,
+        };
+        match self.min_level {
+            Some(level) => self.set_status_message(format!("Showing {} and above", level.name())),
+            None => self.set_status_message("Level filter off"),
+        }
+        self.keep_anchor(anchor);
+    }
+
+    /// Switch the level filter between keeping and hiding lines without a level
+    pub fn toggle_keep_unleveled(&mut self) {
+        let anchor = self.current_line().cloned();
+        self.keep_unleveled = !self.keep_unleveled;
+        self.set_status_message(if self.keep_unleveled {
+            "Level filter keeps lines without a level"
+        } else {
+            "Level filter hides lines without a level"
+        });
+        self.keep_anchor(anchor);
+    }
+
+    /// After the visible lines changed, scroll back to `anchor` (the line that
+    /// was under the cursor) if it is still shown
+    fn keep_anchor(&mut self, anchor: Option<LogEntry>) {
+        if self.auto_scroll {
+            return;
+        }
+        let filtered = self.filtered_logs();
+        let position = anchor.and_then(|anchor| {
+            filtered.iter().position(|m| {
+                m.timestamp == anchor.timestamp
+                    && m.pod_name == anchor.pod_name
+                    && m.container_name == anchor.container_name
+                    && m.line == anchor.line
+            })
+        });
+        self.scroll_offset = position
+            .unwrap_or(self.scroll_offset)
+            .min(self.max_offset());
+    }
+
+    /// Cluster of the item selected in the sidebar
+    fn selected_cluster(&self) -> Option<String> {
+        let idx = self.sidebar_state.selected()?;
+        match self.sidebar_item_types.get(idx)? {
+            TreeNodeType::Cluster(cluster)
+            | TreeNodeType::Namespace(cluster, _)
+            | TreeNodeType::Workload(cluster, _, _)
+            | TreeNodeType::Pod(cluster, _, _) => Some(cluster.clone()),
+            TreeNodeType::Container => self
+                .sidebar_item_keys
+                .get(idx)?
+                .as_ref()
+                .map(|k| k.cluster.clone()),
+        }
+    }
+
+    /// Freeze the sidebar order, or unfreeze it: re-sort and drop the rows of
+    /// deleted pods
+    pub fn toggle_sidebar_freeze(&mut self) {
+        self.sidebar_dirty = true;
+        if self.frozen_order.take().is_some() {
+            self.set_status_message("Sidebar unfrozen");
+        } else {
+            self.frozen_order = Some(FrozenOrder::new(&self.pods));
+            self.set_status_message(
+                "Sidebar frozen: new pods are added at the bottom, deleted pods stay greyed out",
+            );
+        }
+    }
+
+    /// Mute or unmute the cluster selected in the sidebar. With the sidebar
+    /// hidden, mute the current line's cluster, or unmute all if any are muted.
+    pub fn toggle_cluster_mute(&mut self) {
+        let cluster = if self.sidebar_visible {
+            self.selected_cluster()
+        } else if !self.muted_clusters.is_empty() {
+            self.muted_clusters.clear();
+            self.set_status_message("Unmuted all clusters");
+            return;
+        } else {
+            self.current_line().map(|msg| msg.cluster.clone())
+        };
+        let Some(cluster) = cluster else {
+            return;
+        };
+        if self.muted_clusters.remove(&cluster) {
+            self.set_status_message(format!("Unmuted {}", cluster));
+        } else {
+            self.set_status_message(format!(
+                "Muted {} (still streaming; M again to show its lines)",
+                cluster
+            ));
+            self.muted_clusters.insert(cluster);
+        }
+        // The view changed size under the offset
+        self.scroll_offset = self.scroll_offset.min(self.max_offset());
+    }
+
+    /// Cycle the cluster focus forward or backward through all known clusters,
+    /// passing through "no focus" between the last and first cluster.
+    pub fn cycle_cluster_focus(&mut self, forward: bool) {
+        let mut clusters = self.get_clusters();
+        clusters.sort();
+        if clusters.is_empty() {
+            self.focused_cluster = None;
+            return;
+        }
+
+        let current = self
+            .focused_cluster
+            .as_ref()
+            .and_then(|c| clusters.iter().position(|x| x == c));
+        self.focused_cluster = match (current, forward) {
+            (None, true) => clusters.first().cloned(),
+            (None, false) => clusters.last().cloned(),
+            (Some(i), true) => clusters.get(i + 1).cloned(),
+            (Some(i), false) => i.checked_sub(1).and_then(|i| clusters.get(i).cloned()),
+        };
+
+        // Scroll the sidebar to the focused cluster's subtree
+        if let Some(ref cluster) = self.focused_cluster
+            && let Some(idx) = self
+                .sidebar_item_types
+                .iter()
+                .position(|t| matches!(t, TreeNodeType::Cluster(c) if c == cluster))
+        {
+            self.sidebar_state.select(Some(idx));
+        }
+
+        if self.auto_scroll {
+            self.scroll_to_bottom();
+        } else {
+            self.scroll_to_top();
+        }
+    }
+
     pub fn get_clusters(&self) -> Vec<String> {
         self.pod_states
             .keys()
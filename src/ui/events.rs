@@ -1,14 +1,39 @@
 use crate::types::LogMessage;
-use crate::ui::app::{App, AppMode, PodInfo, PodKey};
+use crate::ui::app::{App, AppMode, OverlayScroll, PodInfo, PodKey};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use futures::StreamExt;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 #[derive(Debug)]
 pub enum AppEvent {
     Key(KeyEvent),
     LogMessage(LogMessage),
-    PodUpdate(PodUpdateEvent),
+    PodUpdate(Box<PodUpdateEvent>),
+    PodWarning(PodWarningEvent),
+    /// A `|` pipe command finished; carries the message to show
+    PipeFinished(String),
+    /// A watcher (e.g. `pods matching app=web in prod/default`) has no
+    /// matching pods, or has some again
+    WatchIdle {
+        watch: String,
+        idle: bool,
+    },
+    /// Every stream ended and no watcher can find more pods, or something
+    /// is being tailed again (e.g. after `r`)
+    NothingLeft(bool),
+    /// A watcher (e.g. `pods matching app=web in default`) failed and is
+    /// being resumed, or is receiving events again
+    WatchReconnecting {
+        cluster: String,
+        watch: String,
+        reconnecting: bool,
+    },
+    /// The topology zone of a node was resolved
+    NodeZone {
+        cluster: String,
+        node: String,
+        zone: String,
+    },
     Tick,
     #[allow(dead_code)]
     Quit,
@@ -20,28 +45,58 @@ pub struct PodUpdateEvent {
     pub event_type: PodEventType,
 }
 
+/// A Warning event reported by the control plane for a pod (--watch-events)
+#[derive(Debug)]
+pub struct PodWarningEvent {
+    pub cluster: String,
+    pub namespace: String,
+    pub pod_name: String,
+    /// Name of the Event object; repeats of the same event update one entry
+    pub event_name: String,
+    pub reason: String,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug)]
 pub enum PodEventType {
     Added,
-    #[allow(dead_code)]
     Updated,
     Deleted(PodKey),
 }
 
-pub async fn event_loop(tx: mpsc::Sender<AppEvent>) {
+/// Forward keys and ticks to the app. While `suspended` is set (an --open-cmd
+/// owns the terminal) the keyboard is not read, so keys reach the command;
+/// `paused` follows it once the keyboard reader is dropped or back.
+pub async fn event_loop(
+    tx: mpsc::Sender<AppEvent>,
+    mut suspended: watch::Receiver<bool>,
+    paused: watch::Sender<bool>,
+) {
     use crossterm::event::EventStream;
 
-    let mut event_stream = EventStream::new();
+    let mut event_stream = Some(EventStream::new());
     let mut tick_interval = tokio::time::interval(std::time::Duration::from_millis(250));
 
     loop {
         tokio::select! {
-            maybe_event = event_stream.next() => {
-                if let Some(Ok(Event::Key(key))) = maybe_event
+            Some(maybe_event) = async { event_stream.as_mut()?.next().await } => {
+                if let Ok(Event::Key(key)) = maybe_event
                     && tx.send(AppEvent::Key(key)).await.is_err() {
                         break;
                     }
             }
+            changed = suspended.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let suspend = *suspended.borrow_and_update();
+                event_stream = if suspend {
+                    None
+                } else {
+                    Some(EventStream::new())
+                };
+                let _ = paused.send(suspend);
+            }
             _ = tick_interval.tick() => {
                 if tx.send(AppEvent::Tick).await.is_err() {
                     break;
@@ -57,6 +112,12 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent) -> bool {
         AppMode::Search => handle_search_mode(app, key),
         AppMode::Filter => handle_filter_mode(app, key),
         AppMode::Help => handle_help_mode(app, key),
+        AppMode::LineDetail | AppMode::Tombstone => handle_line_detail_mode(app, key),
+        AppMode::PhaseHistory => handle_phase_history_mode(app, key),
+        AppMode::Command => handle_command_mode(app, key),
+        AppMode::Note => handle_note_mode(app, key),
+        AppMode::DebugTimeline | AppMode::Hotspots => handle_debug_timeline_mode(app, key),
+        AppMode::ContainerPicker => handle_container_picker_mode(app, key),
     }
 }
 
@@ -67,6 +128,9 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
         | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
             return false;
         }
+        (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+            app.dump_state();
+        }
         (KeyCode::Char('s'), _) => {
             app.sidebar_visible = !app.sidebar_visible;
             if app.sidebar_visible {
@@ -83,18 +147,56 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
             app.clear_logs();
         }
         (KeyCode::Char('a'), _) => {
-            app.auto_scroll = !app.auto_scroll;
+            app.toggle_auto_scroll();
         }
         (KeyCode::Char('t'), _) => {
-            app.show_timestamps = !app.show_timestamps;
+            app.cycle_timestamps();
+        }
+        (KeyCode::F(4), _) => {
+            app.save_logs_timestamped();
+        }
+        (KeyCode::Char('O'), _) => {
+            app.toggle_sort_by_time();
+        }
+        (KeyCode::Char('R'), _) => {
+            app.toggle_reverse_order();
+        }
+        (KeyCode::Char('w'), _) => {
+            app.toggle_fixed_wrap();
+        }
+        (KeyCode::Char('v'), _) => {
+            app.toggle_detail_pane();
+        }
+        (KeyCode::Char('<'), _) => {
+            app.resize_detail_pane(true);
+        }
+        (KeyCode::Char('>'), _) => {
+            app.resize_detail_pane(false);
         }
         (KeyCode::Char('x'), _) => {
             app.show_prefix = !app.show_prefix;
         }
+        (KeyCode::Char('X'), _) => {
+            app.colorize_prefix = !app.colorize_prefix;
+        }
+        (KeyCode::Char('i'), _) => {
+            app.show_image = !app.show_image;
+        }
         (KeyCode::Char('/'), _) => {
             app.mode = AppMode::Search;
             app.search_pattern.clear();
         }
+        (KeyCode::Char('&'), _) => {
+            app.toggle_results_only();
+        }
+        (KeyCode::Char(':'), _) => {
+            app.mode = AppMode::Command;
+            app.command_input.clear();
+        }
+        (KeyCode::Char('A'), _) => {
+            app.mode = AppMode::Note;
+            app.note_input.clear();
+        }
         (KeyCode::Char('f'), _) => {
             // Start filter input mode
             app.mode = AppMode::Filter;
@@ -103,6 +205,7 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
         (KeyCode::Char('?'), _) => {
             app.help_visible = !app.help_visible;
             if app.help_visible {
+                app.overlay_scroll = OverlayScroll::default();
                 app.mode = AppMode::Help;
             }
         }
@@ -112,9 +215,12 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
         (KeyCode::Char('N'), _) => {
             app.jump_to_prev_match();
         }
+        // Newest first flips the view, so up moves to newer lines
         (KeyCode::Up, _) => {
             if app.sidebar_visible {
                 app.sidebar_select_previous();
+            } else if app.reverse_order {
+                app.scroll_down();
             } else {
                 app.scroll_up();
             }
@@ -122,22 +228,90 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
         (KeyCode::Down, _) => {
             if app.sidebar_visible {
                 app.sidebar_select_next();
+            } else if app.reverse_order {
+                app.scroll_up();
             } else {
                 app.scroll_down();
             }
         }
+        (KeyCode::PageUp, _) if app.reverse_order => {
+            app.page_down(20);
+        }
         (KeyCode::PageUp, _) => {
             app.page_up(20);
         }
+        (KeyCode::PageDown, _) if app.reverse_order => {
+            app.page_up(20);
+        }
         (KeyCode::PageDown, _) => {
             app.page_down(20);
         }
+        (KeyCode::Home, _) | (KeyCode::Char('g'), _) if app.reverse_order => {
+            app.scroll_to_bottom();
+        }
         (KeyCode::Home, _) | (KeyCode::Char('g'), _) => {
             app.scroll_to_top();
         }
+        (KeyCode::End, _) | (KeyCode::Char('G'), _) if app.reverse_order => {
+            app.scroll_to_top();
+        }
         (KeyCode::End, _) | (KeyCode::Char('G'), _) => {
             app.scroll_to_bottom();
         }
+        (KeyCode::Char(']'), _) => {
+            app.cycle_cluster_focus(true);
+        }
+        (KeyCode::Char('['), _) => {
+            app.cycle_cluster_focus(false);
+        }
+        (KeyCode::Char('H'), _) => {
+            app.show_phase_history();
+        }
+        (KeyCode::Char('r'), _) => {
+            app.restart_failed_streams();
+        }
+        (KeyCode::Char('C'), _) => {
+            app.open_container_picker();
+        }
+        (KeyCode::Char('D'), _) => {
+            app.show_debug_timeline();
+        }
+        (KeyCode::Char('E'), _) => {
+            app.show_hotspots();
+        }
+        (KeyCode::Char('T'), _) => {
+            app.cycle_trace();
+        }
+        (KeyCode::Char('e'), _) => {
+            app.toggle_level_filter();
+        }
+        (KeyCode::Char('L'), _) => {
+            app.cycle_min_level();
+        }
+        (KeyCode::Char('U'), _) => {
+            app.toggle_keep_unleveled();
+        }
+        (KeyCode::Char('F'), _) => {
+            app.toggle_sidebar_freeze();
+        }
+        (KeyCode::Char('M'), _) => {
+            app.toggle_cluster_mute();
+        }
+        (KeyCode::Char('y'), _) => {
+            app.copy_kubectl_commands();
+        }
+        (KeyCode::Char('m'), _) => {
+            app.toggle_mark();
+        }
+        (KeyCode::Char('|'), _) => {
+            app.pipe_selection();
+        }
+        (KeyCode::Char('o'), _) => {
+            app.open_selected();
+        }
+        (KeyCode::Enter, _) => {
+            app.show_line_detail();
+        }
         (KeyCode::Char(' '), _) if app.sidebar_visible => {
             app.toggle_sidebar_item();
         }
@@ -168,6 +342,48 @@ fn handle_search_mode(app: &mut App, key: KeyEvent) -> bool {
     true
 }
 
+fn handle_command_mode(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.command_input.clear();
+        }
+        KeyCode::Enter => {
+            return app.run_command();
+        }
+        KeyCode::Char(c) => {
+            app.command_input.push(c);
+        }
+        // Backspace on an empty prompt leaves command mode, as in vim
+        KeyCode::Backspace if app.command_input.is_empty() => {
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Backspace => {
+            app.command_input.pop();
+        }
+        _ => {}
+    }
+    true
+}
+
+fn handle_note_mode(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.note_input.clear();
+        }
+        KeyCode::Enter => app.submit_note(),
+        KeyCode::Char(c) => {
+            app.note_input.push(c);
+        }
+        KeyCode::Backspace => {
+            app.note_input.pop();
+        }
+        _ => {}
+    }
+    true
+}
+
 fn handle_filter_mode(app: &mut App, key: KeyEvent) -> bool {
     match key.code {
         KeyCode::Esc => {
@@ -191,8 +407,65 @@ fn handle_filter_mode(app: &mut App, key: KeyEvent) -> bool {
     true
 }
 
-fn handle_help_mode(app: &mut App, _key: KeyEvent) -> bool {
-    app.help_visible = false;
+/// Scroll keys of the help and line detail overlays; false for any other key
+fn scroll_overlay(app: &mut App, key: KeyEvent) -> bool {
+    let scroll = &mut app.overlay_scroll;
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => scroll.up(1),
+        KeyCode::Down | KeyCode::Char('j') => scroll.down(1),
+        KeyCode::PageUp => scroll.up(scroll.page),
+        KeyCode::PageDown => scroll.down(scroll.page),
+        KeyCode::Home | KeyCode::Char('g') => scroll.top(),
+        KeyCode::End | KeyCode::Char('G') => scroll.bottom(),
+        _ => return false,
+    }
+    true
+}
+
+fn handle_help_mode(app: &mut App, key: KeyEvent) -> bool {
+    if !scroll_overlay(app, key) {
+        app.help_visible = false;
+        app.mode = AppMode::Normal;
+    }
+    true
+}
+
+fn handle_line_detail_mode(app: &mut App, key: KeyEvent) -> bool {
+    if !scroll_overlay(app, key) {
+        app.detail_line = None;
+        app.mode = AppMode::Normal;
+    }
+    true
+}
+
+fn handle_debug_timeline_mode(app: &mut App, key: KeyEvent) -> bool {
+    if !scroll_overlay(app, key) {
+        app.mode = AppMode::Normal;
+    }
+    true
+}
+
+fn handle_phase_history_mode(app: &mut App, _key: KeyEvent) -> bool {
     app.mode = AppMode::Normal;
     true
 }
+
+fn handle_container_picker_mode(app: &mut App, key: KeyEvent) -> bool {
+    match (key.code, key.modifiers) {
+        (KeyCode::Esc, _) | (KeyCode::Enter, _) => app.close_container_picker(),
+        (KeyCode::Up, _) => app.picker_move(false),
+        (KeyCode::Down, _) => app.picker_move(true),
+        (KeyCode::Char(' '), _) => app.picker_toggle(),
+        (KeyCode::Char('a'), KeyModifiers::CONTROL) => app.picker_toggle_all(),
+        // Other Ctrl keys (quit, state dump) work as anywhere else
+        (KeyCode::Char(_), modifiers) if modifiers.contains(KeyModifiers::CONTROL) => {
+            return handle_normal_mode(app, key);
+        }
+        (KeyCode::Backspace, _) => app.picker_edit_filter(|f| {
+            f.pop();
+        }),
+        (KeyCode::Char(c), _) => app.picker_edit_filter(|f| f.push(c)),
+        _ => {}
+    }
+    true
+}
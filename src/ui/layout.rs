@@ -3,10 +3,13 @@ use ratatui::layout::{Constraint, Direction, Layout, Rect};
 pub struct AppLayout {
     pub sidebar: Rect,
     pub main: Rect,
+    /// Right of the logs while the detail pane is shown, empty otherwise
+    pub detail: Rect,
     pub status_bar: Rect,
 }
 
-pub fn create_layout(area: Rect, sidebar_visible: bool) -> AppLayout {
+/// `detail_width` is the detail pane's share of the log area in percent, when shown
+pub fn create_layout(area: Rect, sidebar_visible: bool, detail_width: Option<u16>) -> AppLayout {
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -15,7 +18,7 @@ pub fn create_layout(area: Rect, sidebar_visible: bool) -> AppLayout {
         ])
         .split(area);
 
-    if sidebar_visible {
+    let (sidebar, logs) = if sidebar_visible {
         let horizontal = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -23,17 +26,29 @@ pub fn create_layout(area: Rect, sidebar_visible: bool) -> AppLayout {
                 Constraint::Min(1),     // Logs
             ])
             .split(main_chunks[0]);
-
-        AppLayout {
-            sidebar: horizontal[0],
-            main: horizontal[1],
-            status_bar: main_chunks[1],
-        }
+        (horizontal[0], horizontal[1])
     } else {
-        AppLayout {
-            sidebar: Rect::default(),
-            main: main_chunks[0],
-            status_bar: main_chunks[1],
+        (Rect::default(), main_chunks[0])
+    };
+
+    let (main, detail) = match detail_width {
+        Some(width) => {
+            let horizontal = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(100 - width), // Logs
+                    Constraint::Percentage(width),       // Detail pane
+                ])
+                .split(logs);
+            (horizontal[0], horizontal[1])
         }
+        None => (logs, Rect::default()),
+    };
+
+    AppLayout {
+        sidebar,
+        main,
+        detail,
+        status_bar: main_chunks[1],
     }
 }
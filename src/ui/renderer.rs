@@ -1,6 +1,10 @@
-use crate::ui::app::{App, PodInfo};
+use crate::ui::app::{App, SidebarPods};
 use crate::ui::layout::create_layout;
-use crate::ui::widgets::{HelpOverlay, LogView, PodList, StatusBar};
+use crate::ui::widgets::{
+    ContainerPickerOverlay, DebugTimelineOverlay, DetailPane, HelpOverlay, HotspotOverlay,
+    LineDetailOverlay, LogView, LogViewOptions, PhaseHistoryOverlay, PodList, PodListOptions,
+    StatusBar, TombstoneOverlay,
+};
 use ratatui::{Frame, Terminal, backend::Backend};
 
 pub fn render<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> std::io::Result<()> {
@@ -9,69 +13,26 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> std::io:
 }
 
 fn render_frame(f: &mut Frame, app: &mut App) {
-    let layout = create_layout(f.area(), app.sidebar_visible);
+    let layout = create_layout(f.area(), app.sidebar_visible, app.detail_pane_width());
 
     // Render sidebar if visible
     if app.sidebar_visible {
-        // Build mapping from list index to container key
-        use std::collections::BTreeMap;
-        let mut tree: BTreeMap<String, BTreeMap<String, BTreeMap<String, Vec<&PodInfo>>>> =
-            BTreeMap::new();
-
-        for pod in &app.pods {
-            tree.entry(pod.key.cluster.clone())
-                .or_default()
-                .entry(pod.key.namespace.clone())
-                .or_default()
-                .entry(pod.key.pod_name.clone())
-                .or_default()
-                .push(pod);
-        }
-
-        // Build the item keys and types mapping
-        app.sidebar_item_keys.clear();
-        app.sidebar_item_types.clear();
-        for (cluster, namespaces) in &tree {
-            app.sidebar_item_keys.push(None); // Cluster header
-            app.sidebar_item_types
-                .push(crate::ui::app::TreeNodeType::Cluster(cluster.clone()));
-
-            // Only show children if cluster is expanded
-            if app.expanded_nodes.contains(cluster) {
-                for (namespace, pods) in namespaces {
-                    app.sidebar_item_keys.push(None); // Namespace header
-                    app.sidebar_item_types.push(crate::ui::app::TreeNodeType::Namespace(
-                        cluster.clone(),
-                        namespace.clone(),
-                    ));
-
-                    // Only show children if namespace is expanded
-                    let ns_path = format!("{}/{}", cluster, namespace);
-                    if app.expanded_nodes.contains(&ns_path) {
-                        for (pod_name, containers) in pods {
-                            app.sidebar_item_keys.push(None); // Pod header
-                            app.sidebar_item_types.push(crate::ui::app::TreeNodeType::Pod(
-                                cluster.clone(),
-                                namespace.clone(),
-                                pod_name.clone(),
-                            ));
-
-                            // Only show children if pod is expanded
-                            let pod_path = format!("{}/{}/{}", cluster, namespace, pod_name);
-                            if app.expanded_nodes.contains(&pod_path) {
-                                for container in containers {
-                                    app.sidebar_item_keys.push(Some(container.key.clone())); // Container (selectable)
-                                    app.sidebar_item_types
-                                        .push(crate::ui::app::TreeNodeType::Container);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        let pod_list = PodList::new(&app.pods, &app.pod_states, &app.expanded_nodes);
+        app.refresh_sidebar();
+        let pod_list = PodList::new(
+            &app.sidebar_layout,
+            SidebarPods::new(&app.pods, app.frozen_order.as_ref(), &app.tombstones),
+            &app.pod_states,
+            &app.pod_warnings,
+            &app.muted_clusters,
+            &app.environments,
+            &app.reconnecting_watches,
+            PodListOptions {
+                show_image: app.show_image,
+                show_qos: app.show_qos,
+                show_sparklines: app.show_sparklines,
+                stream_states: app.stream_states.as_deref(),
+            },
+        );
         f.render_stateful_widget(pod_list, layout.sidebar, &mut app.sidebar_state);
     }
 
@@ -79,15 +40,39 @@ fn render_frame(f: &mut Frame, app: &mut App) {
     let filtered_logs = app.filtered_logs();
     let log_view = LogView::new(
         filtered_logs,
-        app.scroll_offset,
-        &app.search_pattern,
-        app.show_timestamps,
-        app.show_prefix,
+        LogViewOptions {
+            scroll_offset: app.scroll_offset,
+            search_pattern: &app.search_pattern,
+            show_timestamps: app.show_timestamps,
+            timestamp_style: app.timestamp_style,
+            start_time: app.start_time,
+            show_prefix: app.show_prefix,
+            prefix_format: app.prefix_format.as_ref(),
+            colorize_prefix: app.colorize_prefix,
+            max_line_width: app.max_line_width,
+            follow: app.auto_scroll,
+            highlights: &app.highlights,
+            trace: app.trace.as_deref(),
+            trace_field: app.trace_field.as_ref(),
+            environments: Some(&app.environments),
+            access_logs: app.access_logs.as_deref(),
+            wrap_width: app.fixed_wrap_width(),
+            reversed: app.reverse_order,
+            json_logs: app.json_logs.as_ref(),
+            level_colors: app.level_colors,
+        },
     );
     f.render_widget(log_view, layout.main);
 
+    // Render the detail pane next to the logs
+    if app.detail_pane {
+        let (line, pod) = app.detail_pane_content();
+        f.render_widget(DetailPane::new(line, pod), layout.detail);
+    }
+
     // Render status bar
     let clusters = app.get_clusters();
+    let auto_scroll = app.auto_scroll_indicator();
     let status_bar = StatusBar::new(
         app.running_pods,
         app.total_pods,
@@ -96,13 +81,84 @@ fn render_frame(f: &mut Frame, app: &mut App) {
         &app.active_filters,
         &clusters,
         app.paused,
-        app.auto_scroll,
+        auto_scroll.as_deref(),
+        app.reverse_order,
+        app.focused_cluster.as_deref(),
+        app.status_message.as_ref().map(|(msg, _)| msg.as_str()),
+        app.channel_stats
+            .as_ref()
+            .filter(|_| app.verbose)
+            .map(|stats| stats.high_water()),
+        app.is_flashing(),
+        app.stream_states
+            .as_ref()
+            .map(|states| (states.received_total().0, states.wire_total())),
     );
     f.render_widget(status_bar, layout.status_bar);
 
     // Render help overlay if visible
     if app.help_visible {
-        f.render_widget(HelpOverlay, f.area());
+        f.render_stateful_widget(HelpOverlay, f.area(), &mut app.overlay_scroll);
+    }
+
+    // Render the full text of an expanded line
+    if app.mode == crate::ui::app::AppMode::LineDetail
+        && let Some(msg) = &app.detail_line
+    {
+        f.render_stateful_widget(
+            LineDetailOverlay::new(msg),
+            f.area(),
+            &mut app.overlay_scroll,
+        );
+    }
+
+    // Render the final lines of the selected deleted pod
+    if app.mode == crate::ui::app::AppMode::Tombstone {
+        let tombstones: Vec<_> = app.selected_tombstones().into_iter().cloned().collect();
+        f.render_stateful_widget(
+            TombstoneOverlay::new(&tombstones),
+            f.area(),
+            &mut app.overlay_scroll,
+        );
+    }
+
+    // Render the selected pod's phase timeline
+    if app.mode == crate::ui::app::AppMode::PhaseHistory
+        && let Some(key) = app.selected_pod_key()
+        && let Some(state) = app.pod_states.get(&key)
+    {
+        f.render_widget(
+            PhaseHistoryOverlay::new(&key, &state.phase_history),
+            f.area(),
+        );
+    }
+
+    // Render the watcher/stream debug timeline
+    if app.mode == crate::ui::app::AppMode::DebugTimeline {
+        let (pod, entries) = app.debug_timeline();
+        f.render_stateful_widget(
+            DebugTimelineOverlay::new(pod.as_deref(), &entries),
+            f.area(),
+            &mut app.overlay_scroll,
+        );
+    }
+
+    // Render the error hotspots by node
+    if app.mode == crate::ui::app::AppMode::Hotspots {
+        let hotspots = app.error_hotspots();
+        f.render_stateful_widget(
+            HotspotOverlay::new(&hotspots),
+            f.area(),
+            &mut app.overlay_scroll,
+        );
+    }
+
+    // Render the container popup
+    if app.mode == crate::ui::app::AppMode::ContainerPicker
+        && let Some(picker) = &app.container_picker
+    {
+        let entries = app.picker_entries();
+        f.render_widget(ContainerPickerOverlay::new(picker, &entries), f.area());
     }
 
     // Render search bar if in search mode
@@ -138,6 +194,26 @@ fn render_frame(f: &mut Frame, app: &mut App) {
         f.render_widget(search_widget, search_area);
     }
 
+    // Render the command prompt in command mode
+    if app.mode == crate::ui::app::AppMode::Command {
+        render_prompt(
+            f,
+            format!(":{}_", app.command_input),
+            "Command (filter, grep, clear, save, context, tail, level, quit)",
+            ratatui::style::Color::Green,
+        );
+    }
+
+    // Render the note prompt in note mode
+    if app.mode == crate::ui::app::AppMode::Note {
+        render_prompt(
+            f,
+            format!("{}_", app.note_input),
+            "Note (Enter to insert at the live position, Esc to cancel)",
+            ratatui::style::Color::Magenta,
+        );
+    }
+
     // Render filter bar if in filter mode
     if app.mode == crate::ui::app::AppMode::Filter {
         use ratatui::{
@@ -169,3 +245,27 @@ fn render_frame(f: &mut Frame, app: &mut App) {
         f.render_widget(filter_widget, filter_area);
     }
 }
+
+/// A one-line input prompt in a bordered box along the bottom of the screen
+fn render_prompt(f: &mut Frame, text: String, title: &str, color: ratatui::style::Color) {
+    use ratatui::{
+        layout::{Constraint, Direction, Layout},
+        style::Style,
+        text::Span,
+        widgets::{Block, Borders, Clear, Paragraph},
+    };
+
+    let area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .split(f.area())[1];
+    f.render_widget(Clear, area);
+
+    let widget = Paragraph::new(Span::styled(text, Style::default().fg(color))).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .style(Style::default().fg(color)),
+    );
+    f.render_widget(widget, area);
+}
@@ -1,6 +1,13 @@
-use crate::types::LogMessage;
-use crate::ui::app::{PodInfo, PodKey, PodState};
-use crate::utils::get_color;
+use crate::config::{AccessLogEntry, AccessLogSchema, HighlightRule, parse_access_log};
+use crate::types::{ConnectionState, LogLevel, StreamStates, TimelineEntry};
+use crate::ui::app::{
+    ContainerPicker, Hotspot, LogEntry, OverlayScroll, PodInfo, PodKey, PodState, PodWarnings,
+    SidebarLayout, SidebarPods, SidebarRow, TimestampStyle, Tombstone,
+};
+use crate::utils::{
+    JsonLine, JsonLogFormat, PrefixFormat, env_tag, format_bytes, format_duration_short,
+    format_relative_time, get_color, image_tag, parse_json_line,
+};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
@@ -9,189 +16,310 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap},
 };
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
 pub struct PodList<'a> {
-    pods: &'a [PodInfo],
+    layout: &'a SidebarLayout,
+    pods: SidebarPods<'a>,
     states: &'a HashMap<PodKey, PodState>,
-    expanded_nodes: &'a HashSet<String>,
+    warnings: &'a HashMap<String, PodWarnings>,
+    muted_clusters: &'a HashSet<String>,
+    environments: &'a HashMap<String, String>,
+    reconnecting: &'a HashMap<String, BTreeSet<String>>,
+    options: PodListOptions<'a>,
+}
+
+/// What the sidebar shows next to each pod, taken from the app's settings
+#[derive(Default)]
+pub struct PodListOptions<'a> {
+    pub show_image: bool,
+    pub show_qos: bool,
+    pub show_sparklines: bool,
+    pub stream_states: Option<&'a StreamStates>,
 }
 
 impl<'a> PodList<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        pods: &'a [PodInfo],
+        layout: &'a SidebarLayout,
+        pods: SidebarPods<'a>,
         states: &'a HashMap<PodKey, PodState>,
-        expanded_nodes: &'a HashSet<String>,
+        warnings: &'a HashMap<String, PodWarnings>,
+        muted_clusters: &'a HashSet<String>,
+        environments: &'a HashMap<String, String>,
+        reconnecting: &'a HashMap<String, BTreeSet<String>>,
+        options: PodListOptions<'a>,
     ) -> Self {
         Self {
+            layout,
             pods,
             states,
-            expanded_nodes,
+            warnings,
+            muted_clusters,
+            environments,
+            reconnecting,
+            options,
         }
     }
-}
 
-impl<'a> StatefulWidget for PodList<'a> {
-    type State = ratatui::widgets::ListState;
+    /// Count of enabled containers and total containers in a group
+    fn selection_state<'p>(
+        &self,
+        containers: impl IntoIterator<Item = &'p PodInfo>,
+    ) -> (usize, usize) {
+        containers.into_iter().fold((0, 0), |(enabled, total), c| {
+            let on = self.states.get(&c.key).map(|s| s.enabled).unwrap_or(true);
+            (enabled + on as usize, total + 1)
+        })
+    }
 
-    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        use std::collections::BTreeMap;
-
-        // Group pods by cluster -> namespace -> pod -> containers
-        let mut tree: BTreeMap<String, BTreeMap<String, BTreeMap<String, Vec<&PodInfo>>>> =
-            BTreeMap::new();
-
-        for pod in self.pods {
-            tree.entry(pod.key.cluster.clone())
-                .or_default()
-                .entry(pod.key.namespace.clone())
-                .or_default()
-                .entry(pod.key.pod_name.clone())
-                .or_default()
-                .push(pod);
-        }
-
-        // Helper function to calculate selection state for a group of containers
-        let calc_selection_state = |containers: &[&PodInfo]| -> (usize, usize) {
-            let total = containers.len();
-            let enabled = containers
-                .iter()
-                .filter(|c| {
-                    self.states
-                        .get(&c.key)
-                        .map(|s| s.enabled)
-                        .unwrap_or(true)
-                })
-                .count();
-            (enabled, total)
+    /// Header style by how many of the group's containers are enabled
+    fn header_style(color: Color, (enabled, total): (usize, usize)) -> Style {
+        let color = if total > 0 && enabled == 0 {
+            Color::DarkGray
+        } else if enabled < total {
+            Color::Gray
+        } else {
+            color
         };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// Containers whose tail task gave up (restart with 'r')
+    fn is_failed(&self, key: &PodKey) -> bool {
+        matches!(
+            self.options.stream_states.and_then(|s| s.get(key)),
+            Some(ConnectionState::Failed(_))
+        )
+    }
 
-        // Build flat list with tree structure
-        let mut items: Vec<ListItem> = Vec::new();
-
-        for (cluster, namespaces) in &tree {
-            // Calculate cluster selection state
-            let mut cluster_enabled = 0;
-            let mut cluster_total = 0;
-            for pods in namespaces.values() {
-                for containers in pods.values() {
-                    let (enabled, total) = calc_selection_state(containers);
-                    cluster_enabled += enabled;
-                    cluster_total += total;
+    /// Styled text for one row
+    fn item(&self, row: &SidebarRow) -> ListItem<'static> {
+        let icon = |expanded: bool| if expanded { "▼" } else { "▶" };
+        match *row {
+            SidebarRow::Cluster {
+                cluster,
+                containers,
+                expanded,
+            } => {
+                let env = self
+                    .environments
+                    .get(cluster)
+                    .map(|env| format!(" [{}]", env))
+                    .unwrap_or_default();
+                // Dimmed while muted
+                if self.muted_clusters.contains(cluster) {
+                    return ListItem::new(format!("{} {}{} (muted)", icon(expanded), cluster, env))
+                        .style(
+                            Style::default()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::DIM),
+                        );
                 }
+                let state = self.selection_state(containers.iter());
+                // A watch failed and is being resumed: new pods may be missed
+                if self.reconnecting.contains_key(cluster) {
+                    return ListItem::new(format!(
+                        "{} {}{} ↻ reconnecting",
+                        icon(expanded),
+                        cluster,
+                        env
+                    ))
+                    .style(Self::header_style(Color::Yellow, state));
+                }
+                ListItem::new(format!("{} {}{}", icon(expanded), cluster, env))
+                    .style(Self::header_style(Color::Cyan, state))
             }
+            SidebarRow::Namespace {
+                namespace,
+                containers,
+                expanded,
+                ..
+            } => {
+                let state = self.selection_state(containers.iter());
+                ListItem::new(format!("  {} {}", icon(expanded), namespace))
+                    .style(Self::header_style(Color::Blue, state))
+            }
+            SidebarRow::Workload {
+                workload,
+                pod_count,
+                expanded,
+                ..
+            } => ListItem::new(format!(
+                "    {} {} ({} pods)",
+                icon(expanded),
+                workload,
+                pod_count
+            ))
+            .style(Style::default().fg(Color::Magenta)),
+            SidebarRow::Pod {
+                cluster,
+                namespace,
+                pod_name,
+                containers,
+                grouped,
+                expanded,
+            } => {
+                // Pods under a workload header are indented one more level
+                let indent = if grouped { "  " } else { "" };
+                let (pod_enabled, pod_total) = self.selection_state(containers.iter());
+                let first = containers.first();
 
-            // Determine cluster style based on selection state
-            let cluster_style = if cluster_total == 0 {
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
-            } else if cluster_enabled == 0 {
-                Style::default()
-                    .fg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD)
-            } else if cluster_enabled < cluster_total {
-                Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
-            };
-
-            // Cluster header
-            let cluster_expanded = self.expanded_nodes.contains(cluster);
-            let cluster_icon = if cluster_expanded { "▼" } else { "▶" };
-            items.push(
-                ListItem::new(format!("{} {}", cluster_icon, cluster)).style(cluster_style),
-            );
+                // Kept in place by a frozen sidebar after the pod was deleted
+                let gone = !self.states.contains_key(&first.key);
+                let pod_style = if first.skipped || gone {
+                    Style::default().fg(Color::DarkGray)
+                } else if pod_total == 0 {
+                    Style::default().fg(Color::Green)
+                } else if pod_enabled == 0 {
+                    Style::default().fg(Color::DarkGray)
+                } else if pod_enabled < pod_total {
+                    Style::default().fg(Color::Gray)
+                } else {
+                    Style::default().fg(Color::Green)
+                };
 
-            // Only show children if cluster is expanded
-            if cluster_expanded {
-                for (namespace, pods) in namespaces {
-                    // Calculate namespace selection state
-                    let mut ns_enabled = 0;
-                    let mut ns_total = 0;
-                    for containers in pods.values() {
-                        let (enabled, total) = calc_selection_state(containers);
-                        ns_enabled += enabled;
-                        ns_total += total;
+                let phase = &first.phase;
+                let pod_path = format!("{}/{}/{}", cluster, namespace, pod_name);
+                // Age is recomputed on every render so it stays current
+                let status = match first.created {
+                    Some(created) => format!(
+                        "{}, {}",
+                        phase,
+                        format_duration_short(chrono::Utc::now() - created)
+                    ),
+                    None => phase.clone(),
+                };
+                let mut pod_text =
+                    format!("    {}{} {} ({})", indent, icon(expanded), pod_name, status);
+                // Image tag of the first container being shown, useful during rollouts
+                let shown = containers
+                    .iter()
+                    .find(|c| self.states.get(&c.key).is_none_or(|s| s.enabled))
+                    .unwrap_or(first);
+                if self.options.show_image
+                    && let Some(image) = &shown.image
+                {
+                    pod_text.push_str(&format!(" @{}", image_tag(image)));
+                }
+                // QoS class and priority, for eviction debugging
+                if self.options.show_qos {
+                    match (&first.qos_class, first.priority) {
+                        (Some(qos), Some(priority)) => {
+                            pod_text.push_str(&format!(" [{} p={}]", qos, priority))
+                        }
+                        (Some(qos), None) => pod_text.push_str(&format!(" [{}]", qos)),
+                        (None, Some(priority)) => pod_text.push_str(&format!(" [p={}]", priority)),
+                        (None, None) => {}
                     }
+                }
+                if first.skipped {
+                    pod_text.push_str(" [skipped]");
+                }
+                if gone {
+                    pod_text.push_str(" [gone]");
+                }
+                let mut pod_line = vec![Span::raw(pod_text)];
+                if containers.iter().any(|c| self.is_failed(&c.key)) {
+                    pod_line.push(Span::styled(" ✗", Style::default().fg(Color::Red)));
+                }
+                if let Some(badge) = self.warnings.get(&pod_path).and_then(|w| w.badge()) {
+                    pod_line.push(Span::styled(
+                        format!(" {}", badge),
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+                ListItem::new(Line::from(pod_line)).style(pod_style)
+            }
+            SidebarRow::Container { info, grouped } => {
+                let indent = if grouped { "  " } else { "" };
+                let state = self.states.get(&info.key);
+                let enabled = state.map(|s| s.enabled).unwrap_or(true);
+                let checkbox = if enabled { "[x]" } else { "[ ]" };
+                let mut text = format!("      {}{} {}", indent, checkbox, info.key.container_name);
+                if let Some(label) = info.kind.label() {
+                    text.push_str(&format!(" {}", label));
+                }
 
-                    let ns_style = if ns_total == 0 {
-                        Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)
-                    } else if ns_enabled == 0 {
-                        Style::default()
-                            .fg(Color::DarkGray)
-                            .add_modifier(Modifier::BOLD)
-                    } else if ns_enabled < ns_total {
-                        Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)
-                    };
-
-                    // Namespace header (indented)
-                    let ns_path = format!("{}/{}", cluster, namespace);
-                    let ns_expanded = self.expanded_nodes.contains(&ns_path);
-                    let ns_icon = if ns_expanded { "▼" } else { "▶" };
-                    items.push(
-                        ListItem::new(format!("  {} {}", ns_icon, namespace)).style(ns_style),
-                    );
-
-                    // Only show children if namespace is expanded
-                    if ns_expanded {
-                        for (pod_name, containers) in pods {
-                            // Calculate pod selection state
-                            let (pod_enabled, pod_total) = calc_selection_state(containers);
-
-                            let pod_style = if pod_total == 0 {
-                                Style::default().fg(Color::Green)
-                            } else if pod_enabled == 0 {
-                                Style::default().fg(Color::DarkGray)
-                            } else if pod_enabled < pod_total {
-                                Style::default().fg(Color::Gray)
-                            } else {
-                                Style::default().fg(Color::Green)
-                            };
-
-                            // Pod header (indented more)
-                            let phase = &containers[0].phase;
-                            let pod_path = format!("{}/{}/{}", cluster, namespace, pod_name);
-                            let pod_expanded = self.expanded_nodes.contains(&pod_path);
-                            let pod_icon = if pod_expanded { "▼" } else { "▶" };
-                            items.push(
-                                ListItem::new(format!(
-                                    "    {} {} ({})",
-                                    pod_icon, pod_name, phase
-                                ))
-                                .style(pod_style),
-                            );
-
-                            // Only show children if pod is expanded
-                            if pod_expanded {
-                                // Containers (indented most)
-                                for container in containers {
-                                    let enabled = self
-                                        .states
-                                        .get(&container.key)
-                                        .map(|s| s.enabled)
-                                        .unwrap_or(true);
-                                    let checkbox = if enabled { "[x]" } else { "[ ]" };
-
-                                    let text = format!(
-                                        "      {} {}",
-                                        checkbox, container.key.container_name
-                                    );
-
-                                    let style = if enabled {
-                                        Style::default()
-                                    } else {
-                                        Style::default().fg(Color::DarkGray)
-                                    };
-
-                                    items.push(ListItem::new(text).style(style));
-                                }
-                            }
-                        }
+                let style = if enabled && state.is_some() {
+                    Style::default()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+
+                let mut line = vec![Span::raw(text)];
+                if self.options.show_sparklines
+                    && let Some(state) = state
+                {
+                    line.push(Span::styled(
+                        format!(" {}", state.activity.sparkline()),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                match self.options.stream_states.and_then(|s| s.get(&info.key)) {
+                    Some(ConnectionState::Failed(status)) => {
+                        line.push(Span::styled(
+                            format!(" ✗ {}", status),
+                            Style::default().fg(Color::Red),
+                        ));
                     }
+                    Some(ConnectionState::Reconnecting(Some(status))) => {
+                        line.push(Span::styled(
+                            format!(" ↻ {}", status),
+                            Style::default().fg(Color::Yellow),
+                        ));
+                    }
+                    _ => {}
                 }
+                ListItem::new(Line::from(line)).style(style)
             }
         }
+    }
+}
+
+/// First row to show so that `selected` stays on screen, given the previous
+/// offset, the number of rows and the visible height
+pub fn sidebar_window_offset(
+    offset: usize,
+    selected: Option<usize>,
+    len: usize,
+    height: usize,
+) -> usize {
+    // Never leave blank space below the last row, e.g. after a collapse
+    let mut offset = offset.min(len.saturating_sub(height));
+    if let Some(selected) = selected.filter(|&s| s < len) {
+        if selected < offset {
+            offset = selected;
+        } else if selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+    }
+    offset
+}
+
+impl<'a> StatefulWidget for PodList<'a> {
+    type State = ratatui::widgets::ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        // Only the rows on screen are styled; selection and offset stay in
+        // whole-list indices and are translated to the window for List
+        let height = (area.height as usize).max(1);
+        let len = self.layout.row_count();
+        let offset = sidebar_window_offset(state.offset(), state.selected(), len, height);
+        let end = (offset + height).min(len);
+        let items: Vec<ListItem> = self
+            .layout
+            .rows(self.pods, offset..end)
+            .map(|row| self.item(&row))
+            .collect();
+        *state.offset_mut() = offset;
+
+        let mut window_state = ratatui::widgets::ListState::default().with_selected(
+            state
+                .selected()
+                .filter(|s| (offset..end).contains(s))
+                .map(|s| s - offset),
+        );
 
         let list = List::new(items)
             .block(Block::default().borders(Borders::RIGHT))
@@ -203,91 +331,306 @@ impl<'a> StatefulWidget for PodList<'a> {
             )
             .highlight_symbol("→ ");
 
-        StatefulWidget::render(list, area, buf, state);
+        StatefulWidget::render(list, area, buf, &mut window_state);
     }
 }
 
 pub struct LogView<'a> {
-    logs: Vec<&'a LogMessage>,
-    scroll_offset: usize,
-    search_pattern: &'a str,
-    show_timestamps: bool,
-    show_prefix: bool,
+    logs: Vec<&'a LogEntry>,
+    options: LogViewOptions<'a>,
 }
 
-impl<'a> LogView<'a> {
-    pub fn new(
-        logs: Vec<&'a LogMessage>,
-        scroll_offset: usize,
-        search_pattern: &'a str,
-        show_timestamps: bool,
-        show_prefix: bool,
-    ) -> Self {
-        Self {
-            logs,
-            scroll_offset,
-            search_pattern,
-            show_timestamps,
-            show_prefix,
+/// How the log view shows its lines, taken from the app's settings
+#[derive(Default)]
+pub struct LogViewOptions<'a> {
+    pub scroll_offset: usize,
+    pub search_pattern: &'a str,
+    pub show_timestamps: bool,
+    pub timestamp_style: TimestampStyle,
+    /// Zero of relative timestamps
+    pub start_time: chrono::DateTime<chrono::Utc>,
+    pub show_prefix: bool,
+    /// --prefix-format, replacing the default prefix
+    pub prefix_format: Option<&'a PrefixFormat>,
+    pub colorize_prefix: bool,
+    pub max_line_width: Option<usize>,
+    pub follow: bool,
+    pub highlights: &'a [HighlightRule],
+    /// The trace being followed, and the --trace-field that finds it in lines
+    pub trace: Option<&'a str>,
+    pub trace_field: Option<&'a Regex>,
+    /// Environment of each cluster, tagged on prefixes
+    pub environments: Option<&'a HashMap<String, String>>,
+    pub access_logs: Option<&'a [AccessLogSchema]>,
+    pub wrap_width: Option<u16>,
+    /// Newest line at the top, older lines below it
+    pub reversed: bool,
+    /// --parse json
+    pub json_logs: Option<&'a JsonLogFormat>,
+    /// Color lines by their detected level (off with --no-level-colors)
+    pub level_colors: bool,
+}
+
+/// Split `text` into spans styled by search matches and highlight rules. Search
+/// matches take precedence, then rules in order; a later match never restyles
+/// text that an earlier one already claimed.
+pub fn highlight_spans<'b>(
+    text: &'b str,
+    search: Option<&Regex>,
+    rules: &[HighlightRule],
+) -> Vec<Span<'b>> {
+    let search_style = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    // Style claimed by each byte of the line, if any
+    let mut claimed: Vec<Option<Style>> = vec![None; text.len()];
+    let matchers = search
+        .map(|re| (re, search_style))
+        .into_iter()
+        .chain(rules.iter().map(|r| (&r.regex, r.style)));
+    for (regex, style) in matchers {
+        for mat in regex.find_iter(text) {
+            if claimed[mat.range()].iter().all(Option::is_none) {
+                claimed[mat.range()].fill(Some(style));
+            }
         }
     }
 
-    fn format_log_line<'b>(&self, msg: &'b LogMessage) -> Line<'b> {
-        let color_key = format!("{}/{}", msg.cluster, msg.pod_name);
-        let color = get_color(&color_key);
+    // Match boundaries are char boundaries, so slicing at style changes is safe
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for i in 1..=text.len() {
+        if i == text.len() || claimed[i] != claimed[start] {
+            let chunk = &text[start..i];
+            spans.push(match claimed[start] {
+                Some(style) => Span::styled(chunk, style),
+                None => Span::raw(chunk),
+            });
+            start = i;
+        }
+    }
+    spans
+}
+
+/// An access-log line as `METHOD STATUS DURATION PATH` columns, the status
+/// colored by class
+fn access_log_spans(
+    entry: AccessLogEntry,
+    search: Option<&Regex>,
+    rules: &[HighlightRule],
+) -> Vec<Span<'static>> {
+    let status_color = match entry.status {
+        200..=299 => Color::Green,
+        300..=399 => Color::Cyan,
+        400..=499 => Color::Yellow,
+        500..=599 => Color::Red,
+        _ => Color::Gray,
+    };
+    let mut spans = vec![
+        Span::styled(
+            format!("{:<7} ", entry.method),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("{:>3} ", entry.status),
+            Style::default().fg(status_color),
+        ),
+        Span::styled(
+            format!("{:>8} ", entry.duration.unwrap_or_default()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    spans.extend(
+        highlight_spans(&entry.path, search, rules)
+            .into_iter()
+            .map(|span| Span::styled(span.content.into_owned(), span.style)),
+    );
+    spans
+}
+
+/// A --parse json line as `time LEVEL message key=value …`: the level colored
+/// by severity, the time and other fields dimmed
+fn json_line_spans(
+    entry: JsonLine,
+    search: Option<&Regex>,
+    rules: &[HighlightRule],
+) -> Vec<Span<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut spans = Vec::new();
+    if let Some(time) = &entry.time {
+        spans.push(Span::styled(format!("{} ", time), dim));
+    }
+    if let Some(level) = &entry.level {
+        let color = entry.severity().map_or(Color::Reset, |l| l.color());
+        spans.push(Span::styled(
+            format!("{} ", level.to_uppercase()),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.extend(
+        highlight_spans(&entry.message, search, rules)
+            .into_iter()
+            .map(|span| Span::styled(span.content.into_owned(), span.style)),
+    );
+    for field in &entry.fields {
+        spans.push(Span::styled(format!(" {}", field), dim));
+    }
+    spans
+}
+
+impl<'a> LogView<'a> {
+    pub fn new(logs: Vec<&'a LogEntry>, options: LogViewOptions<'a>) -> Self {
+        Self { logs, options }
+    }
+
+    /// `prev` is the line shown above `msg`, if any
+    fn format_log_line<'b>(&self, msg: &'b LogEntry, prev: Option<&LogEntry>) -> Line<'b> {
+        let color = if self.options.colorize_prefix {
+            let color_key = format!("{}/{}", msg.cluster, msg.pod_name);
+            get_color(&color_key)
+        } else {
+            Color::Gray
+        };
 
         let mut spans = Vec::new();
 
         // Add timestamp if enabled
-        if self.show_timestamps {
-            let ts = msg.timestamp.format("%H:%M:%S%.3f").to_string();
+        if self.options.show_timestamps {
+            let same_second =
+                prev.is_some_and(|prev| prev.timestamp.timestamp() == msg.timestamp.timestamp());
+            let ts = match self.options.timestamp_style {
+                // Right-aligned under the milliseconds of the full time
+                TimestampStyle::Compact if same_second => {
+                    format!("{:>12}", msg.timestamp.format("%.3f"))
+                }
+                TimestampStyle::Relative => {
+                    format_relative_time(msg.timestamp - self.options.start_time)
+                }
+                _ => msg.timestamp.format("%H:%M:%S%.3f").to_string(),
+            };
             spans.push(Span::styled(
                 format!("{} ", ts),
                 Style::default().fg(Color::DarkGray),
             ));
         }
 
+        // Notes belong to no pod: no prefix, and styled to stand out
+        if msg.is_note() {
+            spans.push(Span::styled(
+                format!("✎ {}", msg.line),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            return Line::from(spans);
+        }
+
         // Add prefix if enabled: [cluster.namespace/pod/container]
-        if self.show_prefix {
-            let prefix = format!(
-                "[{}.{}/{}/{}]",
-                msg.cluster, msg.namespace, msg.pod_name, msg.container_name
-            );
+        if self.options.show_prefix {
+            let prefix = match self.options.prefix_format {
+                Some(format) => format.render(msg),
+                None => format!(
+                    "[{}{}.{}/{}/{}{}]",
+                    env_tag(
+                        self.options
+                            .environments
+                            .and_then(|envs| envs.get(&msg.cluster))
+                            .map(String::as_str),
+                    ),
+                    msg.cluster,
+                    msg.namespace,
+                    msg.pod_name,
+                    msg.container_name,
+                    if msg.previous { " (previous)" } else { "" }
+                ),
+            };
             spans.push(Span::styled(prefix, Style::default().fg(color)));
             spans.push(Span::raw(" "));
         }
 
-        // Add log line with highlighting if search pattern is active
-        if !self.search_pattern.is_empty() {
-            // Make search case-insensitive by default (prepend (?i))
-            let pattern = format!("(?i){}", self.search_pattern);
-            if let Ok(regex) = Regex::new(&pattern) {
-                let mut last_end = 0;
-                for mat in regex.find_iter(&msg.line) {
-                    // Add text before match
-                    if mat.start() > last_end {
-                        spans.push(Span::raw(&msg.line[last_end..mat.start()]));
-                    }
-                    // Add highlighted match
-                    spans.push(Span::styled(
-                        mat.as_str(),
-                        Style::default()
-                            .fg(Color::Black)
-                            .bg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
-                    ));
-                    last_end = mat.end();
-                }
-                // Add remaining text
-                if last_end < msg.line.len() {
-                    spans.push(Span::raw(&msg.line[last_end..]));
-                }
-            } else {
-                // Invalid regex, just show the line
-                spans.push(Span::raw(&msg.line));
-            }
+        // Truncate overly long lines to `width` characters; the marker counts
+        // the bytes cut. The full text stays in the buffer.
+        let (text, elided) = match self.options.max_line_width {
+            Some(width) => match msg.line.char_indices().nth(width) {
+                Some((cut, _)) => (&msg.line[..cut], msg.line.len() - cut),
+                None => (msg.line.as_str(), 0),
+            },
+            None => (msg.line.as_str(), 0),
+        };
+
+        // Add log line with search matches and configured keywords highlighted.
+        // Make search case-insensitive by default (prepend (?i)); an invalid
+        // search regex just disables search highlighting.
+        let search = if self.options.search_pattern.is_empty() {
+            None
+        } else {
+            Regex::new(&format!("(?i){}", self.options.search_pattern)).ok()
+        };
+        let access_log = self
+            .options
+            .access_logs
+            .filter(|_| msg.elided.is_empty())
+            .and_then(|schemas| parse_access_log(&msg.line, schemas));
+        let json_line = self
+            .options
+            .json_logs
+            .filter(|_| access_log.is_none() && msg.elided.is_empty())
+            .and_then(|json| parse_json_line(&msg.line, json.message_key.as_deref()));
+        let columns = access_log.is_some() || json_line.is_some();
+        if let Some(entry) = access_log {
+            // The columns stand in for the whole JSON line, so no elision marker
+            spans.extend(access_log_spans(
+                entry,
+                search.as_ref(),
+                self.options.highlights,
+            ));
+        } else if let Some(entry) = json_line {
+            spans.extend(json_line_spans(
+                entry,
+                search.as_ref(),
+                self.options.highlights,
+            ));
+        } else if msg.elided.is_empty() {
+            // Search and keyword highlights keep their own colors
+            let level_color = msg
+                .level
+                .filter(|_| self.options.level_colors)
+                .and_then(LogLevel::line_color);
+            spans.extend(
+                highlight_spans(text, search.as_ref(), self.options.highlights)
+                    .into_iter()
+                    .map(|span| match level_color {
+                        Some(color) => {
+                            let style = Style::default().fg(color).patch(span.style);
+                            span.style(style)
+                        }
+                        None => span,
+                    }),
+            );
         } else {
-            spans.push(Span::raw(&msg.line));
+            spans.push(Span::styled(
+                text,
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            ));
+        }
+
+        if elided > 0 && !columns {
+            spans.push(Span::styled(
+                format!("…(+{} bytes)", elided),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+
+        // Mark every line of the trace being followed
+        if self.options.trace.is_some()
+            && msg.trace_id(self.options.trace_field) == self.options.trace
+        {
+            spans.insert(0, Span::styled("┃ ", Style::default().fg(Color::Magenta)));
         }
 
         Line::from(spans)
@@ -296,17 +639,393 @@ impl<'a> LogView<'a> {
 
 impl<'a> Widget for LogView<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let lines: Vec<Line> = self
-            .logs
+        let height = area.height as usize;
+        if self.logs.is_empty() || height == 0 {
+            return;
+        }
+
+        // Only format the lines that can be on screen. Every line takes at least
+        // one row, so a screenful of lines is always enough to fill the view.
+        // `anchor` is the top line: the oldest in view, or the newest if reversed.
+        let anchor = if self.options.follow {
+            if self.options.reversed {
+                self.logs.len() - 1
+            } else {
+                self.logs.len().saturating_sub(height)
+            }
+        } else {
+            self.options.scroll_offset.min(self.logs.len() - 1)
+        };
+        // Indices of the lines in view from the top, and of the line above them
+        let (indices, above): (Vec<usize>, Option<usize>) = if self.options.reversed {
+            let oldest = (anchor + 1).saturating_sub(height);
+            let above = Some(anchor + 1).filter(|&i| i < self.logs.len());
+            ((oldest..=anchor).rev().collect(), above)
+        } else {
+            let end = (anchor + height).min(self.logs.len());
+            ((anchor..end).collect(), anchor.checked_sub(1))
+        };
+        // Wrap up front so the rows the lines take are known: at the pane edge,
+        // or at --wrap-width if that is narrower
+        let wrap_width = self
+            .options
+            .wrap_width
+            .map_or(area.width, |width| width.min(area.width)) as usize;
+        let mut lines: Vec<Line> = Vec::with_capacity(indices.len());
+        // Times are shown without a date, so mark where the day changes,
+        // including between the line above the view and the first one in it
+        let mut day = above.map(|i| self.logs[i].timestamp.date_naive());
+        // The first line in view always gets the full time
+        let mut prev: Option<&LogEntry> = None;
+        for i in indices {
+            let msg = self.logs[i];
+            if self.options.show_timestamps {
+                let date = msg.timestamp.date_naive();
+                if day.is_some_and(|day| day != date) {
+                    lines.push(day_separator(date));
+                }
+                day = Some(date);
+            }
+            let line = self.format_log_line(msg, prev);
+            prev = Some(msg);
+            lines.extend(wrap_at(line, wrap_width));
+        }
+
+        let rows = lines.len();
+        let mut paragraph = Paragraph::new(lines);
+        if self.options.follow && !self.options.reversed {
+            // Keep the newest line on the bottom row when older lines wrap
+            let overflow = rows.saturating_sub(height);
+            paragraph = paragraph.scroll((overflow.min(u16::MAX as usize) as u16, 0));
+        }
+
+        paragraph.render(area, buf);
+    }
+}
+
+/// Break `line` into rows of at most `width` columns, keeping span styles.
+pub fn wrap_at(line: Line<'_>, width: usize) -> Vec<Line<'static>> {
+    let mut rows: Vec<Vec<Span<'static>>> = vec![Vec::new()];
+    let mut used = 0;
+    for grapheme in line.styled_graphemes(Style::default()) {
+        let columns = Span::raw(grapheme.symbol).width();
+        if used > 0 && used + columns > width {
+            rows.push(Vec::new());
+            used = 0;
+        }
+        used += columns;
+        let row = rows.last_mut().unwrap();
+        match row.last_mut() {
+            Some(span) if span.style == grapheme.style => {
+                span.content.to_mut().push_str(grapheme.symbol)
+            }
+            _ => row.push(Span::styled(grapheme.symbol.to_string(), grapheme.style)),
+        }
+    }
+    rows.into_iter().map(Line::from).collect()
+}
+
+/// Dim line inserted where consecutive log lines fall on different days
+pub fn day_separator(date: chrono::NaiveDate) -> Line<'static> {
+    Line::styled(
+        format!("──── {} ────", date.format("%Y-%m-%d")),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::DIM),
+    )
+}
+
+pub struct LineDetailOverlay<'a> {
+    msg: &'a LogEntry,
+}
+
+impl<'a> LineDetailOverlay<'a> {
+    pub fn new(msg: &'a LogEntry) -> Self {
+        Self { msg }
+    }
+}
+
+impl<'a> StatefulWidget for LineDetailOverlay<'a> {
+    type State = OverlayScroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        // Leave a small margin around the overlay
+        let detail_area = Rect {
+            x: area.x + area.width / 10,
+            y: area.y + area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+
+        // Elided stack frames are shown in full, one per row
+        let (size, lines) = if self.msg.elided.is_empty() {
+            (
+                format!("{} bytes", self.msg.line.len()),
+                vec![Line::from(self.msg.line.as_str())],
+            )
+        } else {
+            (
+                format!("{} frames", self.msg.elided.len()),
+                self.msg
+                    .elided
+                    .iter()
+                    .map(|frame| Line::from(frame.as_str()))
+                    .collect(),
+            )
+        };
+        let title = format!(
+            "[{}.{}/{}/{}] {} (↑/↓ scroll, any other key closes)",
+            self.msg.cluster, self.msg.namespace, self.msg.pod_name, self.msg.container_name, size
+        );
+        ScrollOverlay::new(title, lines, true).render(detail_area, buf, state);
+    }
+}
+
+/// The current line in full and details of the selected pod, right of the
+/// logs (v). JSON lines are shown as an indented tree.
+pub struct DetailPane<'a> {
+    line: Option<&'a LogEntry>,
+    pod: Option<&'a PodInfo>,
+}
+
+impl<'a> DetailPane<'a> {
+    pub fn new(line: Option<&'a LogEntry>, pod: Option<&'a PodInfo>) -> Self {
+        Self { line, pod }
+    }
+}
+
+impl<'a> Widget for DetailPane<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        };
+        let dim = Style::default().fg(Color::DarkGray);
+        let field = |name: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{:<10}", name), dim),
+                Span::raw(value),
+            ])
+        };
+
+        let mut lines = vec![heading("Line")];
+        match self.line {
+            Some(msg) => {
+                if !msg.is_note() {
+                    lines.push(Line::styled(
+                        format!(
+                            "{}.{}/{}/{}",
+                            msg.cluster, msg.namespace, msg.pod_name, msg.container_name
+                        ),
+                        dim,
+                    ));
+                }
+                lines.push(Line::styled(msg.timestamp.to_rfc3339(), dim));
+                if !msg.elided.is_empty() {
+                    lines.extend(msg.elided.iter().map(|frame| Line::from(frame.as_str())));
+                } else {
+                    match serde_json::from_str::<serde_json::Value>(&msg.line) {
+                        Ok(value) if value.is_object() || value.is_array() => {
+                            let pretty = serde_json::to_string_pretty(&value).unwrap_or_default();
+                            lines.extend(pretty.lines().map(|l| Line::from(l.to_string())));
+                        }
+                        _ => lines.push(Line::from(msg.line.as_str())),
+                    }
+                }
+            }
+            None => lines.push(Line::styled("No lines yet", dim)),
+        }
+
+        if let Some(pod) = self.pod {
+            lines.push(Line::default());
+            lines.push(heading("Pod"));
+            lines.push(field("name", pod.key.pod_name.clone()));
+            let container = match pod.kind.label() {
+                Some(label) => format!("{} {}", pod.key.container_name, label),
+                None => pod.key.container_name.clone(),
+            };
+            lines.push(field("container", container));
+            lines.push(field(
+                "context",
+                format!("{} / {}", pod.key.cluster, pod.key.namespace),
+            ));
+            let status = match pod.created {
+                Some(created) => format!(
+                    "{}, {} old",
+                    pod.phase,
+                    format_duration_short(chrono::Utc::now() - created)
+                ),
+                None => pod.phase.clone(),
+            };
+            lines.push(field("phase", status));
+            lines.push(field("restarts", pod.restarts.to_string()));
+            if let Some(image) = &pod.image {
+                lines.push(field("image", image.clone()));
+            }
+            if let Some(workload) = &pod.workload {
+                lines.push(field("workload", workload.clone()));
+            }
+            if let Some(node) = &pod.node {
+                let node = match &pod.zone {
+                    Some(zone) => format!("{} ({})", node, zone),
+                    None => node.clone(),
+                };
+                lines.push(field("node", node));
+            }
+            if let Some(resources) = &pod.resources {
+                lines.push(field("resources", resources.clone()));
+            }
+            if let Some(qos) = &pod.qos_class {
+                lines.push(field("qos", qos.clone()));
+            }
+            if let Some(priority) = pod.priority {
+                lines.push(field("priority", priority.to_string()));
+            }
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::LEFT)
+                    .title("Detail (v close, </> resize)"),
+            )
+            .render(area, buf);
+    }
+}
+
+/// Final lines of a deleted pod's containers (--tombstones), merged by time
+pub struct TombstoneOverlay<'a> {
+    tombstones: &'a [Tombstone],
+}
+
+impl<'a> TombstoneOverlay<'a> {
+    pub fn new(tombstones: &'a [Tombstone]) -> Self {
+        Self { tombstones }
+    }
+}
+
+impl<'a> StatefulWidget for TombstoneOverlay<'a> {
+    type State = OverlayScroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let Some(first) = self.tombstones.first() else {
+            return;
+        };
+        let detail_area = Rect {
+            x: area.x + area.width / 10,
+            y: area.y + area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+
+        let mut msgs: Vec<&LogEntry> = self.tombstones.iter().flat_map(|t| &t.lines).collect();
+        msgs.sort_by(|a, b| a.time_order(b));
+        let several = self.tombstones.len() > 1;
+        let mut lines: Vec<Line> = msgs
             .iter()
-            .map(|msg| self.format_log_line(msg))
+            .map(|msg| {
+                if several {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("[{}] ", msg.container_name),
+                            Style::default().fg(get_color(&msg.container_name)),
+                        ),
+                        Span::raw(msg.line.as_str()),
+                    ])
+                } else {
+                    Line::from(msg.line.as_str())
+                }
+            })
             .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("(no lines in the buffer)"));
+        }
 
+        let key = &first.info.key;
+        let title = format!(
+            "[{}.{}/{}] deleted {} ago, last {} lines (↑/↓ scroll, any other key closes)",
+            key.cluster,
+            key.namespace,
+            key.pod_name,
+            format_duration_short(chrono::Utc::now() - first.removed_at),
+            msgs.len()
+        );
+        ScrollOverlay::new(title, lines, true).render(detail_area, buf, state);
+    }
+}
+
+/// Bordered popup whose content scrolls (Up/Down/PgUp/PgDn/Home/End) instead of
+/// being clipped when it does not fit, e.g. on an 80x24 terminal
+pub struct ScrollOverlay<'a> {
+    title: String,
+    lines: Vec<Line<'a>>,
+    wrap: bool,
+}
+
+impl<'a> ScrollOverlay<'a> {
+    pub fn new(title: impl Into<String>, lines: Vec<Line<'a>>, wrap: bool) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+            wrap,
+        }
+    }
+}
+
+impl StatefulWidget for ScrollOverlay<'_> {
+    type State = OverlayScroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        // Clear the area to make it opaque
+        Clear.render(area, buf);
+
+        let style = Style::default().bg(Color::Black).fg(Color::White);
+        // Wrap up front so the number of rows to scroll through is known
+        let lines: Vec<Line> = if self.wrap {
+            let width = area.width.saturating_sub(2) as usize;
+            self.lines
+                .into_iter()
+                .flat_map(|line| wrap_at(line, width))
+                .collect()
+        } else {
+            self.lines
+        };
+        let total = lines.len();
         let paragraph = Paragraph::new(lines)
-            .wrap(Wrap { trim: false })
-            .scroll((self.scroll_offset as u16, 0));
+            .alignment(Alignment::Left)
+            .style(style);
 
-        paragraph.render(area, buf);
+        // Clamp to the content so scrolling past the end does not pile up
+        let page = area.height.saturating_sub(2) as usize;
+        state.page = page.max(1);
+        state.max = total.saturating_sub(page);
+        state.offset = state.offset.min(state.max);
+
+        let mut block = Block::default()
+            .title(self.title)
+            .borders(Borders::ALL)
+            .style(style);
+        if state.max > 0 {
+            block = block.title_bottom(
+                Line::from(format!(
+                    " {}-{}/{} ↑↓ PgUp/PgDn ",
+                    state.offset + 1,
+                    (state.offset + page).min(total),
+                    total
+                ))
+                .right_aligned(),
+            );
+        }
+
+        paragraph
+            .block(block)
+            .scroll((state.offset.min(u16::MAX as usize) as u16, 0))
+            .render(area, buf);
     }
 }
 
@@ -318,7 +1037,13 @@ pub struct StatusBar<'a> {
     active_filters: &'a [String],
     clusters: &'a [String],
     paused: bool,
-    auto_scroll: bool,
+    auto_scroll: Option<&'a str>,
+    reversed: bool,
+    focused_cluster: Option<&'a str>,
+    status_message: Option<&'a str>,
+    channel_high_water: Option<usize>,
+    flash: bool,
+    received_bytes: Option<(u64, Option<u64>)>,
 }
 
 impl<'a> StatusBar<'a> {
@@ -331,7 +1056,13 @@ impl<'a> StatusBar<'a> {
         active_filters: &'a [String],
         clusters: &'a [String],
         paused: bool,
-        auto_scroll: bool,
+        auto_scroll: Option<&'a str>,
+        reversed: bool,
+        focused_cluster: Option<&'a str>,
+        status_message: Option<&'a str>,
+        channel_high_water: Option<usize>,
+        flash: bool,
+        received_bytes: Option<(u64, Option<u64>)>,
     ) -> Self {
         Self {
             running_pods,
@@ -342,6 +1073,12 @@ impl<'a> StatusBar<'a> {
             clusters,
             paused,
             auto_scroll,
+            reversed,
+            focused_cluster,
+            status_message,
+            channel_high_water,
+            flash,
+            received_bytes,
         }
     }
 }
@@ -370,28 +1107,370 @@ impl<'a> Widget for StatusBar<'a> {
 
         let mut status_text = status_parts.join(" | ");
 
+        if let Some((bytes, wire)) = self.received_bytes {
+            status_text.push_str(&format!(" | Received: {}", format_bytes(bytes)));
+            if let Some(wire) = wire {
+                status_text.push_str(&format!(" ({} on the wire)", format_bytes(wire)));
+            }
+        }
+
+        if let Some(hw) = self.channel_high_water {
+            status_text.push_str(&format!(" | Chan HWM: {}", hw));
+        }
+
+        if let Some(cluster) = self.focused_cluster {
+            status_text.push_str(&format!(" | Focus: {}", cluster));
+        }
+
         // Add mode indicators
         if self.paused {
             status_text.push_str(" | [PAUSED]");
         }
-        if self.auto_scroll {
-            status_text.push_str(" | [AUTO]");
+        if let Some(indicator) = self.auto_scroll {
+            status_text.push_str(&format!(" | [{}]", indicator));
+        }
+        if self.reversed {
+            status_text.push_str(" | [↑newest]");
         }
 
         // Add help hint
         status_text.push_str(" | ? for help");
 
-        let paragraph = Paragraph::new(status_text)
-            .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+        // Transient messages take the front of the bar so they are never cut off
+        let line = match self.status_message {
+            Some(msg) => Line::from(vec![
+                Span::styled(
+                    format!(" {} ", msg),
+                    Style::default()
+                        .bg(Color::Yellow)
+                        .fg(Color::Black)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+                Span::raw(status_text),
+            ]),
+            None => Line::from(status_text),
+        };
+
+        let background = if self.flash {
+            Color::Red
+        } else {
+            Color::DarkGray
+        };
+        let paragraph =
+            Paragraph::new(line).style(Style::default().bg(background).fg(Color::White));
 
         paragraph.render(area, buf);
     }
 }
 
-pub struct HelpOverlay;
+pub struct PhaseHistoryOverlay<'a> {
+    key: &'a PodKey,
+    history: &'a VecDeque<(chrono::DateTime<chrono::Utc>, String)>,
+}
 
-impl Widget for HelpOverlay {
+impl<'a> PhaseHistoryOverlay<'a> {
+    pub fn new(
+        key: &'a PodKey,
+        history: &'a VecDeque<(chrono::DateTime<chrono::Utc>, String)>,
+    ) -> Self {
+        Self { key, history }
+    }
+}
+
+impl<'a> Widget for PhaseHistoryOverlay<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let now = chrono::Utc::now();
+        let mut lines: Vec<Line> = Vec::new();
+        for (i, (since, phase)) in self.history.iter().enumerate() {
+            // Each phase lasts until the next transition (or until now)
+            let (until, suffix) = match self.history.get(i + 1) {
+                Some((next, _)) => (*next, ""),
+                None => (now, " (current)"),
+            };
+            lines.push(Line::from(format!(
+                "  {:<12} {}  for {}{}",
+                phase,
+                since.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                format_duration_short(until.signed_duration_since(*since)),
+                suffix
+            )));
+        }
+
+        let width = 60.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+
+        Clear.render(overlay_area, buf);
+
+        let block = Block::default()
+            .title(format!(
+                "Phases: {}/{}",
+                self.key.namespace, self.key.pod_name
+            ))
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black).fg(Color::White));
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().bg(Color::Black).fg(Color::White))
+            .render(overlay_area, buf);
+    }
+}
+
+/// Recent watcher events and tail task transitions, for finding out why a pod
+/// was discovered but never tailed
+pub struct DebugTimelineOverlay<'a> {
+    pod: Option<&'a str>,
+    entries: &'a [TimelineEntry],
+}
+
+impl<'a> DebugTimelineOverlay<'a> {
+    pub fn new(pod: Option<&'a str>, entries: &'a [TimelineEntry]) -> Self {
+        Self { pod, entries }
+    }
+}
+
+impl StatefulWidget for DebugTimelineOverlay<'_> {
+    type State = OverlayScroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut lines: Vec<Line> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                // Within one pod's timeline the pod path is implied
+                let mut target = match self.pod {
+                    Some(pod) if entry.target == pod => String::new(),
+                    _ => entry.target.clone(),
+                };
+                if let Some(container) = &entry.container {
+                    if !target.is_empty() {
+                        target.push('/');
+                    }
+                    target.push_str(container);
+                }
+                let mut spans = vec![Span::styled(
+                    format!(
+                        "{} ",
+                        entry
+                            .at
+                            .with_timezone(&chrono::Local)
+                            .format("%H:%M:%S%.3f")
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )];
+                if !target.is_empty() {
+                    spans.push(Span::styled(
+                        format!("{} ", target),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                spans.push(Span::raw(entry.event.clone()));
+                Line::from(spans)
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("  (nothing recorded yet)"));
+        }
+
+        let overlay_area = Rect {
+            x: area.x + area.width / 10,
+            y: area.y + area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+        let title = match self.pod {
+            Some(pod) => format!("Debug timeline: {} (↑/↓ scroll, any other key closes)", pod),
+            None => "Debug timeline: all pods (↑/↓ scroll, any other key closes)".to_string(),
+        };
+        ScrollOverlay::new(title, lines, true).render(overlay_area, buf, state);
+    }
+}
+
+/// Popup ranking nodes (and zones) by the error lines in the buffer
+pub struct HotspotOverlay<'a> {
+    hotspots: &'a [Hotspot],
+}
+
+impl<'a> HotspotOverlay<'a> {
+    pub fn new(hotspots: &'a [Hotspot]) -> Self {
+        Self { hotspots }
+    }
+}
+
+impl StatefulWidget for HotspotOverlay<'_> {
+    type State = OverlayScroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let total: usize = self.hotspots.iter().map(|h| h.errors).sum();
+        let share = |errors: usize| errors * 100 / total.max(1);
+        // A node (or zone) with most of the errors, when there is more than one
+        let hot = |errors: usize, count: usize| count > 1 && errors * 2 > total;
+        let multi_cluster = self
+            .hotspots
+            .iter()
+            .any(|h| h.cluster != self.hotspots[0].cluster);
+
+        let mut lines = Vec::new();
+        for h in self.hotspots {
+            let mut name = h
+                .node
+                .clone()
+                .unwrap_or_else(|| "(unknown node)".to_string());
+            if let Some(zone) = &h.zone {
+                name.push_str(&format!(" ({})", zone));
+            }
+            if multi_cluster {
+                name.push_str(&format!(" [{}]", h.cluster));
+            }
+            let style = if hot(h.errors, self.hotspots.len()) {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{:>4}%  {}: {} errors from {} pod{}",
+                    share(h.errors),
+                    name,
+                    h.errors,
+                    h.pods,
+                    if h.pods == 1 { "" } else { "s" }
+                ),
+                style,
+            )));
+        }
+
+        // Zone totals, when any zone is known
+        let mut zones: Vec<(&str, usize)> = Vec::new();
+        for h in self.hotspots {
+            if let Some(zone) = &h.zone {
+                match zones.iter_mut().find(|(z, _)| z == zone) {
+                    Some((_, errors)) => *errors += h.errors,
+                    None => zones.push((zone, h.errors)),
+                }
+            }
+        }
+        if !zones.is_empty() {
+            zones.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "By zone:",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            let count = zones.len();
+            for (zone, errors) in zones {
+                let style = if hot(errors, count) {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("{:>4}%  {}: {} errors", share(errors), zone, errors),
+                    style,
+                )));
+            }
+        }
+        if lines.is_empty() {
+            lines.push(Line::from("  (no error lines in the buffer)"));
+        }
+
+        let overlay_area = Rect {
+            x: area.x + area.width / 10,
+            y: area.y + area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+        let title = format!(
+            "Errors by node: {} lines (↑/↓ scroll, any other key closes)",
+            total
+        );
+        ScrollOverlay::new(title, lines, false).render(overlay_area, buf, state);
+    }
+}
+
+/// Popup for bulk enabling/disabling the containers of one pod
+pub struct ContainerPickerOverlay<'a> {
+    picker: &'a ContainerPicker,
+    entries: &'a [(PodKey, bool)],
+}
+
+impl<'a> ContainerPickerOverlay<'a> {
+    pub fn new(picker: &'a ContainerPicker, entries: &'a [(PodKey, bool)]) -> Self {
+        Self { picker, entries }
+    }
+}
+
+impl<'a> Widget for ContainerPickerOverlay<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("  Filter: {}_", self.picker.filter),
+                Style::default().fg(Color::Yellow),
+            )),
+            Line::from(""),
+        ];
+        for (i, (key, enabled)) in self.entries.iter().enumerate() {
+            let checkbox = if *enabled { "[✓]" } else { "[ ]" };
+            let mut style = if *enabled {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            if i == self.picker.selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            lines.push(Line::from(Span::styled(
+                format!("  {} {}", checkbox, key.container_name),
+                style,
+            )));
+        }
+        if self.entries.is_empty() {
+            lines.push(Line::from("  (no matching containers)"));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(
+            "  Space toggle · Ctrl-A toggle all · Enter/Esc close",
+        ));
+
+        let width = 60.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+
+        Clear.render(overlay_area, buf);
+
+        let block = Block::default()
+            .title(format!(
+                "Containers: {}/{}",
+                self.picker.pod.namespace, self.picker.pod.pod_name
+            ))
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black).fg(Color::White));
+
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().bg(Color::Black).fg(Color::White))
+            .render(overlay_area, buf);
+    }
+}
+
+pub struct HelpOverlay;
+
+impl StatefulWidget for HelpOverlay {
+    type State = OverlayScroll;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let help_lines = vec![
             "Keyboard Shortcuts",
             "",
@@ -400,13 +1479,36 @@ impl Widget for HelpOverlay {
             "  p           - Pause/Resume",
             "  c           - Clear log buffer",
             "  a           - Toggle auto-scroll",
-            "  t           - Toggle timestamps",
+            "  t           - Cycle timestamps: off, full, compact, relative",
+            "  O           - Toggle sort by time (vs. arrival order)",
+            "  R           - Toggle newest line at the top (reverses navigation)",
+            "  w           - Toggle wrapping at a fixed column (vs. pane edge)",
+            "  v           - Toggle the detail pane (current line, selected pod)",
+            "  < / >       - Widen/narrow the detail pane",
             "  x           - Toggle pod/container prefix",
+            "  X           - Toggle per-pod prefix colors",
+            "  i           - Toggle image tags in sidebar",
+            "  Enter       - Show the current line in full (or its elided frames,",
+            "                or the last lines of a deleted pod in the sidebar)",
+            "  H           - Show phase timeline of the selected pod",
+            "  D           - Show watcher/stream debug timeline of the selected pod",
+            "  E           - Rank nodes and zones by the error lines in the buffer",
+            "  A           - Add a note at the live position (kept in saves)",
+            "  F4          - Save lines in view to kubectl-tail-<epoch>.log",
+            "  :           - Command: filter/grep <regex>, clear, save <path>,",
+            "                context [name], env [name], tail <n>, level [level],",
+            "                quit",
+            "  r           - Restart failed streams of the selected pod",
+            "  C           - Pick containers of the selected pod",
+            "  m / |       - Mark a line / pipe marked range to --pipe-cmd",
+            "  y           - Copy equivalent kubectl logs command(s)",
+            "  o           - Run --open-cmd for the selected pod",
             "  ?           - Toggle this help",
             "",
             "Search & Filter:",
             "  /           - Search (highlights matches, use n/N to navigate)",
             "  n/N         - Jump to next/previous search match",
+            "  &           - Toggle showing only the search matches",
             "  f           - Filter (show only matching lines)",
             "",
             "Navigation:",
@@ -415,13 +1517,20 @@ impl Widget for HelpOverlay {
             "  Home/End    - Jump to top/bottom (logs)",
             "  g/G         - Jump to top/bottom (logs, vim-style)",
             "  Space       - Toggle pod/container or expand/collapse tree node",
+            "  [ / ]       - Cycle focus through clusters",
+            "  M           - Mute/unmute the selected cluster (keeps streaming)",
+            "  F           - Freeze/unfreeze the sidebar order during pod churn",
+            "  T           - Highlight the current line's trace, then show only it",
+            "  e           - Toggle the level filter (--level, default: errors only)",
+            "  L           - Cycle the level filter: all, info, warn, error",
+            "  U           - Keep or hide lines without a level under the level filter",
             "",
-            "Press any key to close",
+            "Scroll with ↑/↓, PgUp/PgDn; any other key closes",
         ];
 
         let lines: Vec<Line> = help_lines.iter().map(|s| Line::from(*s)).collect();
 
-        // Center the help overlay
+        // Center the help overlay; it scrolls when taller than the terminal
         let help_width = 60;
         let help_height = help_lines.len() as u16 + 2;
         let x = (area.width.saturating_sub(help_width)) / 2;
@@ -434,19 +1543,6 @@ impl Widget for HelpOverlay {
             height: help_height.min(area.height),
         };
 
-        // Clear the area to make it opaque
-        Clear.render(help_area, buf);
-
-        let block = Block::default()
-            .title("Help")
-            .borders(Borders::ALL)
-            .style(Style::default().bg(Color::Black).fg(Color::White));
-
-        let paragraph = Paragraph::new(lines)
-            .block(block)
-            .alignment(Alignment::Left)
-            .style(Style::default().bg(Color::Black).fg(Color::White));
-
-        paragraph.render(help_area, buf);
+        ScrollOverlay::new("Help", lines, false).render(help_area, buf, state);
     }
 }
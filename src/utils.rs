@@ -1,4 +1,4 @@
-use crate::types::ResourceSpec;
+use crate::types::{LogLevel, LogMessage, ResourceSpec};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use kube::core::Selector as KubeSelector;
 use ratatui::style::Color;
@@ -63,13 +63,68 @@ pub fn get_crossterm_color(s: &str) -> crossterm::style::Color {
     colors[(hash % colors.len() as u32) as usize]
 }
 
+/// Extract the tag (or short digest) from a container image reference, e.g.
+/// `registry:5000/team/api:v1.2.3` -> `v1.2.3`. Untagged images report `latest`.
+pub fn image_tag(image: &str) -> &str {
+    if let Some((_, digest)) = image.split_once('@') {
+        // Pinned by digest: show a short prefix of the hash
+        let hash = digest.split_once(':').map(|(_, h)| h).unwrap_or(digest);
+        return &hash[..hash.len().min(12)];
+    }
+    let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match image[name_start..].rfind(':') {
+        Some(i) => &image[name_start + i + 1..],
+        None => "latest",
+    }
+}
+
+/// Environment tag in front of the cluster in a line prefix, as in
+/// `[prod:prod-us.default/web-1/app]`
+pub fn env_tag(env: Option<&str>) -> String {
+    env.map(|env| format!("{env}:")).unwrap_or_default()
+}
+
+/// Pad or shorten `s` to exactly `width` characters. Long values lose their
+/// middle, keeping the start (cluster) and the end (pod suffix and container),
+/// which is where prefixes of different pods usually differ.
+pub fn fit_to_width(s: &str, width: usize) -> String {
+    let len = s.chars().count();
+    if len <= width {
+        return format!("{:<width$}", s);
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail = (width - 1) / 2;
+    let head = width - 1 - tail;
+    let start: String = s.chars().take(head).collect();
+    let end: String = s.chars().skip(len - tail).collect();
+    format!("{}…{}", start, end)
+}
+
 /// Parse a resource specification in format:
 /// - context/namespace/kind/name (4 parts)
 /// - namespace/kind/name (3 parts)
 /// - kind/name (2 parts)
 /// - name (1 part - assumed to be a pod)
 pub fn parse_resource_spec(spec: &str) -> Result<ResourceSpec, String> {
-    let parts: Vec<&str> = spec.split('/').collect();
+    let mut parts: Vec<&str> = spec.split('/').collect();
+
+    // An optional `:container` suffix on the name; only the last part is split,
+    // since context names may contain colons
+    let mut container = None;
+    if let Some(last) = parts.last_mut()
+        && let Some((name, suffix)) = last.split_once(':')
+    {
+        if suffix.is_empty() {
+            return Err(format!(
+                "Invalid resource spec '{}': empty container after ':'",
+                spec
+            ));
+        }
+        *last = name;
+        container = Some(suffix.to_string());
+    }
 
     match parts.len() {
         1 => {
@@ -79,6 +134,7 @@ pub fn parse_resource_spec(spec: &str) -> Result<ResourceSpec, String> {
                 namespace: None,
                 kind: None,
                 name: parts[0].to_string(),
+                container,
             })
         }
         2 => {
@@ -88,6 +144,7 @@ pub fn parse_resource_spec(spec: &str) -> Result<ResourceSpec, String> {
                 namespace: None,
                 kind: Some(parts[0].to_string()),
                 name: parts[1].to_string(),
+                container,
             })
         }
         3 => {
@@ -97,6 +154,7 @@ pub fn parse_resource_spec(spec: &str) -> Result<ResourceSpec, String> {
                 namespace: Some(parts[0].to_string()),
                 kind: Some(parts[1].to_string()),
                 name: parts[2].to_string(),
+                container,
             })
         }
         4 => {
@@ -106,6 +164,7 @@ pub fn parse_resource_spec(spec: &str) -> Result<ResourceSpec, String> {
                 namespace: Some(parts[1].to_string()),
                 kind: Some(parts[2].to_string()),
                 name: parts[3].to_string(),
+                container,
             })
         }
         _ => Err(format!(
@@ -115,6 +174,871 @@ pub fn parse_resource_spec(spec: &str) -> Result<ResourceSpec, String> {
     }
 }
 
+/// Split a StatefulSet ordinal selection off a resource name: `db[0-2]` or
+/// `db[0,3]` yields `db` and the listed ordinals, a plain name has none
+pub fn split_ordinals(name: &str) -> Result<(&str, Option<Vec<u32>>), String> {
+    let Some((base, rest)) = name.split_once('[') else {
+        return Ok((name, None));
+    };
+    let list = rest
+        .strip_suffix(']')
+        .ok_or_else(|| format!("expected ']' at the end of '{}'", name))?;
+    let mut ordinals = Vec::new();
+    for part in list.split(',') {
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("invalid ordinal '{}' in '{}'", n, name))
+        };
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse(from)?, parse(to)?);
+                if from > to {
+                    return Err(format!("empty ordinal range '{}' in '{}'", part, name));
+                }
+                ordinals.extend(from..=to);
+            }
+            None => ordinals.push(parse(part)?),
+        }
+    }
+    ordinals.sort_unstable();
+    ordinals.dedup();
+    Ok((base, Some(ordinals)))
+}
+
+/// Edit distance between two strings (insertions, deletions, substitutions)
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Up to three candidates within a typo's reach of `name` (an edit distance of
+/// a quarter of its length, at least 1), closest first
+pub fn closest_names(name: &str, candidates: &[String]) -> Vec<String> {
+    let max_distance = (name.chars().count() / 4).max(1);
+    let mut close: Vec<(usize, &String)> = candidates
+        .iter()
+        .map(|c| (levenshtein(name, c), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .collect();
+    close.sort();
+    close.into_iter().take(3).map(|(_, c)| c.clone()).collect()
+}
+
+/// Format a duration kubectl-style in its largest whole unit (`42s`, `5m`, `3h`, `2d`)
+pub fn format_duration_short(duration: chrono::Duration) -> String {
+    let total_secs = duration.num_seconds().max(0);
+    if total_secs < 60 {
+        format!("{}s", total_secs)
+    } else if total_secs < 3600 {
+        format!("{}m", total_secs / 60)
+    } else if total_secs < 86400 {
+        format!("{}h", total_secs / 3600)
+    } else {
+        format!("{}d", total_secs / 86400)
+    }
+}
+
+/// A signed stopwatch time with milliseconds, e.g. `+00:01:23.456` or
+/// `-00:00:05.000`; hours grow past 24
+pub fn format_relative_time(elapsed: chrono::Duration) -> String {
+    let sign = if elapsed < chrono::Duration::zero() {
+        '-'
+    } else {
+        '+'
+    };
+    let millis = elapsed.num_milliseconds().unsigned_abs();
+    let secs = millis / 1000;
+    format!(
+        "{}{:02}:{:02}:{:02}.{:03}",
+        sign,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        millis % 1000
+    )
+}
+
+/// Validate a `--server` API server URL: http or https with a host, e.g.
+/// `http://127.0.0.1:8001` for `kubectl proxy`
+pub fn parse_server_url(s: &str) -> Result<String, String> {
+    let rest = s
+        .strip_prefix("https://")
+        .or_else(|| s.strip_prefix("http://"))
+        .ok_or_else(|| format!("'{}' must start with http:// or https://", s))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if host.is_empty() || host.starts_with(':') || host.contains(char::is_whitespace) {
+        return Err(format!("'{}' has no valid host", s));
+    }
+    Ok(s.trim_end_matches('/').to_string())
+}
+
+/// A byte count in binary units, e.g. `512 B` or `12.3 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Parse a Go-style duration such as `90s`, `15m`, `2h` or `1h30m`. A bare
+/// number is taken as seconds.
+pub fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(std::time::Duration::from_secs(secs));
+    }
+
+    let mut total_ms: u64 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| format!("missing unit in duration '{}'", s))?;
+        if digits == 0 {
+            return Err(format!("invalid duration '{}'", s));
+        }
+        let value: u64 = rest[..digits]
+            .parse()
+            .map_err(|_| format!("invalid duration '{}'", s))?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit_ms = match &rest[..unit_len] {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            unit => return Err(format!("unknown unit '{}' in duration '{}'", unit, s)),
+        };
+        total_ms = total_ms.saturating_add(value.saturating_mul(unit_ms));
+        rest = &rest[unit_len..];
+    }
+    Ok(std::time::Duration::from_millis(total_ms))
+}
+
+/// [`parse_duration`] for interval flags, where zero would make the ticker panic
+pub fn parse_nonzero_duration(s: &str) -> Result<std::time::Duration, String> {
+    let duration = parse_duration(s)?;
+    if duration.is_zero() {
+        return Err(format!("duration '{}' must be greater than zero", s));
+    }
+    Ok(duration)
+}
+
+/// A client-side `--annotation` filter: `key=value` (equality) or `key` (existence)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationFilter {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl AnnotationFilter {
+    pub fn matches(&self, annotations: &std::collections::BTreeMap<String, String>) -> bool {
+        match (&self.value, annotations.get(&self.key)) {
+            (Some(expected), Some(actual)) => expected == actual,
+            (None, Some(_)) => true,
+            (_, None) => false,
+        }
+    }
+}
+
+pub fn parse_annotation_filter(spec: &str) -> Result<AnnotationFilter, String> {
+    let (key, value) = match spec.split_once('=') {
+        Some((key, value)) => (key, Some(value.to_string())),
+        None => (spec, None),
+    };
+    if key.is_empty() {
+        return Err(format!("empty annotation key in '{}'", spec));
+    }
+    Ok(AnnotationFilter {
+        key: key.to_string(),
+        value,
+    })
+}
+
+/// When auto-scroll re-engages after the user scrolled up (`--auto-scroll-resume`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoScrollResume {
+    /// Once the view is scrolled back to the bottom, or on End
+    #[default]
+    Sticky,
+    /// Also after this long without scroll input
+    Timeout(std::time::Duration),
+    /// Only on End (or `a`); reaching the bottom keeps the view where it is
+    Never,
+}
+
+pub fn parse_auto_scroll_resume(spec: &str) -> Result<AutoScrollResume, String> {
+    match spec {
+        "sticky" => Ok(AutoScrollResume::Sticky),
+        "never" => Ok(AutoScrollResume::Never),
+        _ => {
+            let duration = spec.strip_prefix("timeout:").ok_or_else(|| {
+                format!(
+                    "expected sticky, timeout:<DURATION> or never, got '{}'",
+                    spec
+                )
+            })?;
+            match parse_duration(duration)? {
+                d if d.is_zero() => Err("auto-scroll resume timeout must be positive".to_string()),
+                d => Ok(AutoScrollResume::Timeout(d)),
+            }
+        }
+    }
+}
+
+/// Canonical name of a `--qos` class, accepted in any case
+pub fn parse_qos_class(class: &str) -> Result<String, String> {
+    ["Guaranteed", "Burstable", "BestEffort"]
+        .into_iter()
+        .find(|known| known.eq_ignore_ascii_case(class))
+        .map(str::to_string)
+        .ok_or_else(|| {
+            format!(
+                "unknown QoS class '{}', expected Guaranteed, Burstable or BestEffort",
+                class
+            )
+        })
+}
+
+/// Build the `--trace-field` extractor. A bare field name such as `trace_id`
+/// matches `trace_id=abc`, `trace_id: abc` and `"trace_id":"abc"`; anything
+/// else is a regex whose first capture group is the ID.
+pub fn parse_trace_field(spec: &str) -> Result<Regex, String> {
+    let is_name = !spec.is_empty()
+        && spec
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if is_name {
+        let pattern = format!(
+            r#"(?:^|[^\w.-])"?{}"?\s*[=:]\s*"?([\w-]+)"#,
+            regex::escape(spec)
+        );
+        return Regex::new(&pattern).map_err(|e| e.to_string());
+    }
+    let regex = Regex::new(spec).map_err(|e| format!("invalid trace regex: {}", e))?;
+    if regex.captures_len() < 2 {
+        return Err("trace regex needs a capture group for the ID".to_string());
+    }
+    Ok(regex)
+}
+
+/// Where a custom resource keeps its pod selector (`--crd-selector-path`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrdSelector {
+    /// API group, empty for the core group
+    pub group: String,
+    pub version: String,
+    pub kind: String,
+    /// Fields leading to the selector, e.g. `["spec", "selector"]`
+    pub path: Vec<String>,
+}
+
+impl CrdSelector {
+    /// Whether a resource spec's kind (e.g. `rollout` in `rollout/my-app`)
+    /// names this resource: its kind or its kind qualified by group
+    /// (`rollout.argoproj.io`), in any case
+    pub fn matches_kind(&self, kind: &str) -> bool {
+        let kind = kind.to_lowercase();
+        let own = self.kind.to_lowercase();
+        kind == own || kind == format!("{}.{}", own, self.group.to_lowercase())
+    }
+}
+
+/// Parse `<group/version/kind>=<path>`, e.g.
+/// `argoproj.io/v1alpha1/Rollout=.spec.selector`. The path is a JSONPath-like
+/// chain of fields; a leading `.` and surrounding `{}` are optional.
+pub fn parse_crd_selector(spec: &str) -> Result<CrdSelector, String> {
+    let (gvk, path) = spec
+        .split_once('=')
+        .ok_or_else(|| "expected <group/version/kind>=<path>".to_string())?;
+    let (group, version, kind) = match gvk.split('/').collect::<Vec<_>>()[..] {
+        [group, version, kind] => (group, version, kind),
+        [version, kind] => ("", version, kind),
+        _ => return Err(format!("invalid group/version/kind '{}'", gvk)),
+    };
+    if version.is_empty() || kind.is_empty() {
+        return Err(format!("invalid group/version/kind '{}'", gvk));
+    }
+    let path = path.trim();
+    let path = path
+        .strip_prefix('{')
+        .and_then(|p| p.strip_suffix('}'))
+        .unwrap_or(path);
+    let path: Vec<String> = path
+        .strip_prefix('.')
+        .unwrap_or(path)
+        .split('.')
+        .map(str::to_string)
+        .collect();
+    if path.iter().any(String::is_empty) {
+        return Err(format!("invalid selector path in '{}'", spec));
+    }
+    Ok(CrdSelector {
+        group: group.to_string(),
+        version: version.to_string(),
+        kind: kind.to_string(),
+        path,
+    })
+}
+
+/// Read a pod selector out of a custom resource. The path may lead to a label
+/// selector (`matchLabels`/`matchExpressions`) or to a plain map of labels.
+/// Returns `None` if the resource has nothing at the path.
+pub fn selector_at_path(
+    object: &serde_json::Value,
+    path: &[String],
+) -> Result<Option<LabelSelector>, String> {
+    let Some(value) = path
+        .iter()
+        .try_fold(object, |value, field| value.get(field.as_str()))
+    else {
+        return Ok(None);
+    };
+    if value.get("matchLabels").is_some() || value.get("matchExpressions").is_some() {
+        return serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("invalid label selector at .{}: {}", path.join("."), e));
+    }
+    serde_json::from_value(value.clone())
+        .map(|labels| {
+            Some(LabelSelector {
+                match_labels: Some(labels),
+                ..Default::default()
+            })
+        })
+        .map_err(|_| format!("no label selector or map of labels at .{}", path.join(".")))
+}
+
+/// Compile the `--elide-frames` pattern for stack frames to fold away
+pub fn parse_frame_pattern(spec: &str) -> Result<Regex, String> {
+    Regex::new(spec).map_err(|e| format!("invalid frame regex: {}", e))
+}
+
+/// Placeholder shown in place of a run of elided stack frames
+pub fn elided_frames_line(count: usize) -> String {
+    format!("... {} frames elided ...", count)
+}
+
+/// Trace ID of a line per the `--trace-field` extractor
+pub fn extract_trace_id(regex: &Regex, line: &str) -> Option<String> {
+    let id = regex.captures(line)?.get(1)?.as_str();
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// A `--template` line format: literal text with `{{.field}}` placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineTemplate {
+    segments: Vec<TemplateSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplateSegment {
+    Literal(String),
+    Field(TemplateField),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TemplateField {
+    Timestamp,
+    Cluster,
+    Namespace,
+    Pod,
+    Container,
+    Message,
+}
+
+/// Parse a `--template` such as `{{.timestamp}} {{.pod}} {{.message}}`. The
+/// fields are timestamp, cluster, namespace, pod, container and message;
+/// anything else is an error.
+pub fn parse_template(spec: &str) -> Result<LineTemplate, String> {
+    let mut segments = Vec::new();
+    let mut rest = spec;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(TemplateSegment::Literal(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("unterminated '{{{{' in template '{}'", spec))?;
+        let field = match after[..end].trim() {
+            ".timestamp" => TemplateField::Timestamp,
+            ".cluster" => TemplateField::Cluster,
+            ".namespace" => TemplateField::Namespace,
+            ".pod" => TemplateField::Pod,
+            ".container" => TemplateField::Container,
+            ".message" => TemplateField::Message,
+            other => {
+                return Err(format!(
+                    "unknown template field '{}' (expected .timestamp, .cluster, .namespace, .pod, .container or .message)",
+                    other
+                ));
+            }
+        };
+        segments.push(TemplateSegment::Field(field));
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(TemplateSegment::Literal(rest.to_string()));
+    }
+    Ok(LineTemplate { segments })
+}
+
+impl LineTemplate {
+    /// Render one line. Field values are inserted verbatim and never
+    /// re-interpreted as template syntax.
+    pub fn render(&self, msg: &LogMessage) -> String {
+        render_segments(&self.segments, msg)
+    }
+}
+
+fn render_segments(segments: &[TemplateSegment], msg: &LogMessage) -> String {
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            TemplateSegment::Literal(text) => out.push_str(text),
+            TemplateSegment::Field(field) => match field {
+                TemplateField::Timestamp => out.push_str(
+                    &msg.timestamp
+                        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                ),
+                TemplateField::Cluster => out.push_str(&msg.cluster),
+                TemplateField::Namespace => out.push_str(&msg.namespace),
+                TemplateField::Pod => out.push_str(&msg.pod_name),
+                TemplateField::Container => out.push_str(&msg.container_name),
+                TemplateField::Message => out.push_str(&msg.line),
+            },
+        }
+    }
+    out
+}
+
+/// A `--prefix-format`: literal text with `{field}` placeholders, standing in
+/// for the `[cluster.namespace/pod/container]` prefix
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixFormat {
+    segments: Vec<TemplateSegment>,
+}
+
+/// Parse a `--prefix-format` such as `{namespace}/{pod}>`. The fields are
+/// cluster, namespace, pod, container and timestamp; anything else is an error.
+pub fn parse_prefix_format(spec: &str) -> Result<PrefixFormat, String> {
+    let mut segments = Vec::new();
+    let mut rest = spec;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(TemplateSegment::Literal(rest[..start].to_string()));
+        }
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated '{{' in prefix format '{}'", spec))?;
+        let field = match after[..end].trim() {
+            "timestamp" => TemplateField::Timestamp,
+            "cluster" => TemplateField::Cluster,
+            "namespace" => TemplateField::Namespace,
+            "pod" => TemplateField::Pod,
+            "container" => TemplateField::Container,
+            other => {
+                return Err(format!(
+                    "unknown prefix placeholder '{{{}}}' (expected {{cluster}}, {{namespace}}, {{pod}}, {{container}} or {{timestamp}})",
+                    other
+                ));
+            }
+        };
+        segments.push(TemplateSegment::Field(field));
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(TemplateSegment::Literal(rest.to_string()));
+    }
+    Ok(PrefixFormat { segments })
+}
+
+impl PrefixFormat {
+    /// Render the prefix of one line
+    pub fn render(&self, msg: &LogMessage) -> String {
+        render_segments(&self.segments, msg)
+    }
+}
+
+/// Shell-style glob match supporting `*` (any run) and `?` (one character)
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen and the text position it was tried at, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether `--exclude-container` leaves out the container `name`
+pub fn is_excluded_container(name: &str, exclude: &[String]) -> bool {
+    exclude.iter().any(|pattern| glob_match(pattern, name))
+}
+
+/// Expand `--context` glob patterns against the kubeconfig's context names,
+/// keeping the order of first match and dropping duplicates. Plain names pass
+/// through untouched; a pattern matching nothing is an error
+pub fn expand_context_globs(patterns: &[String], names: &[String]) -> Result<Vec<String>, String> {
+    let mut out: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let matches: Vec<&String> = if pattern.contains(['*', '?']) {
+            names
+                .iter()
+                .filter(|name| glob_match(pattern, name))
+                .collect()
+        } else {
+            vec![pattern]
+        };
+        if matches.is_empty() {
+            return Err(format!(
+                "--context pattern '{pattern}' matches no context in the kubeconfig (available: {})",
+                names.join(", ")
+            ));
+        }
+        for name in matches {
+            if !out.contains(name) {
+                out.push(name.clone());
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// A container regex from `--container`, when the value is not a plain name.
+/// Container names are DNS labels (lowercase letters, digits and `-`), so any
+/// other character makes it a regex, matched against the whole name (e.g.
+/// `app|migrations`). With a leading `~` it is searched for anywhere in the
+/// name instead (e.g. `~api$` for `api` but not `api-migrations`).
+pub fn container_regex(container: &str) -> Option<Result<Regex, regex::Error>> {
+    if let Some(pattern) = container.strip_prefix('~') {
+        return Some(Regex::new(pattern));
+    }
+    let plain = container
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    (!plain).then(|| Regex::new(&format!("^(?:{})$", container)))
+}
+
+/// Case-insensitive fuzzy match: every character of `pattern` appears in
+/// `text`, in order (e.g. `evy` matches `envoy`)
+pub fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| chars.any(|c| c == p))
+}
+
+/// Shortest time between two `--notify` alerts
+pub const NOTIFY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Rate-limited alerts for lines matching `--notify`
+#[derive(Debug)]
+pub struct Notifier {
+    regex: Regex,
+    last: Option<std::time::Instant>,
+}
+
+impl Notifier {
+    pub fn new(regex: Regex) -> Self {
+        Self { regex, last: None }
+    }
+
+    /// Whether this line should raise an alert: it matches, and no alert was
+    /// raised in the last NOTIFY_INTERVAL, so a flood of matches doesn't beep
+    /// continuously
+    pub fn check(&mut self, line: &str, now: std::time::Instant) -> bool {
+        if !self.regex.is_match(line)
+            || self
+                .last
+                .is_some_and(|last| now.duration_since(last) < NOTIFY_INTERVAL)
+        {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
+
+/// Quote a word for a POSIX shell, leaving plain words (names, contexts such
+/// as `arn:aws:eks:...`) unquoted
+pub fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-:/@=,+".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Fill an --open-cmd template: `{context}`, `{namespace}`, `{pod}` and
+/// `{container}` become the shell-quoted identifiers
+pub fn fill_open_cmd(
+    template: &str,
+    context: &str,
+    namespace: &str,
+    pod: &str,
+    container: &str,
+) -> String {
+    template
+        .replace("{context}", &shell_quote(context))
+        .replace("{namespace}", &shell_quote(namespace))
+        .replace("{pod}", &shell_quote(pod))
+        .replace("{container}", &shell_quote(container))
+}
+
+/// A `--redact` substitution: every match of `pattern` is replaced with `replacement`
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    pub pattern: Regex,
+    pub replacement: String,
+}
+
+/// Parse a `<regex>=<replacement>` redaction rule. The split happens at the last
+/// `=`, so the regex may contain `=` but the replacement may not. The replacement
+/// may reference capture groups (`$1`, `${name}`).
+pub fn parse_redaction(spec: &str) -> Result<Redaction, String> {
+    let (pattern, replacement) = spec
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected <regex>=<replacement>, got '{}'", spec))?;
+    if pattern.is_empty() {
+        return Err(format!("empty regex in redaction '{}'", spec));
+    }
+    let pattern = Regex::new(pattern).map_err(|e| format!("invalid regex '{}': {}", pattern, e))?;
+    Ok(Redaction {
+        pattern,
+        replacement: replacement.to_string(),
+    })
+}
+
+/// Apply all redactions to a line, in order
+pub fn apply_redactions(line: &str, redactions: &[Redaction]) -> String {
+    let mut line = line.to_string();
+    for r in redactions {
+        if let std::borrow::Cow::Owned(redacted) =
+            r.pattern.replace_all(&line, r.replacement.as_str())
+        {
+            line = redacted;
+        }
+    }
+    line
+}
+
+/// Keys a JSON log keeps its level, timestamp and message under
+const JSON_LEVEL_KEYS: &[&str] = &["level", "severity", "lvl"];
+const JSON_TIME_KEYS: &[&str] = &["ts", "time", "timestamp", "@timestamp"];
+const JSON_MESSAGE_KEYS: &[&str] = &["msg", "message"];
+
+/// A JSON log line split into the fields `--parse json` shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonLine {
+    pub time: Option<String>,
+    /// The level as written, e.g. `warning`
+    pub level: Option<String>,
+    pub message: String,
+    /// Every other field as `key=value`, sorted by key
+    pub fields: Vec<String>,
+}
+
+impl JsonLine {
+    /// The level as a severity, for coloring
+    pub fn severity(&self) -> Option<LogLevel> {
+        self.level.as_deref().and_then(LogLevel::from_name)
+    }
+}
+
+/// `--parse json`, as the TUI applies it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonLogFormat {
+    /// --json-message-key
+    pub message_key: Option<String>,
+}
+
+/// Parse a line for `--parse json`: a JSON object, with its message under
+/// `message_key` (default `msg` or `message`). Anything else is None and shown
+/// as is.
+pub fn parse_json_line(line: &str, message_key: Option<&str>) -> Option<JsonLine> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    let serde_json::Value::Object(object) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let text = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let message_key = match message_key {
+        Some(key) => Some(key),
+        None => find_key(&object, JSON_MESSAGE_KEYS),
+    };
+    let level_key = find_key(&object, JSON_LEVEL_KEYS);
+    let time_key = find_key(&object, JSON_TIME_KEYS);
+    let special = [message_key, level_key, time_key];
+    Some(JsonLine {
+        time: time_key.map(|k| text(&object[k])),
+        level: level_key.map(|k| text(&object[k])),
+        message: message_key
+            .and_then(|k| object.get(k))
+            .map(text)
+            .unwrap_or_default(),
+        fields: object
+            .iter()
+            .filter(|(k, _)| !special.contains(&Some(k.as_str())))
+            .map(|(k, v)| format!("{}={}", k, text(v)))
+            .collect(),
+    })
+}
+
+/// The first of `keys` present in `object`
+fn find_key<'a>(
+    object: &serde_json::Map<String, serde_json::Value>,
+    keys: &[&'a str],
+) -> Option<&'a str> {
+    keys.iter().find(|k| object.contains_key(**k)).copied()
+}
+
+/// Parse a `--level` threshold
+pub fn parse_level(s: &str) -> Result<LogLevel, String> {
+    LogLevel::from_name(s).ok_or_else(|| {
+        format!(
+            "unknown level '{}' (expected trace, debug, info, warn, error or fatal)",
+            s
+        )
+    })
+}
+
+/// Level of a log line, recognized from a `level`/`severity` field (JSON or
+/// logfmt, any case), a klog header (`E0102 15:04:05.000000 ...`), an
+/// upper-case word such as `ERROR` or `[WARN]`, a Go or Rust panic, or an
+/// HTTP 5xx status in an access log
+pub fn detect_level(line: &str) -> Option<LogLevel> {
+    static KEYED: OnceLock<Regex> = OnceLock::new();
+    static KLOG: OnceLock<Regex> = OnceLock::new();
+    static BARE: OnceLock<Regex> = OnceLock::new();
+    static PANIC: OnceLock<Regex> = OnceLock::new();
+    static HTTP_5XX: OnceLock<Regex> = OnceLock::new();
+
+    let keyed = KEYED.get_or_init(|| {
+        Regex::new(r#"(?i)"?\b(?:level|severity|lvl)"?\s*[=:]\s*"?([a-z]+)"#).unwrap()
+    });
+    if let Some(level) = keyed
+        .captures(line)
+        .and_then(|c| LogLevel::from_name(&c[1]))
+    {
+        return Some(level);
+    }
+
+    let klog = KLOG.get_or_init(|| Regex::new(r"^([IWEF])\d{4} ").unwrap());
+    if let Some(c) = klog.captures(line) {
+        return match &c[1] {
+            "I" => Some(LogLevel::Info),
+            "W" => Some(LogLevel::Warn),
+            "E" => Some(LogLevel::Error),
+            _ => Some(LogLevel::Fatal),
+        };
+    }
+
+    let bare = BARE.get_or_init(|| {
+        Regex::new(
+            r"\b(TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|ERR|FATAL|CRITICAL|CRIT|PANIC)\b",
+        )
+        .unwrap()
+    });
+    if let Some(level) = bare.captures(line).and_then(|c| LogLevel::from_name(&c[1])) {
+        return Some(level);
+    }
+
+    // `panic: ...` (Go) or `thread 'main' panicked at ...` (Rust)
+    let panic = PANIC.get_or_init(|| Regex::new(r"^panic: |\bpanicked at\b").unwrap());
+    if panic.is_match(line) {
+        return Some(LogLevel::Fatal);
+    }
+
+    // `"GET / HTTP/1.1" 503 ...` (combined log format) or `status=503`
+    let http = HTTP_5XX.get_or_init(|| {
+        Regex::new(r#"(?:HTTP/[\d.]+"\s+|\bstatus(?:_code)?"?\s*[=:]\s*"?)5\d\d\b"#).unwrap()
+    });
+    http.is_match(line).then_some(LogLevel::Error)
+}
+
+/// Cut a line down to at most `max` bytes (at a character boundary) followed
+/// by a `… [N bytes truncated]` marker, counting the `discarded` bytes already
+/// dropped while the line was read. Returns whether the line was cut.
+pub fn truncate_line_bytes(line: &mut String, max: usize, discarded: usize) -> bool {
+    if line.len() <= max && discarded == 0 {
+        return false;
+    }
+    let mut end = max.min(line.len());
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    let dropped = line.len() - end + discarded;
+    line.truncate(end);
+    line.push_str(&format!("… [{} bytes truncated]", dropped));
+    true
+}
+
+/// Split the RFC 3339 timestamp the API server prepends to each line when
+/// logs are requested with `timestamps=true`. Lines without one (or with one
+/// that doesn't parse) come back whole, with no timestamp
+pub fn split_timestamp(line: &str) -> (Option<chrono::DateTime<chrono::Utc>>, &str) {
+    if let Some((prefix, rest)) = line.split_once(' ')
+        && let Ok(ts) = chrono::DateTime::parse_from_rfc3339(prefix)
+    {
+        return (Some(ts.with_timezone(&chrono::Utc)), rest);
+    }
+    (None, line)
+}
+
 /// Strip ANSI escape codes from a string
 /// Uses a cached regex for performance
 pub fn strip_ansi_codes(s: &str) -> String {